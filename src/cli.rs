@@ -1,10 +1,16 @@
-use crate::commands;
-use clap::{Parser, Subcommand};
+use crate::commands::{self, Global, Runnable};
+use crate::config::Config;
+use anyhow::Result;
+use clap::{CommandFactory, FromArgMatches, Parser, Subcommand};
+use serde::Serialize;
+use std::ffi::OsString;
+use std::path::PathBuf;
 
-#[derive(Parser)]
+#[derive(Parser, Serialize)]
 #[command(
     name = "gitrivia",
     version,
+    args_override_self = true,
     about = "✨ Explore who did what, when — in any Git repo",
     arg_required_else_help = true
 )]
@@ -16,6 +22,17 @@ pub struct Cli {
     #[arg(long, global = true)]
     pub json: bool,
 
+    /// Output format: `table` (default), `json`, flat `csv` / `tsv` rows
+    /// with a header for spreadsheets, `markdown` for issues and wikis, or
+    /// `ndjson`: one JSON object per csv row and line.
+    #[arg(
+        long,
+        global = true,
+        value_name = "FORMAT",
+        value_parser = ["table", "json", "csv", "tsv", "markdown", "ndjson"]
+    )]
+    pub format: Option<String>,
+
     /// Sort results in descending order by default.
     ///
     /// Some commands allow per‑call sorting options; this flag acts as a
@@ -23,7 +40,154 @@ pub struct Cli {
     #[arg(long, global = true)]
     pub desc: bool,
 
+    /// Extra mailmap file merged on top of the repository's `.mailmap`.
+    ///
+    /// Authors are always resolved through the mailmap so one person with
+    /// several names or addresses is counted once, in every command.
+    #[arg(long, global = true, value_name = "FILE")]
+    pub mailmap: Option<PathBuf>,
+
+    /// Revision or range to analyse instead of HEAD (repeatable).
+    ///
+    /// Accepts anything `git rev-parse` does: `release/2.4`, `v1.0..v2.0`,
+    /// `main...feature`, `^old-base`. Blame-based commands look at the
+    /// first positive revision.
+    #[arg(long = "rev", global = true, value_name = "REV")]
+    pub revs: Vec<String>,
+
+    /// Analyse commits reachable from any ref (like `git log --all`).
+    #[arg(long, global = true)]
+    pub all_refs: bool,
+
+    /// Analyse local branches, optionally only those matching a glob
+    /// (`--branches=release/*`).
+    #[arg(
+        long,
+        global = true,
+        value_name = "GLOB",
+        num_args = 0..=1,
+        require_equals = true,
+        default_missing_value = "*"
+    )]
+    pub branches: Option<String>,
+
+    /// Skip refs matching this glob when expanding `--all-refs` or
+    /// `--branches` (repeatable).
+    #[arg(long, global = true, value_name = "GLOB")]
+    pub exclude: Vec<String>,
+
+    /// Only count commits on or after this time.
+    ///
+    /// Accepts `2025-01-01`, `2025-01-01 09:30`, RFC 3339, `yesterday`, or
+    /// relative expressions such as `6 months ago` / `2w ago`.
+    #[arg(short, long, global = true, value_name = "WHEN")]
+    pub since: Option<String>,
+
+    /// Only count commits on or before this time (same formats as
+    /// `--since`; a bare date includes that whole day).
+    ///
+    /// Blame-based commands look at the newest selected commit before it.
+    #[arg(long, global = true, value_name = "WHEN")]
+    pub until: Option<String>,
+
+    /// Shorthand for a window ending at `--until` (or now): `90d`, `2w`,
+    /// `6 months`, `1y`.
+    #[arg(long, global = true, value_name = "SPAN", conflicts_with = "since")]
+    pub last: Option<String>,
+
+    /// Only consider paths matching these patterns (repeatable or
+    /// comma-separated).
+    ///
+    /// Git pathspecs and globs: `src`, `src/**/*.rs`, `*.lock` (any depth).
+    /// Prefix with `!` (or `:!`) to exclude: `!**/vendor/**`, `!*.lock`.
+    /// Commit-level commands then only count commits touching a match.
+    #[arg(long, global = true, value_name = "PATTERN", value_delimiter = ',')]
+    pub pathspec: Vec<String>,
+
+    /// Skip files marked `linguist-generated` or `linguist-vendored` in
+    /// `.gitattributes`.
+    #[arg(long, global = true)]
+    pub skip_generated: bool,
+
+    /// Don't read or write the commit facts cache in `.git/gitrivia/`.
+    #[arg(long, global = true)]
+    pub no_cache: bool,
+
+    /// Credit for people named in `Co-authored-by:` trailers: `full` (a
+    /// whole commit each), `split` (shared evenly with the author) or
+    /// `ignore` (author only).
+    #[arg(
+        long,
+        global = true,
+        value_name = "MODE",
+        default_value = "full",
+        value_parser = ["full", "split", "ignore"]
+    )]
+    pub coauthor_credit: String,
+
+    /// Bot and automation accounts (dependabot, renovate, `*[bot]`, …):
+    /// `include` them, `exclude` them from every analysis, or report them
+    /// `separate`ly.
+    #[arg(
+        long,
+        global = true,
+        value_name = "MODE",
+        default_value = "include",
+        value_parser = ["include", "exclude", "separate"]
+    )]
+    pub bots: String,
+
+    /// Extra bot pattern, a regex matched against `Name <email>`
+    /// (repeatable).
+    #[arg(long = "bot-pattern", global = true, value_name = "REGEX")]
+    pub bot_patterns: Vec<String>,
+
+    /// Revision whose lines blame gives to their previous author, such as
+    /// a mass reformat or rename (repeatable).
+    ///
+    /// The repository's own list is read too (unless `--no-ignore-revs`):
+    /// the file named by `blame.ignoreRevsFile`, else
+    /// `.git-blame-ignore-revs`.
+    #[arg(long = "ignore-rev", global = true, value_name = "REV")]
+    pub ignore_revs: Vec<String>,
+
+    /// Read more revisions to ignore from this file, one full hash per
+    /// line, `#` starting a comment (repeatable).
+    #[arg(long = "ignore-revs-file", global = true, value_name = "FILE")]
+    pub ignore_revs_files: Vec<PathBuf>,
+
+    /// Don't read the repository's `.git-blame-ignore-revs` (or
+    /// `blame.ignoreRevsFile`).
+    #[arg(long, global = true)]
+    pub no_ignore_revs: bool,
+
+    /// Blame ignores whitespace-only changes (like `git blame -w`).
+    #[arg(long, global = true)]
+    pub ignore_whitespace: bool,
+
+    /// Give moved or copied lines to their original author: `off`, `file`
+    /// (within a file, like `git blame -M`) or `commit` (also from other
+    /// files changed in the same commit, like `-C`).
+    #[arg(
+        long,
+        global = true,
+        value_name = "MODE",
+        default_value = "off",
+        value_parser = ["off", "file", "commit"]
+    )]
+    pub detect_moves: String,
+
+    /// Replace the built-in file extension allow-list used by `bus-factor`
+    /// and `churn` (comma-separated; `--include-ext` still adds to it).
+    #[arg(long, global = true, value_name = "EXT", value_delimiter = ',')]
+    pub extensions: Vec<String>,
+
+    /// Ignore `.gitrivia.toml` and the user config file.
+    #[arg(long, global = true)]
+    pub no_config: bool,
+
     #[command(subcommand)]
+    #[serde(skip)]
     pub command: CliCommand,
 }
 
@@ -82,9 +246,163 @@ pub enum CliCommand {
     /// signalling knowledge silos.
     BusFactor(commands::BusFactor),
 
+    /// 🧭 Suggest or check a CODEOWNERS file
+    ///
+    /// Top recent line owners per directory or pattern, as a GitHub/GitLab
+    /// CODEOWNERS file or a diff against the existing one.
+    Codeowners(commands::Codeowners),
+
+    /// 👀 Suggest reviewers for a change
+    ///
+    /// Ranks owners of the changed lines and recent editors of the changed
+    /// files for a revision range, a list of paths or a patch.
+    SuggestReviewers(commands::SuggestReviewers),
+
     /// ♨️ Churn (recent file/directory volatility)
     ///
     /// Ranks paths by recent change activity to highlight unstable or
     /// frequently modified areas.
     Churn(commands::Churn),
+
+    /// ⏳ Age of the code at the tip
+    ///
+    /// Line ages per directory or file from blame, and how many of the
+    /// lines each author or team wrote per quarter survive.
+    Age(commands::Age),
+
+    /// 📈 Metrics per week, month, quarter or year
+    ///
+    /// Tracks concentration, merge/revert rates, message hygiene and
+    /// active authors over time.
+    Trend(commands::Trend),
+
+    /// ⚖️ Compare two periods or revision ranges
+    ///
+    /// Metric deltas, new and departed contributors, and movement in the
+    /// author and churn rankings.
+    Compare(commands::Compare),
+
+    /// 📄 Offline HTML dashboard
+    ///
+    /// Writes one self-contained HTML file with inline SVG charts: summary,
+    /// commits over time, weekdays, ownership treemap, churn hotspots.
+    Report(commands::Report),
+
+    /// 📡 Prometheus metrics for dashboards
+    ///
+    /// Summary metrics, bus-factor counts and top churn as gauges with
+    /// repository and branch labels, for scraping or the textfile collector.
+    ExportMetrics(commands::ExportMetrics),
+
+    /// 🗄️ Inspect, rebuild or prune the analysis cache
+    ///
+    /// Per-commit facts are cached on disk so repeated runs only process
+    /// new commits.
+    Cache(commands::Cache),
+
+    /// ⚙️ Show the effective configuration
+    ///
+    /// Merges the user config and `.gitrivia.toml`; every key maps to a
+    /// command-line flag of the matching subcommand.
+    Config(commands::Config),
+
+    /// 🌐 Local HTTP server with a JSON API
+    ///
+    /// `GET /stats`, `/bus-factor`, `/churn`, `/blame-summary?file=…` and
+    /// the other analyses return their `--json` output, cached per HEAD.
+    Serve(commands::Serve),
+
+    /// 🖥️ Interactive terminal explorer
+    ///
+    /// Panes for the summary, authors and a file tree with ownership and
+    /// churn; drill down from directories to files to a file's blame.
+    Tui(commands::Tui),
+
+    /// 📐 JSON Schema of every command's `--json` output
+    ///
+    /// All JSON output is versioned (`schema_version`) and carries the
+    /// command, repository, HEAD, timestamp and effective options.
+    Schema(commands::Schema),
+}
+
+impl Cli {
+    /// Parse `args` with config settings as the defaults of their flags.
+    pub fn parse_with(config: &Config, args: &[OsString]) -> Result<Self, clap::Error> {
+        let mut cmd = config.apply(Self::command());
+        let matches = cmd.try_get_matches_from_mut(args)?;
+        Self::from_arg_matches(&matches).map_err(|e| e.format(&mut cmd))
+    }
+}
+
+impl CliCommand {
+    pub fn run(self, g: &Global) -> Result<()> {
+        match self {
+            CliCommand::Stats(c) => c.run(g),
+            CliCommand::TopAuthors(c) => c.run(g),
+            CliCommand::AuthorActivity(c) => c.run(g),
+            CliCommand::BlameSummary(c) => c.run(g),
+            CliCommand::FileContributions(c) => c.run(g),
+            CliCommand::CommitTimes(c) => c.run(g),
+            CliCommand::FirstCommits(c) => c.run(g),
+            CliCommand::TopCoauthors(c) => c.run(g),
+            CliCommand::BusFactor(c) => c.run(g),
+            CliCommand::Codeowners(c) => c.run(g),
+            CliCommand::SuggestReviewers(c) => c.run(g),
+            CliCommand::Churn(c) => c.run(g),
+            CliCommand::Age(c) => c.run(g),
+            CliCommand::Trend(c) => c.run(g),
+            CliCommand::Compare(c) => c.run(g),
+            CliCommand::Report(c) => c.run(g),
+            CliCommand::ExportMetrics(c) => c.run(g),
+            CliCommand::Cache(c) => c.run(g),
+            CliCommand::Config(c) => c.run(g),
+            CliCommand::Serve(c) => c.run(g),
+            CliCommand::Tui(c) => c.run(g),
+            CliCommand::Schema(c) => c.run(g),
+        }
+    }
+
+    /// Repository path the command was pointed at (`-p/--path`).
+    pub fn path(&self) -> &str {
+        match self {
+            CliCommand::Stats(c) => &c.path,
+            CliCommand::TopAuthors(c) => &c.path,
+            CliCommand::AuthorActivity(c) => &c.path,
+            CliCommand::BlameSummary(c) => &c.path,
+            CliCommand::FileContributions(c) => &c.path,
+            CliCommand::CommitTimes(c) => &c.path,
+            CliCommand::FirstCommits(c) => &c.path,
+            CliCommand::TopCoauthors(c) => &c.path,
+            CliCommand::BusFactor(c) => &c.path,
+            CliCommand::Codeowners(c) => &c.path,
+            CliCommand::SuggestReviewers(c) => &c.path,
+            CliCommand::Churn(c) => &c.path,
+            CliCommand::Age(c) => &c.path,
+            CliCommand::Trend(c) => &c.path,
+            CliCommand::Compare(c) => &c.path,
+            CliCommand::Report(c) => &c.path,
+            CliCommand::ExportMetrics(c) => &c.path,
+            CliCommand::Cache(c) => &c.path,
+            CliCommand::Config(c) => &c.path,
+            CliCommand::Serve(c) => &c.path,
+            CliCommand::Tui(c) => &c.path,
+            CliCommand::Schema(_) => ".",
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn cli_is_consistent() {
+        Cli::command().debug_assert();
+    }
+
+    #[test]
+    fn short_since_still_works_after_the_subcommand() {
+        let cli = Cli::try_parse_from(["gitrivia", "top-authors", "-s", "2024-01-01"]).unwrap();
+        assert_eq!(cli.since.as_deref(), Some("2024-01-01"));
+    }
 }
@@ -0,0 +1,338 @@
+use anyhow::{bail, Result};
+use chrono::{DateTime, Local, NaiveDate};
+use clap::Args;
+use comfy_table::{presets::UTF8_HORIZONTAL_ONLY, Table};
+use serde::Serialize;
+use std::collections::{BTreeMap, HashMap};
+
+use crate::commands::Global;
+use crate::domain::{
+    age::{self, AgeOpts, Cohort, PathAge, Survival, Teams},
+    bus_factor::ScanOpts,
+    git::RepoExt,
+    trend::Period,
+    window::TimeWindow,
+};
+use crate::presentation::{flat::Rows, json, schema::schema};
+use crate::utils::fmt_date;
+
+/// Report how old the code at the tip is.
+///
+/// Blames every selected file and reports, per directory or file, the
+/// median and 90th-percentile age of its lines and the share older than
+/// `--old-years`. A survival curve shows what fraction of the lines
+/// written in each quarter (or `--period`) the tip still has, per author,
+/// per team or overall.
+#[derive(Debug, Args, Serialize)]
+pub struct Age {
+    /// Path to the Git repository.
+    #[arg(short, long, default_value = ".")]
+    pub path: String,
+
+    /// Report ages per directory or per file.
+    #[arg(long, value_parser = ["file", "dir"], default_value = "dir")]
+    pub by: String,
+
+    /// Directory depth when `--by dir` is used.
+    #[arg(long, default_value = "1")]
+    pub depth: usize,
+
+    /// Lines older than this many years count as old.
+    #[arg(long, default_value = "2")]
+    pub old_years: f64,
+
+    /// Survival bucket size.
+    #[arg(long, value_parser = ["week", "month", "quarter", "year"], default_value = "quarter")]
+    pub period: String,
+
+    /// Survival curves per `author`, per `team` (see `--team`) or for
+    /// `all` lines together.
+    #[arg(long, value_parser = ["all", "author", "team"], default_value = "author")]
+    pub survival_by: String,
+
+    /// A team and its members' emails, or `@domain` for a whole domain
+    /// (repeatable), e.g. `infra=ann@example.com,bob@example.com`.
+    #[arg(long, value_name = "TEAM=EMAIL,…")]
+    pub team: Vec<String>,
+
+    /// Authors or teams shown as survival columns, largest first (the
+    /// table also has an `All` column).
+    #[arg(long, default_value = "5")]
+    pub cohorts: usize,
+
+    /// Ignore paths with fewer blamed lines than this.
+    #[arg(long, default_value = "1")]
+    pub min_total: usize,
+
+    /// Maximum number of paths to display in human‑readable output.
+    #[arg(long, default_value = "20")]
+    pub limit: usize,
+
+    /// Include all files, even those normally filtered out.
+    #[arg(long)]
+    pub all: bool,
+
+    /// Additional file extensions to include (comma‑separated).
+    #[arg(long, value_delimiter = ',')]
+    pub include_ext: Vec<String>,
+
+    /// Number of threads for blame analysis (0 = auto).
+    #[arg(long, default_value = "0")]
+    pub threads: usize,
+
+    /// Emit JSON even when the global flag is not set.
+    #[arg(long)]
+    pub json: bool,
+}
+
+/// `age --json` payload.
+#[derive(Debug, Serialize)]
+pub struct AgeJson {
+    /// Survival only counts lines written inside the window.
+    pub window: TimeWindow,
+    /// Ages are measured from this commit time.
+    pub tip_date: DateTime<Local>,
+    pub old_years: f64,
+    /// `file` or `dir`; each path row is one of those.
+    pub by: String,
+    /// Directory depth with `by = dir`.
+    pub depth: Option<usize>,
+    /// All scanned lines (`path` is `.`).
+    pub total: PathAge,
+    /// Oldest median first (all of them, not just `--limit`).
+    pub paths: Vec<PathAge>,
+    pub period: &'static str,
+    /// `all`, `author` or `team`.
+    pub survival_by: String,
+    /// By cohort, then period, oldest first.
+    pub survival: Vec<Survival>,
+}
+schema!(AgeJson {
+    window: TimeWindow,
+    tip_date: DateTime<Local>,
+    old_years: f64,
+    by: String,
+    depth: Option<usize>,
+    total: PathAge,
+    paths: Vec<PathAge>,
+    period: &'static str,
+    survival_by: String,
+    survival: Vec<Survival>,
+});
+
+impl super::Runnable for Age {
+    fn run(self, g: &Global) -> Result<()> {
+        if self.threads > 0 {
+            let pool = rayon::ThreadPoolBuilder::new()
+                .num_threads(self.threads)
+                .build()?;
+            pool.install(|| self.report(g))
+        } else {
+            self.report(g)
+        }
+    }
+}
+
+impl Age {
+    fn report(&self, g: &Global) -> Result<()> {
+        if self.old_years < 0.0 {
+            bail!("--old-years must not be negative");
+        }
+        let cohort = match self.survival_by.as_str() {
+            "all" => Cohort::All,
+            "author" => Cohort::Author,
+            _ => {
+                let teams = Teams::parse(&self.team)?;
+                if teams.is_empty() {
+                    bail!("--survival-by team needs at least one --team TEAM=EMAIL,…");
+                }
+                Cohort::Team(teams)
+            }
+        };
+        let period = Period::parse(&self.period)?;
+        let repo = RepoExt::open(&self.path, &g.repo)?;
+        let opts = AgeOpts {
+            scan: ScanOpts {
+                all: self.all,
+                extensions: g.extensions.clone(),
+                include_ext: self.include_ext.clone(),
+                min_total: self.min_total,
+            },
+            depth: (self.by == "dir").then_some(self.depth),
+            old_years: self.old_years,
+            period,
+            cohort,
+        };
+        let report = age::analyse(&repo, &opts)?;
+
+        if g.format.uses_rows() {
+            let mut rows = Rows::new(&[
+                "section",
+                "name",
+                "period",
+                "lines",
+                "median_days",
+                "p90_days",
+                "old_share",
+                "written",
+                "survival",
+            ])
+            .titled(format!("Code age at {}", fmt_date(report.tip_time)))
+            .typed(&[
+                "lines",
+                "median_days",
+                "p90_days",
+                "old_share",
+                "written",
+                "survival",
+            ]);
+            for (section, p) in std::iter::once(("total", &report.total))
+                .chain(report.paths.iter().map(|p| (self.by.as_str(), p)))
+            {
+                rows.push([
+                    section.to_string(),
+                    p.path.clone(),
+                    String::new(),
+                    p.lines.to_string(),
+                    p.median_days.to_string(),
+                    p.p90_days.to_string(),
+                    g.format.ratio(p.old_share),
+                    String::new(),
+                    String::new(),
+                ]);
+            }
+            for s in &report.survival {
+                rows.push([
+                    "survival".to_string(),
+                    s.cohort.clone(),
+                    s.period.clone(),
+                    s.alive.to_string(),
+                    String::new(),
+                    String::new(),
+                    String::new(),
+                    s.written.to_string(),
+                    g.format.ratio(s.survival),
+                ]);
+            }
+            rows.print(g.format);
+        } else if g.json || self.json {
+            let data = AgeJson {
+                window: *repo.window(),
+                tip_date: report.tip_time,
+                old_years: self.old_years,
+                by: self.by.clone(),
+                depth: opts.depth,
+                total: report.total,
+                paths: report.paths,
+                period: period.as_str(),
+                survival_by: self.survival_by.clone(),
+                survival: report.survival,
+            };
+            json::print(g, "age", Some(&repo), self, &data)?;
+        } else {
+            self.print(&report);
+        }
+        Ok(())
+    }
+
+    fn print(&self, report: &age::AgeReport) {
+        let t = &report.total;
+        println!(
+            "⏳ Code age at {}: {} lines, median {}, p90 {}, {:.1}% older than {} years",
+            fmt_date(report.tip_time),
+            t.lines,
+            fmt_days(t.median_days),
+            fmt_days(t.p90_days),
+            t.old_share * 100.0,
+            self.old_years
+        );
+        if report.paths.is_empty() {
+            return;
+        }
+
+        let mut table = Table::new();
+        table.load_preset(UTF8_HORIZONTAL_ONLY).set_header(vec![
+            if self.by == "dir" {
+                "Directory"
+            } else {
+                "File"
+            },
+            "Lines",
+            "Median",
+            "p90",
+            "Old",
+        ]);
+        for p in report.paths.iter().take(self.limit) {
+            table.add_row(vec![
+                p.path.clone(),
+                p.lines.to_string(),
+                fmt_days(p.median_days),
+                fmt_days(p.p90_days),
+                format!("{:.1}%", p.old_share * 100.0),
+            ]);
+        }
+        println!("{table}");
+        if report.paths.len() > self.limit {
+            println!("… {} more (--limit)", report.paths.len() - self.limit);
+        }
+
+        if report.survival.is_empty() {
+            return;
+        }
+        // Largest cohorts by lines written become columns, next to `All`.
+        let mut sizes: HashMap<&str, usize> = HashMap::new();
+        // period start → (label, written, alive) per column
+        let mut all: BTreeMap<NaiveDate, (&str, usize, usize)> = BTreeMap::new();
+        for s in &report.survival {
+            *sizes.entry(&s.cohort).or_default() += s.written;
+            let e = all.entry(s.start).or_insert((&s.period, 0, 0));
+            e.1 += s.written;
+            e.2 += s.alive;
+        }
+        let mut columns: Vec<(&str, usize)> = sizes.into_iter().collect();
+        columns.sort_by(|a, b| b.1.cmp(&a.1).then(a.0.cmp(b.0)));
+        columns.truncate(if self.survival_by == "all" {
+            0
+        } else {
+            self.cohorts
+        });
+        let cell: HashMap<(&str, NaiveDate), &Survival> = report
+            .survival
+            .iter()
+            .map(|s| ((s.cohort.as_str(), s.start), s))
+            .collect();
+
+        let mut header = vec!["Written in".to_string(), "All".to_string()];
+        header.extend(columns.iter().map(|(c, _)| c.to_string()));
+        let mut table = Table::new();
+        table.load_preset(UTF8_HORIZONTAL_ONLY).set_header(header);
+        for (start, (label, written, alive)) in &all {
+            let mut row = vec![
+                label.to_string(),
+                format!(
+                    "{:.0}% of {written}",
+                    *alive as f64 / *written as f64 * 100.0
+                ),
+            ];
+            for (c, _) in &columns {
+                row.push(match cell.get(&(*c, *start)) {
+                    Some(s) => format!("{:.0}%", s.survival * 100.0),
+                    None => "—".to_string(),
+                });
+            }
+            table.add_row(row);
+        }
+        println!();
+        println!("🧬 Lines still at the tip, by when they were written");
+        println!("{table}");
+    }
+}
+
+/// `45 d`, `8.2 mo`, `3.4 y`.
+fn fmt_days(days: i64) -> String {
+    match days {
+        d if d < 60 => format!("{d} d"),
+        d if d < 730 => format!("{:.1} mo", d as f64 / 30.44),
+        d => format!("{:.1} y", d as f64 / 365.25),
+    }
+}
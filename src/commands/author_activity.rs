@@ -1,60 +1,95 @@
 use crate::commands::Global;
+use crate::presentation::{
+    flat::Rows,
+    json::{self, AuthorRow},
+    schema::schema,
+};
 use crate::{
-    domain::{git::RepoExt, stats as d},
-    utils::fmt_date,
+    domain::{git::RepoExt, stats as d, window::TimeWindow},
+    utils::{fmt_credit, fmt_date},
 };
 use anyhow::Result;
 use clap::Args;
-use serde_json::json;
+use serde::Serialize;
 
 /// Show the active commit range for a single author.
 ///
 /// Prints the first and last commit dates along with total commit count
 /// for the specified author.
-#[derive(Debug, Args)]
+#[derive(Debug, Args, Serialize)]
 pub struct AuthorActivity {
     /// Path to the Git repository. Defaults to the current directory.
     #[arg(short, long, default_value = ".")]
     pub path: String,
 
-    /// Email address to match exactly when searching commits.
+    /// Email address to look up. Any alias listed in the mailmap resolves
+    /// to the same canonical author.
     #[arg(short, long)]
     pub author: String,
+
+    /// Emit JSON even when the global flag is not set.
+    #[arg(long)]
+    pub json: bool,
 }
 
+/// `author-activity --json` payload.
+#[derive(Debug, Serialize)]
+pub struct AuthorActivityJson {
+    pub window: TimeWindow,
+    /// The `--author` as given.
+    pub query: String,
+    pub found: bool,
+    pub activity: Option<AuthorRow>,
+}
+schema!(AuthorActivityJson {
+    window: TimeWindow,
+    query: String,
+    found: bool,
+    activity: Option<AuthorRow>,
+});
+
 impl super::Runnable for AuthorActivity {
     fn run(self, g: &Global) -> Result<()> {
-        let repo = RepoExt::open(&self.path)?;
-        let stats = d::collect_commits(repo.repo(), usize::MAX, None);
-
-        match stats.data.get(&self.author) {
-            Some(m) => {
-                if g.json {
-                    let payload = json!({
-                        "author": self.author,
-                        "count": m.count,
-                        "first": fmt_date(m.first),
-                        "last":  fmt_date(m.last),
-                    });
-                    println!("{}", serde_json::to_string_pretty(&payload)?);
-                } else {
-                    println!(
-                        "{:<30} {:>4} commits 🗓  {} → {}",
-                        self.author,
-                        m.count,
-                        fmt_date(m.first),
-                        fmt_date(m.last)
-                    );
-                }
-            }
-            None => {
-                if g.json {
-                    let payload = json!({ "author": self.author, "found": false });
-                    println!("{}", serde_json::to_string_pretty(&payload)?);
-                } else {
-                    eprintln!("No commits by {}", self.author);
-                }
+        let repo = RepoExt::open(&self.path, &g.repo)?;
+        let stats = d::collect_commits(&repo, usize::MAX)?;
+        let email = repo.mailmap().canonical_email(&self.author);
+
+        if g.format.uses_rows() {
+            let mut rows = Rows::new(&["author", "commits", "first", "last"])
+                .titled(format!("Author activity ({})", repo.window()))
+                .typed(&["commits"]);
+            if let Some((author, m)) = stats.find(&email) {
+                rows.push([
+                    author.clone(),
+                    m.count.to_string(),
+                    fmt_date(m.first),
+                    fmt_date(m.last),
+                ]);
             }
+            rows.print(g.format);
+            return Ok(());
+        }
+
+        let found = stats.find(&email);
+        if g.json || self.json {
+            let data = AuthorActivityJson {
+                window: *repo.window(),
+                query: self.author.clone(),
+                found: found.is_some(),
+                activity: found.map(|(a, m)| AuthorRow::new(a, m)),
+            };
+            return json::print(g, "author-activity", Some(&repo), &self, &data);
+        }
+
+        match found {
+            Some((author, m)) => println!(
+                "{:<30} {:>4} commits 🗓  {} → {}",
+                author,
+                fmt_credit(m.count),
+                fmt_date(m.first),
+                fmt_date(m.last)
+            ),
+            None => eprintln!("No commits by {}", self.author),
         }
         Ok(())
     }
@@ -1,14 +1,23 @@
 use crate::commands::Global;
-use crate::domain::{blame, git::RepoExt};
+use crate::domain::window::TimeWindow;
+use crate::domain::{
+    blame::{self, AgeBucket, AuthorShare, LineSpan},
+    git::RepoExt,
+};
+use crate::presentation::{flat::Rows, json, schema::schema};
+use crate::utils::fmt_date;
 use anyhow::Result;
 use clap::Args;
+use comfy_table::{presets::UTF8_HORIZONTAL_ONLY, Table};
+use serde::Serialize;
 use std::path::Path;
 
 /// Summarise line ownership for a single file via `git blame`.
 ///
-/// The command reports how many lines each author owns and can emit JSON
-/// for tooling or a plain table for quick inspection.
-#[derive(Debug, Args)]
+/// The command reports how many lines each author owns, how old the lines
+/// are and each author's latest change to them, for the whole file or for
+/// line ranges and functions (`-L`).
+#[derive(Debug, Args, Serialize)]
 pub struct BlameSummary {
     /// File to analyse relative to the repository root.
     #[arg(short, long)]
@@ -19,20 +28,122 @@ pub struct BlameSummary {
     #[arg(short, long, default_value = ".")]
     pub path: String,
 
+    /// Only these lines, as with `git blame -L` (repeatable): `120,200`,
+    /// `120,+40`, `120,-40`, `120,` (to the end) or `:name` for a function.
+    #[arg(short = 'L', long = "lines", value_name = "RANGE")]
+    pub lines: Vec<String>,
+
     /// Output JSON regardless of the global `--json` flag.
     #[arg(long)]
     pub json: bool,
 }
 
+/// `blame-summary --json` payload; authors by lines owned, most first.
+#[derive(Debug, Serialize)]
+pub struct BlameSummaryJson {
+    pub window: TimeWindow,
+    pub file: String,
+    /// Line ranges blamed; empty for the whole file.
+    pub ranges: Vec<LineSpan>,
+    /// Lines blamed (bots left out per `--bots`).
+    pub lines: usize,
+    pub authors: Vec<AuthorShare>,
+    /// Line ages at the tip, youngest first.
+    pub ages: Vec<AgeBucket>,
+}
+schema!(BlameSummaryJson {
+    window: TimeWindow,
+    file: String,
+    ranges: Vec<LineSpan>,
+    lines: usize,
+    authors: Vec<AuthorShare>,
+    ages: Vec<AgeBucket>,
+});
+
 impl super::Runnable for BlameSummary {
     fn run(self, g: &Global) -> Result<()> {
-        let repo = RepoExt::open(&self.path)?;
-        let counts = blame::blame_counts(repo.repo(), Path::new(&self.file))?;
-        if g.json || self.json {
-            println!("{}", serde_json::to_string_pretty(&counts)?);
+        let repo = RepoExt::open(&self.path, &g.repo)?;
+        let file = Path::new(&self.file);
+        let ranges = if self.lines.is_empty() {
+            Vec::new()
+        } else {
+            blame::line_spans(&repo, file, &self.lines)?
+        };
+        let summary = blame::summarize(&repo, file, &ranges)?;
+        let what = if ranges.is_empty() {
+            self.file.clone()
         } else {
-            for (email, count) in counts {
-                println!("{:<30} {:>4} lines", email, count);
+            let r: Vec<_> = ranges
+                .iter()
+                .map(|r| format!("{}–{}", r.start, r.end))
+                .collect();
+            format!("{} lines {}", self.file, r.join(", "))
+        };
+
+        if g.format.uses_rows() {
+            let mut rows = Rows::new(&[
+                "author",
+                "lines",
+                "share",
+                "median_age_days",
+                "last_commit",
+                "last_commit_date",
+                "last_commit_summary",
+            ])
+            .titled(format!("Blame summary: {what}"))
+            .typed(&["lines", "share", "median_age_days"]);
+            for a in &summary.authors {
+                rows.push([
+                    a.author.clone(),
+                    a.lines.to_string(),
+                    g.format.ratio(a.share),
+                    a.median_age_days.to_string(),
+                    a.last_change.commit.clone(),
+                    fmt_date(a.last_change.date),
+                    a.last_change.summary.clone(),
+                ]);
+            }
+            rows.print(g.format);
+        } else if g.json || self.json {
+            let data = BlameSummaryJson {
+                window: *repo.window(),
+                file: self.file.clone(),
+                ranges,
+                lines: summary.lines,
+                authors: summary.authors,
+                ages: summary.ages,
+            };
+            json::print(g, "blame-summary", Some(&repo), &self, &data)?;
+        } else {
+            println!("📄 {what} — {} lines", summary.lines);
+            let mut t = Table::new();
+            t.load_preset(UTF8_HORIZONTAL_ONLY).set_header(vec![
+                "Author",
+                "Lines",
+                "Share",
+                "Median age",
+                "Last change",
+            ]);
+            for a in &summary.authors {
+                let c = &a.last_change;
+                t.add_row(vec![
+                    a.author.clone(),
+                    a.lines.to_string(),
+                    format!("{:.1}%", a.share * 100.0),
+                    format!("{} d", a.median_age_days),
+                    format!("{} {} {}", fmt_date(c.date), &c.commit[..8], c.summary),
+                ]);
+            }
+            println!("{t}");
+            println!("Line age:");
+            for b in &summary.ages {
+                println!(
+                    "  {:<12} {:>6}  {:>5.1}%  {}",
+                    b.label,
+                    b.lines,
+                    b.share * 100.0,
+                    "█".repeat((b.share * 30.0).round() as usize)
+                );
             }
         }
         Ok(())
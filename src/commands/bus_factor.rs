@@ -1,9 +1,14 @@
 use anyhow::Result;
 use clap::Args;
-use serde_json::json;
+use serde::Serialize;
 
 use crate::commands::Global;
-use crate::domain::{bus_factor, git::RepoExt};
+use crate::domain::{bus_factor, git::RepoExt, window::TimeWindow};
+use crate::presentation::{
+    flat::{Format, Rows},
+    json,
+    schema::schema,
+};
 use comfy_table::{presets::UTF8_HORIZONTAL_ONLY, Table};
 
 /// Detect single‑author dominance in files or directories.
@@ -11,7 +16,7 @@ use comfy_table::{presets::UTF8_HORIZONTAL_ONLY, Table};
 /// In "blame" mode the command calculates line ownership using `git blame`.
 /// The `--fast` option switches to a quicker heuristic based on recent
 /// touches which trades accuracy for speed.
-#[derive(Debug, Args)]
+#[derive(Debug, Args, Serialize)]
 pub struct BusFactor {
     /// Path to the Git repository to scan.
     #[arg(short, long, default_value = ".")]
@@ -65,255 +70,239 @@ pub struct BusFactor {
     pub threads: usize,
 }
 
-impl super::Runnable for BusFactor {
-    fn run(self, g: &Global) -> Result<()> {
-        let json = self.json || g.json;
+/// `bus-factor --json` payload.
+#[derive(Debug, Serialize)]
+pub struct BusFactorJson {
+    pub window: TimeWindow,
+    /// `blame` (lines) or `fast` (touches).
+    pub mode: &'static str,
+    /// `file` or `dir`; `path` below is one of those.
+    pub by: String,
+    /// Directory depth with `by = dir`.
+    pub depth: Option<usize>,
+    pub threshold: f64,
+    /// Every path above the threshold.
+    pub matches: Vec<Ownership>,
+    /// The `--limit` most dominated paths.
+    pub top_candidates: Vec<Ownership>,
+}
+schema!(BusFactorJson {
+    window: TimeWindow,
+    mode: &'static str,
+    by: String,
+    depth: Option<usize>,
+    threshold: f64,
+    matches: Vec<Ownership>,
+    top_candidates: Vec<Ownership>,
+});
 
-        fn render_table(
-            title: &str,
-            unit: &str,
-            rows: &[(String, String, f64, usize)],
-            limit: usize,
-        ) {
-            let mut t = Table::new();
-            t.load_preset(UTF8_HORIZONTAL_ONLY).set_header(vec![
-                if title.contains("Directory") {
-                    "Directory"
-                } else {
-                    "File"
-                },
-                "Owner",
-                "Ownership",
-                unit,
-            ]);
+/// Share of a path owned by its top author (0..1).
+#[derive(Debug, Serialize)]
+pub struct Ownership {
+    pub path: String,
+    pub author: String,
+    pub ownership: f64,
+    pub total: usize,
+}
+schema!(Ownership {
+    path: String,
+    author: String,
+    ownership: f64,
+    total: usize,
+});
 
-            for (k, owner, ratio, total) in rows.iter().take(limit) {
-                t.add_row(vec![
-                    k.clone(),
-                    owner.clone(),
-                    format!("{:>4.1}%", ratio * 100.0),
-                    total.to_string(),
-                ]);
-            }
-            println!("{title}");
-            println!("{t}");
+impl From<&(String, String, f64, usize)> for Ownership {
+    fn from((path, author, ownership, total): &(String, String, f64, usize)) -> Self {
+        Self {
+            path: path.clone(),
+            author: author.clone(),
+            ownership: *ownership,
+            total: *total,
         }
+    }
+}
 
+impl super::Runnable for BusFactor {
+    fn run(self, g: &Global) -> Result<()> {
+        if !self.fast && self.threads > 0 {
+            let pool = rayon::ThreadPoolBuilder::new()
+                .num_threads(self.threads)
+                .build()?;
+            pool.install(|| self.report(g))
+        } else {
+            self.report(g)
+        }
+    }
+}
+
+impl BusFactor {
+    /// Scores as `(path, owner, ratio, total)`, most dominated first.
+    fn scores(&self, repo: &RepoExt, g: &Global) -> Result<Vec<(String, String, f64, usize)>> {
         let opts = bus_factor::ScanOpts {
             all: self.all,
+            extensions: g.extensions.clone(),
             include_ext: self.include_ext.clone(),
             min_total: self.min_total,
         };
-
-        let run_inner = || -> Result<()> {
-            let repo = RepoExt::open(&self.path)?;
-            let (mode, unit) = if self.fast {
-                ("FAST (touches)", "Touches")
+        let max = (self.max_commits > 0).then_some(self.max_commits);
+        Ok(if self.by == "dir" {
+            let scores = if self.fast {
+                bus_factor::compute_dir_scores_fast(repo, max, &opts, self.depth)?
             } else {
-                ("Blame (lines)", "Lines")
+                bus_factor::compute_dir_scores_parallel(repo, &opts, self.depth)?
             };
+            scores
+                .into_iter()
+                .map(|s| (s.dir, s.top_author, s.ratio, s.total))
+                .collect()
+        } else {
+            let scores = if self.fast {
+                bus_factor::compute_scores_fast(repo, max, &opts)?
+            } else {
+                bus_factor::compute_scores_parallel(repo, &opts)?
+            };
+            scores
+                .into_iter()
+                .map(|s| (s.file, s.top_author, s.ratio, s.total))
+                .collect()
+        })
+    }
 
-            if self.by == "dir" {
-                if self.fast {
-                    let max = if self.max_commits == 0 {
-                        None
-                    } else {
-                        Some(self.max_commits)
-                    };
-                    let scores =
-                        bus_factor::compute_dir_scores_fast(repo.repo(), max, &opts, self.depth)?;
-                    let hits: Vec<_> = scores
-                        .iter()
-                        .filter(|s| s.ratio > self.threshold)
-                        .map(|s| (s.dir.clone(), s.top_author.clone(), s.ratio, s.total))
-                        .collect();
-
-                    if json {
-                        let payload = json!({
-                            "mode": mode, "by": "dir", "depth": self.depth, "threshold": self.threshold,
-                            "matches": hits.iter().map(|(d,a,r,t)| json!({"dir": d, "author": a, "ownership": r, "total": t})).collect::<Vec<_>>(),
-                            "top_candidates": scores.iter().take(self.limit).map(|s| json!({"dir": s.dir, "author": s.top_author, "ownership": s.ratio, "total": s.total})).collect::<Vec<_>>()
-                        });
-                        println!("{}", serde_json::to_string_pretty(&payload)?);
-                        return Ok(());
-                    }
-
-                    if hits.is_empty() {
-                        println!(
-                            "No directories exceed threshold {:>4.1}% — {} mode.\nTop candidates:",
-                            self.threshold * 100.0,
-                            mode
-                        );
-                        let rows: Vec<_> = scores
-                            .iter()
-                            .map(|s| (s.dir.clone(), s.top_author.clone(), s.ratio, s.total))
-                            .collect();
-                        render_table(
-                            "⚠️  Bus Factor — Top Directories (candidates)",
-                            unit,
-                            &rows,
-                            self.limit,
-                        );
-                    } else {
-                        render_table(
-                            &format!(
-                                "⚠️  Bus Factor — Directories above threshold {:>4.1}%  ({mode})",
-                                self.threshold * 100.0
-                            ),
-                            unit,
-                            &hits,
-                            self.limit,
-                        );
-                    }
-                    return Ok(());
-                } else {
-                    let scores =
-                        bus_factor::compute_dir_scores_parallel(&self.path, &opts, self.depth)?;
-                    let hits: Vec<_> = scores
-                        .iter()
-                        .filter(|s| s.ratio > self.threshold)
-                        .map(|s| (s.dir.clone(), s.top_author.clone(), s.ratio, s.total))
-                        .collect();
-
-                    if json {
-                        let payload = json!({
-                            "mode": mode, "by": "dir", "depth": self.depth, "threshold": self.threshold,
-                            "matches": hits.iter().map(|(d,a,r,t)| json!({"dir": d, "author": a, "ownership": r, "total": t})).collect::<Vec<_>>(),
-                            "top_candidates": scores.iter().take(self.limit).map(|s| json!({"dir": s.dir, "author": s.top_author, "ownership": s.ratio, "total": s.total})).collect::<Vec<_>>()
-                        });
-                        println!("{}", serde_json::to_string_pretty(&payload)?);
-                        return Ok(());
-                    }
-
-                    if hits.is_empty() {
-                        println!(
-                            "No directories exceed threshold {:>4.1}% — {} mode.\nTop candidates:",
-                            self.threshold * 100.0,
-                            mode
-                        );
-                        let rows: Vec<_> = scores
-                            .iter()
-                            .map(|s| (s.dir.clone(), s.top_author.clone(), s.ratio, s.total))
-                            .collect();
-                        render_table(
-                            "⚠️  Bus Factor — Top Directories (candidates)",
-                            unit,
-                            &rows,
-                            self.limit,
-                        );
-                    } else {
-                        render_table(
-                            &format!(
-                                "⚠️  Bus Factor — Directories above threshold {:>4.1}%  ({mode})",
-                                self.threshold * 100.0
-                            ),
-                            unit,
-                            &hits,
-                            self.limit,
-                        );
-                    }
-                    return Ok(());
-                }
-            }
-
-            // by == "file"
-            if self.fast {
-                let max = if self.max_commits == 0 {
-                    None
-                } else {
-                    Some(self.max_commits)
-                };
-                let scores = bus_factor::compute_scores_fast(repo.repo(), max, &opts)?;
-                let hits: Vec<_> = scores
-                    .iter()
-                    .filter(|s| s.ratio > self.threshold)
-                    .map(|s| (s.file.clone(), s.top_author.clone(), s.ratio, s.total))
-                    .collect();
+    fn report(&self, g: &Global) -> Result<()> {
+        let repo = RepoExt::open(&self.path, &g.repo)?;
+        let (mode, unit) = if self.fast {
+            ("FAST (touches)", "Touches")
+        } else {
+            ("Blame (lines)", "Lines")
+        };
+        let scores = self.scores(&repo, g)?;
+        let hits: Vec<_> = scores
+            .iter()
+            .filter(|s| s.2 > self.threshold)
+            .cloned()
+            .collect();
 
-                if json {
-                    let payload = json!({
-                        "mode": mode, "by": "file", "threshold": self.threshold,
-                        "matches": hits.iter().map(|(f,a,r,t)| json!({"file": f, "author": a, "ownership": r, "total": t})).collect::<Vec<_>>(),
-                        "top_candidates": scores.iter().take(self.limit).map(|s| json!({"file": s.file, "author": s.top_author, "ownership": s.ratio, "total": s.total})).collect::<Vec<_>>()
-                    });
-                    println!("{}", serde_json::to_string_pretty(&payload)?);
-                    return Ok(());
-                }
+        if g.format.uses_rows() {
+            print_flat(
+                &self.by,
+                mode,
+                &scores,
+                self.threshold,
+                self.limit,
+                g.format,
+            );
+            return Ok(());
+        }
 
-                if hits.is_empty() {
-                    println!(
-                        "No files exceed threshold {:>4.1}% — {} mode.\nTop candidates:",
-                        self.threshold * 100.0,
-                        mode
-                    );
-                    let rows: Vec<_> = scores
-                        .iter()
-                        .map(|s| (s.file.clone(), s.top_author.clone(), s.ratio, s.total))
-                        .collect();
-                    render_table("⚠️  Bus Factor — Top Candidates", unit, &rows, self.limit);
-                } else {
-                    render_table(
-                        &format!(
-                            "⚠️  Bus Factor — Files above threshold {:>4.1}%  ({mode})",
-                            self.threshold * 100.0
-                        ),
-                        unit,
-                        &hits,
-                        self.limit,
-                    );
-                }
-                return Ok(());
-            } else {
-                let scores = bus_factor::compute_scores_parallel(&self.path, &opts)?;
-                let hits: Vec<_> = scores
+        if self.json || g.json {
+            let data = BusFactorJson {
+                window: *repo.window(),
+                mode: if self.fast { "fast" } else { "blame" },
+                by: self.by.clone(),
+                depth: (self.by == "dir").then_some(self.depth),
+                threshold: self.threshold,
+                matches: hits.iter().map(Ownership::from).collect(),
+                top_candidates: scores
                     .iter()
-                    .filter(|s| s.ratio > self.threshold)
-                    .map(|s| (s.file.clone(), s.top_author.clone(), s.ratio, s.total))
-                    .collect();
-
-                if json {
-                    let payload = json!({
-                        "mode": mode, "by": "file", "threshold": self.threshold,
-                        "matches": hits.iter().map(|(f,a,r,t)| json!({"file": f, "author": a, "ownership": r, "total": t})).collect::<Vec<_>>(),
-                        "top_candidates": scores.iter().take(self.limit).map(|s| json!({"file": s.file, "author": s.top_author, "ownership": s.ratio, "total": s.total})).collect::<Vec<_>>()
-                    });
-                    println!("{}", serde_json::to_string_pretty(&payload)?);
-                    return Ok(());
-                }
+                    .take(self.limit)
+                    .map(Ownership::from)
+                    .collect(),
+            };
+            return json::print(g, "bus-factor", Some(&repo), self, &data);
+        }
 
-                if hits.is_empty() {
-                    println!(
-                        "No files exceed threshold {:>4.1}% — {} mode.\nTop candidates:",
-                        self.threshold * 100.0,
-                        mode
-                    );
-                    let rows: Vec<_> = scores
-                        .iter()
-                        .map(|s| (s.file.clone(), s.top_author.clone(), s.ratio, s.total))
-                        .collect();
-                    render_table("⚠️  Bus Factor — Top Candidates", unit, &rows, self.limit);
-                } else {
-                    render_table(
-                        &format!(
-                            "⚠️  Bus Factor — Files above threshold {:>4.1}%  ({mode})",
-                            self.threshold * 100.0
-                        ),
-                        unit,
-                        &hits,
-                        self.limit,
-                    );
-                }
-                return Ok(());
-            }
+        let (what, header) = if self.by == "dir" {
+            ("Directories", "Directory")
+        } else {
+            ("Files", "File")
         };
-
-        if !self.fast && self.threads > 0 {
-            let pool = rayon::ThreadPoolBuilder::new()
-                .num_threads(self.threads)
-                .build()?;
-            pool.install(|| run_inner())
+        if hits.is_empty() {
+            println!(
+                "No {} exceed threshold {:>4.1}% — {} mode.\nTop candidates:",
+                what.to_lowercase(),
+                self.threshold * 100.0,
+                mode
+            );
+            let title = if self.by == "dir" {
+                "⚠️  Bus Factor — Top Directories (candidates)"
+            } else {
+                "⚠️  Bus Factor — Top Candidates"
+            };
+            render_table(title, header, unit, &scores, self.limit);
         } else {
-            run_inner()
+            render_table(
+                &format!(
+                    "⚠️  Bus Factor — {what} above threshold {:>4.1}%  ({mode})",
+                    self.threshold * 100.0
+                ),
+                header,
+                unit,
+                &hits,
+                self.limit,
+            );
         }
+        Ok(())
+    }
+}
+
+fn render_table(
+    title: &str,
+    header: &str,
+    unit: &str,
+    rows: &[(String, String, f64, usize)],
+    limit: usize,
+) {
+    let mut t = Table::new();
+    t.load_preset(UTF8_HORIZONTAL_ONLY)
+        .set_header(vec![header, "Owner", "Ownership", unit]);
+    for (k, owner, ratio, total) in rows.iter().take(limit) {
+        t.add_row(vec![
+            k.clone(),
+            owner.clone(),
+            format!("{:>4.1}%", ratio * 100.0),
+            total.to_string(),
+        ]);
+    }
+    println!("{title}");
+    println!("{t}");
+}
+
+/// Flat rows mirroring the table: paths above the threshold, or the top
+/// candidates when none are.
+fn print_flat(
+    key: &str,
+    mode: &str,
+    scores: &[(String, String, f64, usize)],
+    threshold: f64,
+    limit: usize,
+    format: Format,
+) {
+    let any_hit = scores.iter().any(|s| s.2 > threshold);
+    let what = if key == "dir" { "Directories" } else { "Files" };
+    let title = if any_hit {
+        format!(
+            "Bus Factor — {what} above threshold {:.1}% ({mode})",
+            threshold * 100.0
+        )
+    } else {
+        format!("Bus Factor — Top candidates ({mode})")
+    };
+    let mut rows = Rows::new(&[key, "owner", "ownership", "total", "above_threshold"])
+        .titled(title)
+        .typed(&["ownership", "total", "above_threshold"]);
+    for (path, owner, ratio, total) in scores
+        .iter()
+        .filter(|s| !any_hit || s.2 > threshold)
+        .take(limit)
+    {
+        let above = *ratio > threshold;
+        rows.push([
+            path.clone(),
+            owner.clone(),
+            format.ratio(*ratio),
+            total.to_string(),
+            above.to_string(),
+        ]);
     }
+    rows.print(format);
 }
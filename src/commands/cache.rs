@@ -0,0 +1,167 @@
+use anyhow::Result;
+use clap::{Args, Subcommand};
+use git2::{Oid, Sort};
+use serde::Serialize;
+use std::collections::HashSet;
+use std::path::PathBuf;
+
+use crate::{
+    commands::Global,
+    domain::git::RepoExt,
+    presentation::{flat::Rows, json, schema::schema},
+};
+
+/// Inspect or maintain the on-disk commit facts cache.
+///
+/// History scanners (churn, file-contributions, top-coauthors,
+/// bus-factor --fast) store per-commit facts in `.git/gitrivia/` so later
+/// runs only diff commits they have not seen yet.
+#[derive(Debug, Args, Serialize)]
+pub struct Cache {
+    /// Path to the Git repository.
+    #[arg(short, long, default_value = ".")]
+    pub path: String,
+
+    /// Emit JSON even when the global flag is not set.
+    #[arg(long, global = true)]
+    pub json: bool,
+
+    #[command(subcommand)]
+    pub action: Option<CacheAction>,
+}
+
+#[derive(Debug, Clone, Copy, Subcommand, Serialize)]
+#[serde(rename_all = "lowercase")]
+pub enum CacheAction {
+    /// Show location, size and coverage of the selected history (default)
+    Info,
+    /// Drop everything and recompute facts for the selected history
+    Rebuild,
+    /// Remove facts for commits no longer reachable from any ref
+    Prune,
+    /// Delete the cache
+    Clear,
+}
+
+/// `cache --json` payload. Fields an action does not report are `null`.
+#[derive(Debug, Serialize)]
+pub struct CacheJson {
+    pub action: &'static str,
+    pub path: PathBuf,
+    pub entries: usize,
+    pub bytes: Option<u64>,
+    pub selected_commits: Option<usize>,
+    pub selected_cached: Option<usize>,
+    pub removed: Option<usize>,
+}
+schema!(CacheJson {
+    action: &'static str,
+    path: PathBuf,
+    entries: usize,
+    bytes: Option<u64>,
+    selected_commits: Option<usize>,
+    selected_cached: Option<usize>,
+    removed: Option<usize>,
+});
+
+impl CacheJson {
+    fn new(action: &'static str, path: PathBuf, entries: usize) -> Self {
+        Self {
+            action,
+            path,
+            entries,
+            bytes: None,
+            selected_commits: None,
+            selected_cached: None,
+            removed: None,
+        }
+    }
+}
+
+impl super::Runnable for Cache {
+    fn run(self, g: &Global) -> Result<()> {
+        let repo = RepoExt::open(&self.path, &g.repo)?;
+        let mut cache = repo.open_cache()?;
+        let Some(file) = cache.path().map(|p| p.to_path_buf()) else {
+            anyhow::bail!("caching is disabled (--no-cache)");
+        };
+
+        let payload = match self.action.unwrap_or(CacheAction::Info) {
+            CacheAction::Info => {
+                let selected: Vec<Oid> = repo.revwalk()?.flatten().collect();
+                let cached = selected.iter().filter(|id| cache.contains(**id)).count();
+                let bytes = std::fs::metadata(&file).map(|m| m.len()).unwrap_or(0);
+                CacheJson {
+                    bytes: Some(bytes),
+                    selected_commits: Some(selected.len()),
+                    selected_cached: Some(cached),
+                    ..CacheJson::new("info", file.clone(), cache.len())
+                }
+            }
+            CacheAction::Rebuild => {
+                cache.clear()?;
+                for id in repo.revwalk()?.flatten() {
+                    if let Ok(commit) = repo.repo().find_commit(id) {
+                        cache.get(repo.repo(), &commit)?;
+                    }
+                }
+                cache.flush()?;
+                CacheJson::new("rebuild", file.clone(), cache.len())
+            }
+            CacheAction::Prune => {
+                let reachable = reachable_from_refs(&repo)?;
+                let removed = cache.retain(|id| reachable.contains(&id))?;
+                CacheJson {
+                    removed: Some(removed),
+                    ..CacheJson::new("prune", file.clone(), cache.len())
+                }
+            }
+            CacheAction::Clear => {
+                let removed = cache.len();
+                cache.clear()?;
+                CacheJson {
+                    removed: Some(removed),
+                    ..CacheJson::new("clear", file.clone(), 0)
+                }
+            }
+        };
+
+        if g.json || self.json {
+            return json::print(g, "cache", Some(&repo), &self, &payload);
+        }
+        // Reported fields in key order, for the table and flat output.
+        let fields = serde_json::to_value(&payload)?;
+        let fields = fields
+            .as_object()
+            .into_iter()
+            .flatten()
+            .filter(|(_, v)| !v.is_null());
+        if g.format.uses_rows() {
+            let mut rows = Rows::new(&["key", "value"])
+                .titled("Cache")
+                .typed(&["value"]);
+            for (k, v) in fields {
+                let v = v.as_str().map_or_else(|| v.to_string(), str::to_string);
+                rows.push([k.as_str(), &v]);
+            }
+            rows.print(g.format);
+        } else {
+            println!("🗄️  Cache: {}", file.display());
+            for (k, v) in fields {
+                if k != "path" && k != "action" {
+                    println!("  {:<18} {}", k.replace('_', " "), v);
+                }
+            }
+        }
+        Ok(())
+    }
+}
+
+/// Every commit reachable from HEAD or any ref (ignores `--rev`).
+fn reachable_from_refs(repo: &RepoExt) -> Result<HashSet<Oid>> {
+    let mut walk = repo.repo().revwalk()?;
+    walk.set_sorting(Sort::NONE)?;
+    let _ = walk.push_head();
+    walk.push_glob("*")?;
+    Ok(walk.flatten().collect())
+}
@@ -1,23 +1,30 @@
 use anyhow::Result;
 use clap::Args;
 use comfy_table::{presets::UTF8_HORIZONTAL_ONLY, Table};
-use serde_json::json;
+use serde::Serialize;
+use std::collections::HashMap;
 
 use crate::commands::Global;
 use crate::domain::git::RepoExt;
-use crate::domain::{bus_factor::ScanOpts, churn};
+use crate::domain::{
+    bus_factor::ScanOpts,
+    churn::{self, ChurnEntry},
+    window::TimeWindow,
+};
+use crate::presentation::{flat::Rows, json, schema::schema};
 
 /// Rank paths by recent weighted change activity.
 ///
 /// Useful for spotting hotspots or volatile areas of the repository over
 /// a configurable time window.
-#[derive(Debug, Args)]
+#[derive(Debug, Args, Serialize)]
 pub struct Churn {
     /// Path to the Git repository.
     #[arg(short, long, default_value = ".")]
     pub path: String,
 
     /// Number of days of history to include when calculating churn.
+    /// Ignored when the global `--since` / `--last` is given.
     #[arg(long, default_value = "90")]
     pub window_days: i64,
 
@@ -50,93 +57,79 @@ pub struct Churn {
     pub json: bool,
 }
 
+/// `churn --json` payload.
+#[derive(Debug, Serialize)]
+pub struct ChurnJson {
+    /// The window churn was measured over.
+    pub window: TimeWindow,
+    pub window_days: i64,
+    /// `file` or `dir`; each row's `path` is one of those.
+    pub by: String,
+    /// Directory depth with `by = dir`.
+    pub depth: Option<usize>,
+    /// The `--limit` most churned paths.
+    pub rows: Vec<ChurnEntry>,
+}
+schema!(ChurnJson {
+    window: TimeWindow,
+    window_days: i64,
+    by: String,
+    depth: Option<usize>,
+    rows: Vec<ChurnEntry>,
+});
+
 impl super::Runnable for Churn {
     fn run(self, g: &Global) -> Result<()> {
-        let json = self.json || g.json;
-
-        let repo = RepoExt::open(&self.path)?;
+        let repo = RepoExt::open(&self.path, &g.repo)?;
         let opts = ScanOpts {
             all: self.all,
+            extensions: g.extensions.clone(),
             include_ext: self.include_ext.clone(),
             min_total: self.min_total,
         };
-        let mut entries = churn::compute_churn(repo.repo(), self.window_days, &opts)?;
-
-        if self.by == "dir" {
-            use std::collections::HashMap;
-            let mut by_dir: HashMap<String, (f64, usize, usize, usize)> = HashMap::new();
-            for e in entries.iter() {
-                let k = churn::dir_key(&e.path, self.depth);
-                let v = by_dir.entry(k).or_insert((0.0, 0, 0, 0));
-                v.0 += e.churn;
-                v.1 += e.adds;
-                v.2 += e.dels;
-                v.3 += e.touches;
-            }
-            let mut dir_rows: Vec<_> = by_dir
-                .into_iter()
-                .map(|(dir, (churn, adds, dels, touches))| (dir, churn, adds, dels, touches))
-                .collect();
-            dir_rows.sort_by(|a, b| b.1.partial_cmp(&a.1).unwrap_or(std::cmp::Ordering::Equal));
-
-            if json {
-                let payload = json!({
-                    "by": "dir",
-                    "depth": self.depth,
-                    "window_days": self.window_days,
-                    "rows": dir_rows.iter().take(self.limit).map(|(dir,ch,adds,dels,t)| json!({
-                        "dir": dir, "churn": ch, "adds": adds, "dels": dels, "touches": t
-                    })).collect::<Vec<_>>()
-                });
-                println!("{}", serde_json::to_string_pretty(&payload)?);
-                return Ok(());
-            }
-
-            let mut t = Table::new();
-            t.load_preset(UTF8_HORIZONTAL_ONLY).set_header(vec![
-                "Directory",
-                "Churn",
-                "Adds",
-                "Dels",
-                "Touches",
-            ]);
-
-            for (dir, churn, adds, dels, touches) in dir_rows.into_iter().take(self.limit) {
-                t.add_row(vec![
-                    dir,
-                    format!("{:.1}", churn),
-                    adds.to_string(),
-                    dels.to_string(),
-                    touches.to_string(),
+        let mut entries = churn::compute_churn(&repo, self.window_days, &opts)?;
+        let window = churn::churn_window(&repo, self.window_days);
+
+        let (label, header) = if self.by == "dir" {
+            entries = by_dir(&entries, self.depth);
+            (format!("by directory (depth {})", self.depth), "Directory")
+        } else {
+            ("by file".to_string(), "File")
+        };
+        entries.truncate(self.limit);
+
+        if g.format.uses_rows() {
+            let mut rows = Rows::new(&[self.by.as_str(), "churn", "adds", "dels", "touches"])
+                .titled(format!("Churn ({window}) — {label}"))
+                .typed(&["churn", "adds", "dels", "touches"]);
+            for e in &entries {
+                rows.push([
+                    e.path.clone(),
+                    g.format.float(e.churn, 1),
+                    e.adds.to_string(),
+                    e.dels.to_string(),
+                    e.touches.to_string(),
                 ]);
             }
-
-            println!(
-                "♨️  Churn (last {} days) — by directory (depth {})",
-                self.window_days, self.depth
-            );
-            println!("{t}");
+            rows.print(g.format);
             return Ok(());
         }
 
-        // by == "file"
-        if json {
-            let payload = json!({
-                "by": "file",
-                "window_days": self.window_days,
-                "rows": entries.iter().take(self.limit).map(|e| json!({
-                    "file": e.path, "churn": e.churn, "adds": e.adds, "dels": e.dels, "touches": e.touches
-                })).collect::<Vec<_>>()
-            });
-            println!("{}", serde_json::to_string_pretty(&payload)?);
-            return Ok(());
+        if self.json || g.json {
+            let data = ChurnJson {
+                window,
+                window_days: self.window_days,
+                by: self.by.clone(),
+                depth: (self.by == "dir").then_some(self.depth),
+                rows: entries,
+            };
+            return json::print(g, "churn", Some(&repo), &self, &data);
         }
 
         let mut t = Table::new();
         t.load_preset(UTF8_HORIZONTAL_ONLY)
-            .set_header(vec!["File", "Churn", "Adds", "Dels", "Touches"]);
-
-        for e in entries.drain(..).take(self.limit) {
+            .set_header(vec![header, "Churn", "Adds", "Dels", "Touches"]);
+        for e in entries {
             t.add_row(vec![
                 e.path,
                 format!("{:.1}", e.churn),
@@ -145,10 +138,30 @@ impl super::Runnable for Churn {
                 e.touches.to_string(),
             ]);
         }
-
-        println!("♨️  Churn (last {} days) — by file", self.window_days);
-
+        println!("♨️  Churn ({window}) — {label}");
         println!("{t}");
         Ok(())
     }
 }
+
+/// File entries summed per directory prefix, highest churn first.
+fn by_dir(entries: &[ChurnEntry], depth: usize) -> Vec<ChurnEntry> {
+    let mut dirs: HashMap<String, ChurnEntry> = HashMap::new();
+    for e in entries {
+        let k = churn::dir_key(&e.path, depth);
+        let d = dirs.entry(k.clone()).or_insert_with(|| ChurnEntry {
+            path: k,
+            churn: 0.0,
+            adds: 0,
+            dels: 0,
+            touches: 0,
+        });
+        d.churn += e.churn;
+        d.adds += e.adds;
+        d.dels += e.dels;
+        d.touches += e.touches;
+    }
+    let mut out: Vec<_> = dirs.into_values().collect();
+    out.sort_by(|a, b| b.churn.total_cmp(&a.churn));
+    out
+}
@@ -0,0 +1,348 @@
+use anyhow::{bail, Context, Result};
+use clap::Args;
+use comfy_table::{presets::UTF8_HORIZONTAL_ONLY, Table};
+use serde::Serialize;
+use std::collections::HashMap;
+
+use crate::commands::Global;
+use crate::domain::{
+    bus_factor::ScanOpts,
+    codeowners::{self, CodeOwners, Grouping, OwnerRule, OwnersDiff, OwnersOpts},
+    git::RepoExt,
+    window::TimeWindow,
+};
+use crate::presentation::{codeowners as render, flat::Rows, json, schema::schema};
+use crate::utils::fmt_date;
+
+/// Suggest a CODEOWNERS file from line ownership.
+///
+/// Each directory (or `--glob` pattern) gets the top authors of its blamed
+/// lines, newer lines weighing more and authors who left ignored. With
+/// `--diff`, the suggestion is compared with the committed CODEOWNERS and
+/// its owners are checked for having left or never touched their paths.
+#[derive(Debug, Args, Serialize)]
+pub struct Codeowners {
+    /// Path to the Git repository.
+    #[arg(short, long, default_value = ".")]
+    pub path: String,
+
+    /// Directory depth of the generated rules (`/a/`, `/a/b/`, …; root
+    /// files fall under `*`).
+    #[arg(long, default_value = "1")]
+    pub depth: usize,
+
+    /// Generate one rule per CODEOWNERS pattern instead of per directory
+    /// (repeatable; later patterns win, as in the file).
+    #[arg(long, value_name = "PATTERN")]
+    pub glob: Vec<String>,
+
+    /// Owners per rule at most.
+    #[arg(long, default_value = "3")]
+    pub top: usize,
+
+    /// Share of a rule's weighted lines an author needs to be an owner
+    /// (0..1).
+    #[arg(long, default_value = "0.2")]
+    pub min_share: f64,
+
+    /// Lines lose half their weight every this many days, so recent
+    /// authors rank higher. 0 weighs every line the same.
+    #[arg(long, default_value = "365")]
+    pub half_life_days: f64,
+
+    /// Authors without a commit in this many days before the tip have left:
+    /// never suggested, and flagged by `--diff`. 0 disables.
+    #[arg(long, default_value = "365")]
+    pub inactive_days: i64,
+
+    /// Leave out rules with fewer blamed lines than this.
+    #[arg(long, default_value = "10")]
+    pub min_total: usize,
+
+    /// Compare with the existing CODEOWNERS instead of printing a new one.
+    #[arg(long)]
+    pub diff: bool,
+
+    /// CODEOWNERS file to compare with (default: the committed one in
+    /// `.github/`, the root, `docs/` or `.gitlab/`).
+    #[arg(long, value_name = "FILE", requires = "diff")]
+    pub against: Option<String>,
+
+    /// Map a `@handle` in the existing file to a commit email (repeatable),
+    /// e.g. `octocat=mona@example.com`. Unmapped handles are matched by
+    /// email user name or author name.
+    #[arg(long, value_name = "HANDLE=EMAIL")]
+    pub alias: Vec<String>,
+
+    /// Write the suggested file here instead of standard output.
+    #[arg(short, long, value_name = "FILE", conflicts_with = "diff")]
+    pub output: Option<String>,
+
+    /// Include all files, even those normally filtered out.
+    #[arg(long)]
+    pub all: bool,
+
+    /// Additional file extensions to include (comma‑separated).
+    #[arg(long, value_delimiter = ',')]
+    pub include_ext: Vec<String>,
+
+    /// Number of threads for blame analysis (0 = auto).
+    #[arg(long, default_value = "0")]
+    pub threads: usize,
+
+    /// Emit JSON even when the global flag is not set.
+    #[arg(long)]
+    pub json: bool,
+}
+
+/// `codeowners --json` payload.
+#[derive(Debug, Serialize)]
+pub struct CodeownersJson {
+    pub window: TimeWindow,
+    pub rules: Vec<OwnerRule>,
+    /// File the suggestion was written to, if any.
+    pub output: Option<String>,
+    /// File compared with (`--diff` only).
+    pub against: Option<String>,
+    pub diff: Option<OwnersDiff>,
+}
+schema!(CodeownersJson {
+    window: TimeWindow,
+    rules: Vec<OwnerRule>,
+    output: Option<String>,
+    against: Option<String>,
+    diff: Option<OwnersDiff>,
+});
+
+impl super::Runnable for Codeowners {
+    fn run(self, g: &Global) -> Result<()> {
+        if self.threads > 0 {
+            let pool = rayon::ThreadPoolBuilder::new()
+                .num_threads(self.threads)
+                .build()?;
+            pool.install(|| self.report(g))
+        } else {
+            self.report(g)
+        }
+    }
+}
+
+impl Codeowners {
+    fn report(&self, g: &Global) -> Result<()> {
+        if !(0.0..=1.0).contains(&self.min_share) {
+            bail!("--min-share must be between 0 and 1");
+        }
+        let repo = RepoExt::open(&self.path, &g.repo)?;
+        let opts = OwnersOpts {
+            scan: ScanOpts {
+                all: self.all,
+                extensions: g.extensions.clone(),
+                include_ext: self.include_ext.clone(),
+                min_total: self.min_total,
+            },
+            grouping: if self.glob.is_empty() {
+                Grouping::Dirs(self.depth)
+            } else {
+                Grouping::Patterns(self.glob.clone())
+            },
+            top: self.top,
+            min_share: self.min_share,
+            half_life_days: self.half_life_days,
+            inactive_days: self.inactive_days,
+        };
+        let ownership = codeowners::collect(&repo, &opts)?;
+        let rules = ownership.suggest(&opts)?;
+
+        let (against, diff) = if self.diff {
+            let (name, text) = match &self.against {
+                Some(f) => (
+                    f.clone(),
+                    std::fs::read_to_string(f).with_context(|| format!("reading {f}"))?,
+                ),
+                None => codeowners::read_committed(&repo)?,
+            };
+            let existing = CodeOwners::parse(&text).with_context(|| format!("parsing {name}"))?;
+            let diff = ownership.diff(&repo, &rules, &existing, &self.aliases()?, &opts)?;
+            (Some(name), Some(diff))
+        } else {
+            (None, None)
+        };
+
+        if let Some(out) = &self.output {
+            let header = self.header(&repo);
+            std::fs::write(out, render::render(&rules, &header))
+                .with_context(|| format!("writing {out}"))?;
+        }
+
+        if g.format.uses_rows() {
+            match &diff {
+                Some(d) => print_diff_rows(d, g),
+                None => {
+                    let mut rows = Rows::new(&["pattern", "owner", "share", "lines"])
+                        .titled(format!("Suggested code owners ({})", repo.window()))
+                        .typed(&["share", "lines"]);
+                    for r in &rules {
+                        for c in &r.owners {
+                            rows.push([
+                                r.pattern.clone(),
+                                c.author.clone(),
+                                g.format.ratio(c.share),
+                                c.lines.to_string(),
+                            ]);
+                        }
+                    }
+                    rows.print(g.format);
+                }
+            }
+        } else if g.json || self.json {
+            let data = CodeownersJson {
+                window: *repo.window(),
+                rules,
+                output: self.output.clone(),
+                against,
+                diff,
+            };
+            json::print(g, "codeowners", Some(&repo), self, &data)?;
+        } else if let Some(d) = &diff {
+            print_diff(d, against.as_deref().unwrap_or_default());
+        } else if let Some(out) = &self.output {
+            println!("📝 CODEOWNERS written to {out} ({} rules)", rules.len());
+        } else {
+            print!("{}", render::render(&rules, &self.header(&repo)));
+        }
+        Ok(())
+    }
+
+    /// Comment lines on top of the generated file.
+    fn header(&self, repo: &RepoExt) -> Vec<String> {
+        let tip = repo.tip().to_string();
+        let mut h = vec![
+            format!(
+                "Suggested by gitrivia from blame at {} ({}).",
+                &tip[..tip.len().min(12)],
+                repo.window()
+            ),
+            format!(
+                "Up to {} owners per rule with at least {:.0}% of its lines,",
+                self.top,
+                self.min_share * 100.0
+            ),
+        ];
+        h.push(if self.half_life_days > 0.0 {
+            format!(
+                "lines weighted by age (half-life {} days), inactive authors left out.",
+                self.half_life_days
+            )
+        } else {
+            "inactive authors left out.".to_string()
+        });
+        h
+    }
+
+    /// `--alias` as lower-cased handle → email.
+    fn aliases(&self) -> Result<HashMap<String, String>> {
+        self.alias
+            .iter()
+            .map(|a| {
+                let (h, e) = a
+                    .split_once('=')
+                    .with_context(|| format!("expected HANDLE=EMAIL, got '{a}'"))?;
+                Ok((h.trim_start_matches('@').to_lowercase(), e.to_string()))
+            })
+            .collect()
+    }
+}
+
+fn print_diff(d: &OwnersDiff, against: &str) {
+    let changed: Vec<_> = d
+        .rules
+        .iter()
+        .filter(|r| !r.added.is_empty() || !r.removed.is_empty())
+        .collect();
+    println!("🧭 Suggested owners vs {against}");
+    if changed.is_empty() {
+        println!("All {} rules agree with the suggestion.", d.rules.len());
+    } else {
+        let mut t = Table::new();
+        t.load_preset(UTF8_HORIZONTAL_ONLY)
+            .set_header(vec!["Pattern", "Current", "Add", "Remove"]);
+        for r in &changed {
+            t.add_row(vec![
+                r.pattern.clone(),
+                r.current.join(" "),
+                r.added.join(" "),
+                r.removed.join(" "),
+            ]);
+        }
+        println!("{t}");
+        println!(
+            "{} of {} rules differ from the suggestion.",
+            changed.len(),
+            d.rules.len()
+        );
+    }
+
+    println!();
+    if d.flags.is_empty() {
+        println!("✅ No owners who left or never touched their paths.");
+        return;
+    }
+    let mut t = Table::new();
+    t.load_preset(UTF8_HORIZONTAL_ONLY).set_header(vec![
+        "Line",
+        "Pattern",
+        "Owner",
+        "Issue",
+        "Last commit",
+    ]);
+    for f in &d.flags {
+        t.add_row(vec![
+            f.line.to_string(),
+            f.pattern.clone(),
+            f.owner.clone(),
+            issue_text(f.issue).to_string(),
+            f.last_commit.map(fmt_date).unwrap_or_default(),
+        ]);
+    }
+    println!("⚠️  Owner issues");
+    println!("{t}");
+}
+
+fn issue_text(issue: codeowners::Issue) -> &'static str {
+    match issue {
+        codeowners::Issue::Left => "left",
+        codeowners::Issue::Unknown => "not a contributor",
+        codeowners::Issue::Untouched => "never touched these paths",
+        codeowners::Issue::NoFiles => "pattern matches no file",
+    }
+}
+
+/// One row per added or removed owner and per flagged owner.
+fn print_diff_rows(d: &OwnersDiff, g: &Global) {
+    let mut rows = Rows::new(&["pattern", "line", "change", "owner", "last_commit"])
+        .titled("CODEOWNERS diff")
+        .typed(&["line"]);
+    for r in &d.rules {
+        for (change, owners) in [("add", &r.added), ("remove", &r.removed)] {
+            for o in owners {
+                rows.push([
+                    r.pattern.clone(),
+                    String::new(),
+                    change.to_string(),
+                    o.clone(),
+                    String::new(),
+                ]);
+            }
+        }
+    }
+    for f in &d.flags {
+        rows.push([
+            f.pattern.clone(),
+            f.line.to_string(),
+            f.issue.as_str().to_string(),
+            f.owner.clone(),
+            f.last_commit.map(fmt_date).unwrap_or_default(),
+        ]);
+    }
+    rows.print(g.format);
+}
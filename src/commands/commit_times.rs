@@ -1,12 +1,15 @@
 use crate::commands::Global;
-use crate::domain::{git::RepoExt, times};
+use crate::domain::{git::RepoExt, times, window::TimeWindow};
+use crate::presentation::{flat::Rows, json, schema::schema};
 use anyhow::Result;
 use clap::Args;
+use serde::Serialize;
+use std::collections::BTreeMap;
 
 /// Aggregate commit counts into hourly buckets for each author.
 ///
 /// Reveals work patterns or time‑zone differences within the team.
-#[derive(Debug, Args)]
+#[derive(Debug, Args, Serialize)]
 pub struct CommitTimes {
     /// Path to the Git repository to analyse.
     #[arg(short, long, default_value = ".")]
@@ -17,12 +20,37 @@ pub struct CommitTimes {
     pub json: bool,
 }
 
+/// `commit-times --json` payload: author → hour bucket → commits.
+#[derive(Debug, Serialize)]
+pub struct CommitTimesJson {
+    pub window: TimeWindow,
+    pub authors: BTreeMap<String, BTreeMap<String, usize>>,
+}
+schema!(CommitTimesJson {
+    window: TimeWindow,
+    authors: BTreeMap<String, BTreeMap<String, usize>>,
+});
+
 impl super::Runnable for CommitTimes {
     fn run(self, g: &Global) -> Result<()> {
-        let repo = RepoExt::open(&self.path)?;
-        let map = times::commit_times(repo.repo())?;
-        if g.json || self.json {
-            println!("{}", serde_json::to_string_pretty(&map)?);
+        let repo = RepoExt::open(&self.path, &g.repo)?;
+        let map = times::commit_times(&repo)?;
+        if g.format.uses_rows() {
+            let mut rows = Rows::new(&["author", "bucket", "commits"])
+                .titled("Commit times")
+                .typed(&["commits"]);
+            for (author, buckets) in map {
+                for (label, count) in buckets {
+                    rows.push([author.clone(), label, count.to_string()]);
+                }
+            }
+            rows.print(g.format);
+        } else if g.json || self.json {
+            let data = CommitTimesJson {
+                window: *repo.window(),
+                authors: map,
+            };
+            json::print(g, "commit-times", Some(&repo), &self, &data)?;
         } else {
             for (email, buckets) in map {
                 println!("{email}");
@@ -0,0 +1,339 @@
+use anyhow::{bail, Result};
+use chrono::{Duration, Local};
+use clap::Args;
+use comfy_table::{presets::UTF8_HORIZONTAL_ONLY, Table};
+use serde::Serialize;
+
+use crate::commands::Global;
+use crate::domain::{
+    bus_factor::ScanOpts,
+    compare::{self, Comparison, RankDelta},
+    git::{RepoExt, RepoOptions},
+    trend::Period,
+    window::TimeWindow,
+};
+use crate::presentation::{flat::Rows, json, schema::schema};
+use crate::utils::fmt_credit;
+
+/// Compare two periods or two revision ranges side by side.
+///
+/// Runs the `stats` summary, author ranking and churn ranking for a base
+/// and a current side and reports what changed: metric deltas, new and
+/// departed contributors, and paths entering or leaving the churn top-N.
+/// Without options, compares this quarter so far with the last one.
+#[derive(Debug, Args, Serialize)]
+pub struct Compare {
+    /// Path to the Git repository.
+    #[arg(short, long, default_value = ".")]
+    pub path: String,
+
+    /// Compare the current calendar period (up to `--until` or now) with
+    /// the previous one.
+    #[arg(long, value_parser = ["week", "month", "quarter", "year"])]
+    pub by: Option<String>,
+
+    /// Base window as `SINCE..UNTIL` (either end may be empty), e.g.
+    /// `2025-01-01..2025-03-31`.
+    #[arg(long, value_name = "SINCE..UNTIL")]
+    pub base: Option<String>,
+
+    /// Current window as `SINCE..UNTIL`.
+    #[arg(long, value_name = "SINCE..UNTIL")]
+    pub current: Option<String>,
+
+    /// Base revision or range (repeatable), e.g. `v1.0` or `v0.9..v1.0`.
+    #[arg(long, value_name = "REV")]
+    pub base_rev: Vec<String>,
+
+    /// Current revision or range (repeatable), e.g. `v1.0..v2.0`.
+    #[arg(long, value_name = "REV")]
+    pub current_rev: Vec<String>,
+
+    /// Size of the author and churn rankings.
+    #[arg(long, default_value = "10")]
+    pub top: usize,
+
+    /// Include all files in churn, even those normally filtered out.
+    #[arg(long)]
+    pub all: bool,
+
+    /// Additional file extensions to include in churn (comma‑separated).
+    #[arg(long, value_delimiter = ',')]
+    pub include_ext: Vec<String>,
+
+    /// Emit JSON even when the global flag is not set.
+    #[arg(long)]
+    pub json: bool,
+}
+
+/// `compare --json` payload.
+#[derive(Debug, Serialize)]
+pub struct CompareJson {
+    pub base: SideJson,
+    pub current: SideJson,
+    pub top: usize,
+    pub comparison: Comparison,
+}
+schema!(CompareJson {
+    base: SideJson,
+    current: SideJson,
+    top: usize,
+    comparison: Comparison,
+});
+
+/// What one side covered.
+#[derive(Debug, Serialize)]
+pub struct SideJson {
+    /// The side's window, or its first → last commit when open-ended.
+    pub window: TimeWindow,
+    /// `--base-rev` / `--current-rev` (empty = the global selection).
+    pub revs: Vec<String>,
+}
+schema!(SideJson {
+    window: TimeWindow,
+    revs: Vec<String>,
+});
+
+impl super::Runnable for Compare {
+    fn run(self, g: &Global) -> Result<()> {
+        let (base_opts, cur_opts) = self.sides(&g.repo)?;
+        let scan = ScanOpts {
+            all: self.all,
+            extensions: g.extensions.clone(),
+            include_ext: self.include_ext.clone(),
+            min_total: 1,
+        };
+        let base_repo = RepoExt::open(&self.path, &base_opts)?;
+        let cur_repo = RepoExt::open(&self.path, &cur_opts)?;
+        let base = compare::collect_side(&base_repo, &scan)?;
+        let current = compare::collect_side(&cur_repo, &scan)?;
+        let cmp = compare::compare(&base, &current, self.top);
+
+        let label = |o: &RepoOptions, span: &TimeWindow| {
+            if o.revs.revs.is_empty() {
+                span.to_string()
+            } else {
+                format!("{} ({span})", o.revs.revs.join(" "))
+            }
+        };
+        let (base_label, cur_label) = (
+            label(&base_opts, &base.span),
+            label(&cur_opts, &current.span),
+        );
+
+        if g.format.uses_rows() {
+            let mut rows = Rows::new(&["section", "name", "base", "current", "delta"])
+                .titled(format!("Compare: {base_label} vs {cur_label}"))
+                .typed(&["base", "current", "delta"]);
+            let f = g.format;
+            for m in &cmp.metrics {
+                rows.push([
+                    "metric",
+                    m.metric,
+                    &f.float(m.base, 2),
+                    &f.float(m.current, 2),
+                    &f.float(m.delta, 2),
+                ]);
+            }
+            for c in &cmp.new_contributors {
+                rows.push(["new_contributor", &c.author, "", &f.float(c.commits, 1), ""]);
+            }
+            for c in &cmp.departed_contributors {
+                rows.push([
+                    "departed_contributor",
+                    &c.author,
+                    &f.float(c.commits, 1),
+                    "",
+                    "",
+                ]);
+            }
+            let rank = |r: Option<usize>| r.map_or(String::new(), |r| r.to_string());
+            for a in &cmp.top_authors {
+                rows.push([
+                    "author_rank",
+                    &a.name,
+                    &rank(a.base_rank),
+                    &a.rank.to_string(),
+                    &rank_move(a),
+                ]);
+            }
+            for a in &cmp.churn_top {
+                rows.push([
+                    "churn_rank",
+                    &a.name,
+                    &rank(a.base_rank),
+                    &a.rank.to_string(),
+                    &rank_move(a),
+                ]);
+            }
+            for p in &cmp.churn_entered {
+                rows.push(["churn_entered", p.as_str(), "", "", ""]);
+            }
+            for p in &cmp.churn_left {
+                rows.push(["churn_left", p.as_str(), "", "", ""]);
+            }
+            rows.print(g.format);
+            return Ok(());
+        }
+
+        if g.json || self.json {
+            let side = |o: &RepoOptions, window: TimeWindow| SideJson {
+                window,
+                revs: o.revs.revs.clone(),
+            };
+            let data = CompareJson {
+                base: side(&base_opts, base.span),
+                current: side(&cur_opts, current.span),
+                top: self.top,
+                comparison: cmp,
+            };
+            return json::print(g, "compare", Some(&cur_repo), &self, &data);
+        }
+
+        println!("🔍 Compare");
+        println!("  Base:    {base_label}");
+        println!("  Current: {cur_label}");
+
+        let mut t = Table::new();
+        t.load_preset(UTF8_HORIZONTAL_ONLY)
+            .set_header(vec!["Metric", "Base", "Current", "Δ"]);
+        for m in &cmp.metrics {
+            t.add_row(vec![
+                m.metric.replace('_', " "),
+                fmt_metric(m.base),
+                fmt_metric(m.current),
+                format!("{} {}", arrow(m.delta), fmt_metric(m.delta.abs())),
+            ]);
+        }
+        println!("\n📊 Metrics\n{t}");
+
+        let names = |v: &[compare::Contributor]| {
+            v.iter()
+                .map(|c| format!("  {} ({} commits)", c.author, fmt_credit(c.commits)))
+                .collect::<Vec<_>>()
+        };
+        println!("\n👋 New contributors ({}):", cmp.new_contributors.len());
+        for line in names(&cmp.new_contributors) {
+            println!("{line}");
+        }
+        println!(
+            "\n🚪 Departed contributors ({}):",
+            cmp.departed_contributors.len()
+        );
+        for line in names(&cmp.departed_contributors) {
+            println!("{line}");
+        }
+
+        println!("\n🏆 Top {} authors", self.top);
+        println!(
+            "{}",
+            rank_table("Author", "Commits", &cmp.top_authors, fmt_credit)
+        );
+        println!("\n♨️  Top {} churn", self.top);
+        println!(
+            "{}",
+            rank_table("Path", "Churn", &cmp.churn_top, |x| format!("{x:.2}"))
+        );
+        if !cmp.churn_left.is_empty() {
+            println!("  Left the top {}: {}", self.top, cmp.churn_left.join(", "));
+        }
+        Ok(())
+    }
+}
+
+impl Compare {
+    /// Repository options for the base and current sides.
+    fn sides(&self, global: &RepoOptions) -> Result<(RepoOptions, RepoOptions)> {
+        let explicit = self.base.is_some()
+            || self.current.is_some()
+            || !self.base_rev.is_empty()
+            || !self.current_rev.is_empty();
+        if explicit && self.by.is_some() {
+            bail!("--by cannot be combined with --base/--current or --base-rev/--current-rev");
+        }
+
+        let mut base = global.clone();
+        let mut current = global.clone();
+        if explicit {
+            if let Some(w) = &self.base {
+                base.window = parse_range(w)?;
+            }
+            if let Some(w) = &self.current {
+                current.window = parse_range(w)?;
+            }
+            if !self.base_rev.is_empty() {
+                base.revs.revs = self.base_rev.clone();
+            }
+            if !self.current_rev.is_empty() {
+                current.revs.revs = self.current_rev.clone();
+            }
+            return Ok((base, current));
+        }
+
+        let period = Period::parse(self.by.as_deref().unwrap_or("quarter"))?;
+        let anchor = global.window.until.unwrap_or_else(Local::now).date_naive();
+        let cur_start = period.start(anchor);
+        let base_start = period.start(cur_start - Duration::days(1));
+        let day = |d: chrono::NaiveDate| d.format("%Y-%m-%d").to_string();
+        current.window = TimeWindow::parse(Some(&day(cur_start)), Some(&day(anchor)), None)?;
+        base.window = TimeWindow::parse(
+            Some(&day(base_start)),
+            Some(&day(cur_start - Duration::days(1))),
+            None,
+        )?;
+        Ok((base, current))
+    }
+}
+
+/// `SINCE..UNTIL` with either end optional.
+fn parse_range(s: &str) -> Result<TimeWindow> {
+    let Some((since, until)) = s.split_once("..") else {
+        bail!("expected SINCE..UNTIL, got '{s}'");
+    };
+    let opt = |p: &str| Some(p.trim()).filter(|p| !p.is_empty()).map(str::to_string);
+    TimeWindow::parse(opt(since).as_deref(), opt(until).as_deref(), None)
+}
+
+fn arrow(delta: f64) -> &'static str {
+    if delta > 1e-9 {
+        "↑"
+    } else if delta < -1e-9 {
+        "↓"
+    } else {
+        "→"
+    }
+}
+
+fn fmt_metric(x: f64) -> String {
+    if x.fract() == 0.0 {
+        format!("{x:.0}")
+    } else {
+        format!("{x:.2}")
+    }
+}
+
+/// `↑2`, `↓1`, `=` or `new` relative to the base rank.
+fn rank_move(r: &RankDelta) -> String {
+    match r.base_rank {
+        None => "new".into(),
+        Some(b) if b == r.rank => "=".into(),
+        Some(b) if b > r.rank => format!("↑{}", b - r.rank),
+        Some(b) => format!("↓{}", r.rank - b),
+    }
+}
+
+fn rank_table(what: &str, unit: &str, rows: &[RankDelta], value: fn(f64) -> String) -> Table {
+    let mut t = Table::new();
+    t.load_preset(UTF8_HORIZONTAL_ONLY)
+        .set_header(vec!["#", what, unit, "Base", "Move"]);
+    for r in rows {
+        t.add_row(vec![
+            r.rank.to_string(),
+            r.name.clone(),
+            value(r.value),
+            r.base_rank.map_or("—".into(), |_| value(r.base_value)),
+            rank_move(r),
+        ]);
+    }
+    t
+}
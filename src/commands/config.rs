@@ -0,0 +1,201 @@
+use anyhow::Result;
+use clap::{ArgAction, Args, CommandFactory, Subcommand};
+use serde::Serialize;
+use std::collections::BTreeMap;
+use std::path::PathBuf;
+
+use crate::{
+    cli::Cli,
+    commands::Global,
+    config as cfg,
+    domain::git::RepoExt,
+    presentation::{flat::Rows, json, schema::schema},
+};
+
+/// Inspect the shared configuration.
+///
+/// Settings come from the user config (`~/.config/gitrivia/config.toml`)
+/// and `.gitrivia.toml` at the repository root; command-line flags
+/// override both.
+#[derive(Debug, Args, Serialize)]
+pub struct Config {
+    /// Path inside the repository whose `.gitrivia.toml` to read.
+    #[arg(short, long, default_value = ".")]
+    pub path: String,
+
+    /// Emit JSON even when the global flag is not set.
+    #[arg(long, global = true)]
+    pub json: bool,
+
+    #[command(subcommand)]
+    pub action: Option<ConfigAction>,
+}
+
+#[derive(Debug, Clone, Copy, Subcommand, Serialize)]
+#[serde(rename_all = "lowercase")]
+pub enum ConfigAction {
+    /// Print the effective merged settings for every command (default)
+    Show,
+}
+
+/// One effective setting and where it came from.
+#[derive(Debug, Serialize)]
+pub struct Effective {
+    /// Comma-joined values; `null` when unset.
+    pub value: Option<String>,
+    /// `default`, `command line` or the config file it came from.
+    pub source: String,
+}
+schema!(Effective {
+    value: Option<String>,
+    source: String,
+});
+
+/// `config show --json` payload.
+#[derive(Debug, Serialize)]
+pub struct ConfigJson {
+    /// Files looked at, in load order.
+    pub files: Vec<ConfigFile>,
+    /// Section → key → effective setting.
+    pub sections: BTreeMap<String, BTreeMap<String, Effective>>,
+}
+schema!(ConfigJson {
+    files: Vec<ConfigFile>,
+    sections: BTreeMap<String, BTreeMap<String, Effective>>,
+});
+
+#[derive(Debug, Serialize)]
+pub struct ConfigFile {
+    pub path: PathBuf,
+    pub found: bool,
+}
+schema!(ConfigFile {
+    path: PathBuf,
+    found: bool,
+});
+
+impl super::Runnable for Config {
+    fn run(self, g: &Global) -> Result<()> {
+        let ConfigAction::Show = self.action.unwrap_or(ConfigAction::Show);
+        let cli = Cli::command();
+        let user_args: Vec<String> = std::env::args().collect();
+
+        let mut sections: BTreeMap<String, BTreeMap<String, Effective>> = BTreeMap::new();
+        sections.insert(
+            cfg::GLOBAL.to_string(),
+            effective(g, &cli, cfg::GLOBAL, &user_args)?,
+        );
+        for sub in cli.get_subcommands() {
+            let name = sub.get_name();
+            if name == "config" || name == "help" {
+                continue;
+            }
+            sections.insert(name.to_string(), effective(g, &cli, name, &[])?);
+        }
+
+        if g.format.uses_rows() {
+            let mut rows =
+                Rows::new(&["section", "key", "value", "source"]).titled("Configuration");
+            for (name, keys) in &sections {
+                for (k, e) in keys {
+                    rows.push([name, k, e.value.as_deref().unwrap_or(""), &e.source]);
+                }
+            }
+            rows.print(g.format);
+            return Ok(());
+        }
+
+        if g.json || self.json {
+            let data = ConfigJson {
+                files: g
+                    .config
+                    .files
+                    .iter()
+                    .map(|(path, found)| ConfigFile {
+                        path: path.clone(),
+                        found: *found,
+                    })
+                    .collect(),
+                sections,
+            };
+            let repo = RepoExt::open(&self.path, &g.repo).ok();
+            return json::print(g, "config", repo.as_ref(), &self, &data);
+        }
+
+        println!("⚙️  Config files (later wins):");
+        for (p, found) in &g.config.files {
+            let note = if *found { "" } else { "  (not found)" };
+            println!("  {}{note}", p.display());
+        }
+        for (name, keys) in sections {
+            println!("\n[{name}]");
+            for (k, e) in keys {
+                let value = e.value.as_deref().unwrap_or("(unset)");
+                println!("  {:<18} = {:<24} # {}", k, value, e.source);
+            }
+        }
+        Ok(())
+    }
+}
+
+/// Effective values of one section: config settings applied over the
+/// defaults (plus, for `[global]`, this invocation's own flags).
+fn effective(
+    g: &Global,
+    cli: &clap::Command,
+    section: &str,
+    user_args: &[String],
+) -> Result<BTreeMap<String, Effective>> {
+    let cli = g.config.apply(cli.clone());
+    let Some(cmd) = cfg::section_command(&cli, section) else {
+        return Ok(BTreeMap::new());
+    };
+    // Don't fail on required flags (e.g. author-activity --author).
+    let mut cmd = cmd
+        .clone()
+        .subcommand_required(false)
+        .arg_required_else_help(false);
+    let ids: Vec<_> = cmd.get_arguments().map(|a| a.get_id().clone()).collect();
+    for id in ids {
+        cmd = cmd.mut_arg(id, |a| a.required(false));
+    }
+
+    let mut argv: Vec<std::ffi::OsString> = vec![cmd.get_name().into()];
+    if section == cfg::GLOBAL {
+        // Only this invocation's global flags (up to the subcommand).
+        argv.extend(
+            user_args
+                .iter()
+                .skip(1)
+                .take_while(|a| *a != "config")
+                .map(Into::into),
+        );
+    }
+    let matches = cmd.clone().try_get_matches_from(argv)?;
+    let from_config = g.config.sections.get(section);
+
+    let mut out = BTreeMap::new();
+    for arg in cmd.get_arguments() {
+        let Some(long) = arg.get_long() else { continue };
+        if matches!(arg.get_action(), ArgAction::Help | ArgAction::Version) {
+            continue;
+        }
+        let id = arg.get_id().as_str();
+        let value = matches.get_raw(id).map(|vals| {
+            vals.map(|v| v.to_string_lossy().into_owned())
+                .collect::<Vec<_>>()
+                .join(",")
+        });
+        let typed = user_args.iter().any(|a| {
+            a.strip_prefix("--")
+                .is_some_and(|f| f.split('=').next() == Some(long))
+        });
+        let source = match from_config.and_then(|s| s.get(long)) {
+            _ if section == cfg::GLOBAL && typed => "command line".to_string(),
+            Some(s) => s.source.display().to_string(),
+            None => "default".to_string(),
+        };
+        out.insert(long.to_string(), Effective { value, source });
+    }
+    Ok(out)
+}
@@ -0,0 +1,214 @@
+use anyhow::{bail, Context, Result};
+use clap::Args;
+use serde::Serialize;
+use std::collections::BTreeMap;
+use std::path::Path;
+
+use crate::commands::Global;
+use crate::domain::{
+    bus_factor::ScanOpts,
+    git::{RepoExt, RepoOptions},
+    metrics::{self, Family, MetricsOpts},
+    window::TimeWindow,
+};
+use crate::presentation::{flat::Rows, json, prometheus, schema::schema};
+
+/// Export repository health metrics in the Prometheus text format.
+///
+/// Emits the `stats` summary metrics, bus-factor counts and the top churn
+/// values as gauges labelled with the repository and branch, for a
+/// scrape job or node_exporter's textfile collector.
+#[derive(Debug, Args, Serialize)]
+pub struct ExportMetrics {
+    /// Path to the Git repository.
+    #[arg(short, long, default_value = ".")]
+    pub path: String,
+
+    /// Write to this file instead of standard output. The file is replaced
+    /// atomically, as the textfile collector expects (use a `.prom`
+    /// extension there).
+    #[arg(short, long, value_name = "FILE")]
+    pub output: Option<String>,
+
+    /// `repository` label (default: the work tree's directory name).
+    #[arg(long, value_name = "NAME")]
+    pub repository: Option<String>,
+
+    /// `branch` label (default: the current branch, or the selected
+    /// revisions).
+    #[arg(long, value_name = "NAME")]
+    pub branch: Option<String>,
+
+    /// Extra label on every sample (repeatable), e.g. `team=infra`.
+    #[arg(long, value_name = "KEY=VALUE")]
+    pub label: Vec<String>,
+
+    /// Churn window in days. Ignored when the global `--since` / `--last`
+    /// is given.
+    #[arg(long, default_value = "90")]
+    pub window_days: i64,
+
+    /// Files exported with their churn.
+    #[arg(long, default_value = "10")]
+    pub top: usize,
+
+    /// Ownership share above which a file counts towards
+    /// `gitrivia_bus_factor_files_above_threshold`.
+    #[arg(long, default_value = "0.75")]
+    pub threshold: f64,
+
+    /// Estimate ownership from commit touches instead of blame.
+    #[arg(long)]
+    pub fast: bool,
+
+    /// When in fast mode, inspect at most this many recent commits.
+    /// Use 0 to scan the entire history.
+    #[arg(long, default_value = "5000")]
+    pub max_commits: usize,
+
+    /// Ignore files with fewer lines/touches than this for ownership.
+    #[arg(long, default_value = "10")]
+    pub min_total: usize,
+
+    /// Include all files, even those normally filtered out.
+    #[arg(long)]
+    pub all: bool,
+
+    /// Additional file extensions to include (comma‑separated).
+    #[arg(long, value_delimiter = ',')]
+    pub include_ext: Vec<String>,
+
+    /// Emit the metrics as JSON instead of the text format.
+    #[arg(long)]
+    pub json: bool,
+}
+
+/// `export-metrics --json` payload.
+#[derive(Debug, Serialize)]
+pub struct ExportMetricsJson {
+    pub window: TimeWindow,
+    /// Labels carried by every sample.
+    pub labels: BTreeMap<String, String>,
+    /// File the text format was written to, if any.
+    pub output: Option<String>,
+    pub metrics: Vec<Family>,
+}
+schema!(ExportMetricsJson {
+    window: TimeWindow,
+    labels: BTreeMap<String, String>,
+    output: Option<String>,
+    metrics: Vec<Family>,
+});
+
+impl super::Runnable for ExportMetrics {
+    fn run(self, g: &Global) -> Result<()> {
+        if !(0.0..=1.0).contains(&self.threshold) {
+            bail!("--threshold must be between 0 and 1");
+        }
+        let repo = RepoExt::open(&self.path, &g.repo)?;
+        let labels = self.labels(&repo, &g.repo)?;
+        let scan_opts = |min_total| ScanOpts {
+            all: self.all,
+            extensions: g.extensions.clone(),
+            include_ext: self.include_ext.clone(),
+            min_total,
+        };
+        let opts = MetricsOpts {
+            window_days: self.window_days,
+            fast: self.fast,
+            max_commits: (self.max_commits > 0).then_some(self.max_commits),
+            threshold: self.threshold,
+            ownership: scan_opts(self.min_total),
+            churn: scan_opts(1),
+            top: self.top,
+        };
+        let families = metrics::collect(&repo, &opts)?.families();
+
+        if let Some(out) = &self.output {
+            write_atomic(Path::new(out), &prometheus::render(&families, &labels))?;
+        }
+
+        if g.format.uses_rows() {
+            let mut rows = Rows::new(&["metric", "labels", "value"])
+                .titled(format!("Metrics ({})", repo.window()))
+                .typed(&["value"]);
+            for f in &families {
+                for s in &f.samples {
+                    let l: Vec<_> = labels
+                        .iter()
+                        .chain(&s.labels)
+                        .map(|(k, v)| format!("{k}={v}"))
+                        .collect();
+                    rows.push([f.name.clone(), l.join(","), s.value.to_string()]);
+                }
+            }
+            rows.print(g.format);
+        } else if g.json || self.json {
+            let data = ExportMetricsJson {
+                window: *repo.window(),
+                labels,
+                output: self.output.clone(),
+                metrics: families,
+            };
+            json::print(g, "export-metrics", Some(&repo), &self, &data)?;
+        } else if let Some(out) = &self.output {
+            println!("📈 Metrics written to {out}");
+        } else {
+            print!("{}", prometheus::render(&families, &labels));
+        }
+        Ok(())
+    }
+}
+
+impl ExportMetrics {
+    /// `repository`, `branch` and `--label` labels.
+    fn labels(&self, repo: &RepoExt, opts: &RepoOptions) -> Result<BTreeMap<String, String>> {
+        let branch = self.branch.clone().unwrap_or_else(|| {
+            let sel = &opts.revs;
+            if !sel.revs.is_empty() {
+                sel.revs.join(" ")
+            } else if sel.all_refs {
+                "all".into()
+            } else if let Some(glob) = &sel.branches {
+                glob.clone()
+            } else {
+                repo.repo()
+                    .head()
+                    .ok()
+                    .and_then(|h| h.shorthand().map(str::to_string))
+                    .unwrap_or_else(|| "HEAD".into())
+            }
+        });
+        let mut labels = BTreeMap::from([
+            (
+                "repository".to_string(),
+                self.repository.clone().unwrap_or_else(|| repo.name()),
+            ),
+            ("branch".to_string(), branch),
+        ]);
+        for l in &self.label {
+            let Some((k, v)) = l.split_once('=') else {
+                bail!("expected KEY=VALUE, got '{l}'");
+            };
+            let valid = k.starts_with(|c: char| c.is_ascii_alphabetic() || c == '_')
+                && k.chars().all(|c| c.is_ascii_alphanumeric() || c == '_');
+            if !valid || k.starts_with("__") {
+                bail!("invalid label name '{k}'");
+            }
+            if matches!(k, "path" | "threshold") {
+                bail!("label '{k}' is used by the metrics themselves");
+            }
+            labels.insert(k.to_string(), v.to_string());
+        }
+        Ok(labels)
+    }
+}
+
+/// Write next to `path` and rename over it, so a collector never reads a
+/// half-written file.
+fn write_atomic(path: &Path, contents: &str) -> Result<()> {
+    let name = path.file_name().context("output path has no file name")?;
+    let tmp = path.with_file_name(format!(".{}.tmp", name.to_string_lossy()));
+    std::fs::write(&tmp, contents).with_context(|| format!("writing {}", tmp.display()))?;
+    std::fs::rename(&tmp, path).with_context(|| format!("writing {}", path.display()))
+}
@@ -1,13 +1,17 @@
 use crate::commands::Global;
-use crate::domain::{files, git::RepoExt};
+use crate::domain::{files, git::RepoExt, window::TimeWindow};
+use crate::presentation::{flat::Rows, json, schema::schema};
+use crate::utils::fmt_credit;
 use anyhow::Result;
 use clap::Args;
+use serde::Serialize;
+use std::collections::BTreeMap;
 
 /// Show per‑file commit counts broken down by author.
 ///
 /// Useful for visualising file ownership or building heatmaps for
 /// routing pull requests.
-#[derive(Debug, Args)]
+#[derive(Debug, Args, Serialize)]
 pub struct FileContributions {
     /// Path to the Git repository to inspect.
     #[arg(short, long, default_value = ".")]
@@ -18,17 +22,43 @@ pub struct FileContributions {
     pub json: bool,
 }
 
+/// `file-contributions --json` payload: file → author → commit credit.
+#[derive(Debug, Serialize)]
+pub struct FileContributionsJson {
+    pub window: TimeWindow,
+    pub files: BTreeMap<String, BTreeMap<String, f64>>,
+}
+schema!(FileContributionsJson {
+    window: TimeWindow,
+    files: BTreeMap<String, BTreeMap<String, f64>>,
+});
+
 impl super::Runnable for FileContributions {
     fn run(self, g: &Global) -> Result<()> {
-        let repo = RepoExt::open(&self.path)?;
-        let map = files::file_contributions(repo.repo())?;
-        if g.json || self.json {
-            println!("{}", serde_json::to_string_pretty(&map)?);
+        let repo = RepoExt::open(&self.path, &g.repo)?;
+        let map = files::file_contributions(&repo)?;
+        if g.format.uses_rows() {
+            let mut rows = Rows::new(&["file", "author", "commits"])
+                .titled("File contributions")
+                .typed(&["commits"])
+                .writer(g.format)?;
+            for (file, authors) in map {
+                for (author, count) in authors {
+                    rows.push([file.as_str(), &author, &count.to_string()])?;
+                }
+            }
+            rows.finish()?;
+        } else if g.json || self.json {
+            let data = FileContributionsJson {
+                window: *repo.window(),
+                files: map,
+            };
+            json::print(g, "file-contributions", Some(&repo), &self, &data)?;
         } else {
             for (file, authors) in map {
                 println!("{file}");
                 for (author, count) in authors {
-                    println!("  {:<30} {} commits", author, count);
+                    println!("  {:<30} {} commits", author, fmt_credit(count));
                 }
             }
         }
@@ -1,15 +1,19 @@
 use crate::commands::Global;
 use crate::{
-    domain::{firsts, git::RepoExt},
+    domain::{firsts, git::RepoExt, window::TimeWindow},
+    presentation::{flat::Rows, json, schema::schema},
     utils::fmt_date,
 };
 use anyhow::Result;
+use chrono::NaiveDate;
 use clap::Args;
+use serde::Serialize;
+use std::collections::BTreeMap;
 
 /// List when each contributor made their first commit.
 ///
 /// Helps gauge longevity of contributors or find long‑term maintainers.
-#[derive(Debug, Args)]
+#[derive(Debug, Args, Serialize)]
 pub struct FirstCommits {
     /// Path to the Git repository to analyse.
     #[arg(short, long, default_value = ".")]
@@ -20,16 +24,33 @@ pub struct FirstCommits {
     pub json: bool,
 }
 
+/// `first-commits --json` payload: author → date of their first commit.
+#[derive(Debug, Serialize)]
+pub struct FirstCommitsJson {
+    pub window: TimeWindow,
+    pub authors: BTreeMap<String, NaiveDate>,
+}
+schema!(FirstCommitsJson {
+    window: TimeWindow,
+    authors: BTreeMap<String, NaiveDate>,
+});
+
 impl super::Runnable for FirstCommits {
     fn run(self, g: &Global) -> Result<()> {
-        let repo = RepoExt::open(&self.path)?;
-        let map = firsts::first_commits(repo.repo())?;
-        if g.json || self.json {
-            let as_str = map
-                .into_iter()
-                .map(|(k, v)| (k, fmt_date(v)))
-                .collect::<std::collections::BTreeMap<_, _>>();
-            println!("{}", serde_json::to_string_pretty(&as_str)?);
+        let repo = RepoExt::open(&self.path, &g.repo)?;
+        let map = firsts::first_commits(&repo)?;
+        if g.format.uses_rows() {
+            let mut rows = Rows::new(&["author", "first_commit"]).titled("First commits");
+            for (author, dt) in map {
+                rows.push([author, fmt_date(dt)]);
+            }
+            rows.print(g.format);
+        } else if g.json || self.json {
+            let data = FirstCommitsJson {
+                window: *repo.window(),
+                authors: map.into_iter().map(|(k, v)| (k, v.date_naive())).collect(),
+            };
+            json::print(g, "first-commits", Some(&repo), &self, &data)?;
         } else {
             for (email, dt) in map {
                 println!("{:<30} {}", email, fmt_date(dt));
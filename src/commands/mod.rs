@@ -1,35 +1,131 @@
 use anyhow::Result;
+use std::sync::{Arc, Mutex};
+
+use crate::cli::Cli;
+use crate::config::Config as Settings;
+use crate::domain::{
+    blame::{BlameSettings, MoveDetection},
+    bots::BotMode,
+    git::RepoOptions,
+    identity::CoauthorCredit,
+    revs::RevSelection,
+    window::TimeWindow,
+};
+use crate::presentation::flat::Format;
 
 #[derive(Debug, Clone, Default)]
 pub struct Global {
     /// Global JSON output toggle (overrides per-command flags)
     pub json: bool,
+    /// Output format (`json` is set whenever this is `Json`)
+    pub format: Format,
     /// Global default for “descending” ordering in commands that support it
     pub desc: bool,
+    /// How every command opens and interprets the repository
+    pub repo: RepoOptions,
+    /// Extension allow-list for bus-factor/churn (empty = built-in list)
+    pub extensions: Vec<String>,
+    /// Settings loaded from config files (already applied to the arguments)
+    pub config: Settings,
+    /// Effective global flags, echoed in every JSON envelope
+    pub options: serde_json::Value,
+    /// When set, JSON payloads are stored here instead of printed (`serve`)
+    pub capture: Option<Arc<Mutex<String>>>,
+}
+
+impl Global {
+    /// Settings shared by every command, from the parsed global flags.
+    pub fn new(cli: &Cli, config: Settings) -> Result<Self> {
+        let format = match cli.format.as_deref() {
+            Some(f) => Format::parse(f)?,
+            None if cli.json => Format::Json,
+            None => Format::Table,
+        };
+        let mut repo = RepoOptions::default()
+            .revs(RevSelection {
+                revs: cli.revs.clone(),
+                all_refs: cli.all_refs,
+                branches: cli.branches.clone(),
+                exclude: cli.exclude.clone(),
+            })
+            .window(TimeWindow::parse(
+                cli.since.as_deref(),
+                cli.until.as_deref(),
+                cli.last.as_deref(),
+            )?)
+            .pathspec(cli.pathspec.clone())
+            .skip_generated(cli.skip_generated)
+            .no_cache(cli.no_cache)
+            .coauthor_credit(CoauthorCredit::parse(&cli.coauthor_credit)?)
+            .bots(BotMode::parse(&cli.bots)?)
+            .bot_patterns(cli.bot_patterns.clone())
+            .blame(BlameSettings {
+                ignore_revs: cli.ignore_revs.clone(),
+                ignore_revs_files: cli.ignore_revs_files.clone(),
+                no_default_ignore_revs: cli.no_ignore_revs,
+                ignore_whitespace: cli.ignore_whitespace,
+                moves: MoveDetection::parse(&cli.detect_moves)?,
+            });
+        repo.mailmap = cli.mailmap.clone();
+        Ok(Self {
+            json: format == Format::Json,
+            format,
+            desc: cli.desc,
+            repo,
+            extensions: cli.extensions.clone(),
+            config,
+            options: serde_json::to_value(cli)?,
+            capture: None,
+        })
+    }
 }
 
 pub trait Runnable {
     fn run(self, g: &Global) -> Result<()>;
 }
 
+pub mod age;
 pub mod author_activity;
 pub mod blame_summary;
 pub mod bus_factor;
+pub mod cache;
 pub mod churn;
+pub mod codeowners;
 pub mod commit_times;
+pub mod compare;
+pub mod config;
+pub mod export_metrics;
 pub mod file_contributions;
 pub mod first_commits;
+pub mod report;
+pub mod schema;
+pub mod serve;
 pub mod stats;
+pub mod suggest_reviewers;
 pub mod top_authors;
 pub mod top_coauthors;
+pub mod trend;
+pub mod tui;
 
+pub use age::Age;
 pub use author_activity::AuthorActivity;
 pub use blame_summary::BlameSummary;
 pub use bus_factor::BusFactor;
+pub use cache::Cache;
 pub use churn::Churn;
+pub use codeowners::Codeowners;
 pub use commit_times::CommitTimes;
+pub use compare::Compare;
+pub use config::Config;
+pub use export_metrics::ExportMetrics;
 pub use file_contributions::FileContributions;
 pub use first_commits::FirstCommits;
+pub use report::Report;
+pub use schema::Schema;
+pub use serve::Serve;
 pub use stats::Stats;
+pub use suggest_reviewers::SuggestReviewers;
 pub use top_authors::TopAuthors;
 pub use top_coauthors::TopCoauthors;
+pub use trend::Trend;
+pub use tui::Tui;
@@ -0,0 +1,160 @@
+use anyhow::{Context, Result};
+use clap::Args;
+use serde::Serialize;
+
+use crate::commands::Global;
+use crate::domain::{
+    bus_factor::ScanOpts,
+    git::RepoExt,
+    report::{self, ReportOpts},
+    window::TimeWindow,
+};
+use crate::presentation::{html, json, schema::schema};
+
+/// Write a single-file HTML dashboard of the repository.
+///
+/// Combines the repo summary, commits over time, weekday activity, file
+/// ownership, churn hotspots, commit times, first commits and co-author
+/// pairs. Charts are inline SVG and the page loads nothing from the
+/// network, so it opens offline.
+#[derive(Debug, Args, Serialize)]
+pub struct Report {
+    /// Path to the Git repository.
+    #[arg(short, long, default_value = ".")]
+    pub path: String,
+
+    /// HTML file to write (`-` for standard output).
+    #[arg(short, long, default_value = "gitrivia-report.html")]
+    pub output: String,
+
+    /// Churn window in days. Ignored when the global `--since` / `--last`
+    /// is given.
+    #[arg(long, default_value = "90")]
+    pub window_days: i64,
+
+    /// Estimate ownership from commit touches instead of blame.
+    #[arg(long)]
+    pub fast: bool,
+
+    /// When in fast mode, inspect at most this many recent commits.
+    /// Use 0 to scan the entire history.
+    #[arg(long, default_value = "5000")]
+    pub max_commits: usize,
+
+    /// Include all files, even those normally filtered out.
+    #[arg(long)]
+    pub all: bool,
+
+    /// Additional file extensions to include (comma‑separated).
+    #[arg(long, value_delimiter = ',')]
+    pub include_ext: Vec<String>,
+
+    /// Ignore files with fewer lines/touches than this for ownership.
+    #[arg(long, default_value = "10")]
+    pub min_total: usize,
+
+    /// Rows per table and chart.
+    #[arg(long, default_value = "15")]
+    pub limit: usize,
+
+    /// Print a JSON note about the written file instead of a message.
+    #[arg(long)]
+    pub json: bool,
+}
+
+/// `report --json` payload.
+#[derive(Debug, Serialize)]
+pub struct ReportJson {
+    pub window: TimeWindow,
+    /// The HTML file written.
+    pub output: String,
+}
+schema!(ReportJson {
+    window: TimeWindow,
+    output: String,
+});
+
+impl super::Runnable for Report {
+    fn run(self, g: &Global) -> Result<()> {
+        let repo = RepoExt::open(&self.path, &g.repo)?;
+        let scan_opts = |min_total| ScanOpts {
+            all: self.all,
+            extensions: g.extensions.clone(),
+            include_ext: self.include_ext.clone(),
+            min_total,
+        };
+        let opts = ReportOpts {
+            window_days: self.window_days,
+            fast: self.fast,
+            max_commits: (self.max_commits > 0).then_some(self.max_commits),
+            ownership: scan_opts(self.min_total),
+            churn: scan_opts(1),
+        };
+        let data = report::collect(&repo, &opts)?;
+
+        let mode = if self.fast {
+            "FAST (touches)"
+        } else {
+            "Blame (lines)"
+        };
+        let page = html::report(&data, &repo.name(), repo.window(), mode, self.limit);
+
+        if self.output == "-" {
+            print!("{page}");
+            return Ok(());
+        }
+        std::fs::write(&self.output, page).with_context(|| format!("writing {}", self.output))?;
+        if g.json || self.json {
+            let data = ReportJson {
+                window: *repo.window(),
+                output: self.output.clone(),
+            };
+            json::print(g, "report", Some(&repo), &self, &data)?;
+        } else {
+            println!("📄 Report written to {}", self.output);
+        }
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::cli::Cli;
+    use crate::commands::Global;
+    use crate::config::Config;
+    use clap::Parser;
+    use git2::{Repository, Signature};
+
+    #[test]
+    fn writes_a_self_contained_page() {
+        let dir = tempfile::TempDir::new().unwrap();
+        let repo = Repository::init(dir.path()).unwrap();
+        std::fs::write(dir.path().join("a&b.rs"), "fn a() {}\n").unwrap();
+        let mut index = repo.index().unwrap();
+        index.add_path(std::path::Path::new("a&b.rs")).unwrap();
+        let tree = repo.find_tree(index.write_tree().unwrap()).unwrap();
+        let sig = Signature::now("Ann", "ann@x.io").unwrap();
+        repo.commit(Some("HEAD"), &sig, &sig, "add", &tree, &[])
+            .unwrap();
+
+        let path = dir.path().to_str().unwrap();
+        let output = dir.path().join("report.html");
+        let args = [
+            "gitrivia",
+            "report",
+            "--path",
+            path,
+            "--min-total=1",
+            "--output",
+            output.to_str().unwrap(),
+        ];
+        let cli = Cli::try_parse_from(args).unwrap();
+        let g = Global::new(&cli, Config::default()).unwrap();
+        cli.command.run(&g).unwrap();
+
+        let page = std::fs::read_to_string(output).unwrap();
+        assert!(page.starts_with("<!DOCTYPE html>"));
+        assert!(page.contains("a&amp;b.rs"));
+        assert!(!page.contains("src=") && !page.contains("href="));
+    }
+}
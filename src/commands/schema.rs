@@ -0,0 +1,105 @@
+use anyhow::{bail, Context, Result};
+use clap::{Args, Command, CommandFactory};
+use serde::Serialize;
+use serde_json::{json, Map, Value};
+use std::path::PathBuf;
+
+use crate::cli::Cli;
+use crate::commands::{
+    age::AgeJson, author_activity::AuthorActivityJson, blame_summary::BlameSummaryJson,
+    bus_factor::BusFactorJson, cache::CacheJson, churn::ChurnJson, codeowners::CodeownersJson,
+    commit_times::CommitTimesJson, compare::CompareJson, config::ConfigJson,
+    export_metrics::ExportMetricsJson, file_contributions::FileContributionsJson,
+    first_commits::FirstCommitsJson, report::ReportJson, stats::StatsJson,
+    suggest_reviewers::SuggestReviewersJson, top_authors::TopAuthorsJson,
+    top_coauthors::TopCoauthorsJson, trend::TrendJson, Global,
+};
+use crate::presentation::json::{self, SCHEMA_VERSION};
+
+/// Print JSON Schema documents for the `--json` output of each command.
+///
+/// Every JSON payload carries `schema_version`, `command`, `repo`,
+/// `head_oid`, `generated_at` and the effective `options` next to the
+/// command's own fields; the schemas describe all of them so dashboards
+/// can validate what they ingest.
+#[derive(Debug, Args, Serialize)]
+pub struct Schema {
+    /// Only this command's schema (e.g. `bus-factor`); all when omitted.
+    pub command: Option<String>,
+
+    /// Write `<command>.schema.json` files into this directory instead of
+    /// printing.
+    #[arg(short, long, value_name = "DIR")]
+    pub out_dir: Option<PathBuf>,
+}
+
+/// Schema builder for a command's payload, if it has JSON output.
+fn payload(name: &str) -> Option<fn(&Command, &Command) -> Value> {
+    Some(match name {
+        "stats" => json::document::<StatsJson>,
+        "top-authors" => json::document::<TopAuthorsJson>,
+        "author-activity" => json::document::<AuthorActivityJson>,
+        "blame-summary" => json::document::<BlameSummaryJson>,
+        "file-contributions" => json::document::<FileContributionsJson>,
+        "commit-times" => json::document::<CommitTimesJson>,
+        "first-commits" => json::document::<FirstCommitsJson>,
+        "top-coauthors" => json::document::<TopCoauthorsJson>,
+        "bus-factor" => json::document::<BusFactorJson>,
+        "churn" => json::document::<ChurnJson>,
+        "age" => json::document::<AgeJson>,
+        "codeowners" => json::document::<CodeownersJson>,
+        "suggest-reviewers" => json::document::<SuggestReviewersJson>,
+        "trend" => json::document::<TrendJson>,
+        "compare" => json::document::<CompareJson>,
+        "report" => json::document::<ReportJson>,
+        "export-metrics" => json::document::<ExportMetricsJson>,
+        "cache" => json::document::<CacheJson>,
+        "config" => json::document::<ConfigJson>,
+        _ => return None,
+    })
+}
+
+impl super::Runnable for Schema {
+    fn run(self, _g: &Global) -> Result<()> {
+        let cli = Cli::command();
+        let mut docs: Vec<(String, Value)> = cli
+            .get_subcommands()
+            .filter_map(|cmd| {
+                Some((
+                    cmd.get_name().to_string(),
+                    payload(cmd.get_name())?(&cli, cmd),
+                ))
+            })
+            .collect();
+        if let Some(name) = &self.command {
+            docs.retain(|(n, _)| n == name);
+            if docs.is_empty() {
+                bail!("no JSON output schema for '{name}'");
+            }
+        }
+
+        if let Some(dir) = &self.out_dir {
+            std::fs::create_dir_all(dir).with_context(|| format!("creating {}", dir.display()))?;
+            for (name, doc) in &docs {
+                let file = dir.join(format!("{name}.schema.json"));
+                std::fs::write(&file, serde_json::to_string_pretty(doc)? + "\n")
+                    .with_context(|| format!("writing {}", file.display()))?;
+                println!("📐 {}", file.display());
+            }
+            return Ok(());
+        }
+
+        let out = match docs.pop() {
+            Some((_, doc)) if self.command.is_some() => doc,
+            last => {
+                docs.extend(last);
+                json!({
+                    "schema_version": SCHEMA_VERSION,
+                    "commands": docs.into_iter().collect::<Map<_, _>>(),
+                })
+            }
+        };
+        println!("{}", serde_json::to_string_pretty(&out)?);
+        Ok(())
+    }
+}
@@ -0,0 +1,551 @@
+use anyhow::{anyhow, bail, Context, Result};
+use clap::{Args, Command, CommandFactory};
+use git2::Repository;
+use serde::Serialize;
+use serde_json::json;
+use std::collections::HashMap;
+use std::ffi::OsString;
+use std::sync::{Arc, Mutex};
+use std::time::Instant;
+use tiny_http::{Header, Method, Request, Response, Server};
+
+use crate::cli::Cli;
+use crate::commands::Global;
+use crate::config as cfg;
+use crate::domain::blame::IGNORE_REVS_FILE;
+use crate::domain::revs::ResolvedRevs;
+
+/// Analyses served as `GET /<command>`; each answers with the command's
+/// `--json` payload.
+const ENDPOINTS: [&str; 16] = [
+    "stats",
+    "top-authors",
+    "author-activity",
+    "blame-summary",
+    "file-contributions",
+    "commit-times",
+    "first-commits",
+    "top-coauthors",
+    "bus-factor",
+    "churn",
+    "age",
+    "codeowners",
+    "suggest-reviewers",
+    "trend",
+    "compare",
+    "export-metrics",
+];
+
+/// Options a client may not set: the repository is the server's, output
+/// is always JSON, and nothing is read from standard input.
+const RESERVED: [&str; 6] = ["path", "json", "format", "output", "no-config", "patch"];
+
+/// Cached responses are dropped beyond this many.
+const CACHE_LIMIT: usize = 256;
+
+/// Serve the analyses over HTTP as a local JSON API.
+///
+/// `GET /stats`, `/bus-factor`, `/churn`, `/blame-summary?file=src/x.rs`
+/// and the other analysis commands return exactly what `--json` prints.
+/// Query parameters are the command-line options without dashes
+/// (`?since=2025-01-01&by=dir&threshold=0.8`) or positional arguments by
+/// name (`/suggest-reviewers?range=main...feature`); flags take
+/// `true`/`false`.
+/// Global flags given to `serve` apply to every request unless the query
+/// sets them. Results are cached until the commits they were computed
+/// from, or the mailmap and blame ignore lists, change.
+#[derive(Debug, Args, Serialize)]
+pub struct Serve {
+    /// Path to the Git repository.
+    #[arg(short, long, default_value = ".")]
+    pub path: String,
+
+    /// Address to listen on. Keep it local: the API has no authentication.
+    #[arg(short, long, default_value = "127.0.0.1:8787")]
+    pub addr: String,
+}
+
+impl super::Runnable for Serve {
+    fn run(self, g: &Global) -> Result<()> {
+        let repo = Repository::discover(&self.path)
+            .with_context(|| format!("cannot open repo at {}", self.path))?;
+        let server =
+            Server::http(&self.addr).map_err(|e| anyhow!("cannot listen on {}: {e}", self.addr))?;
+        println!(
+            "🌐 Serving {} on http://{}/",
+            self.path,
+            server.server_addr()
+        );
+
+        let g = &Global {
+            config: server_config(g),
+            ..g.clone()
+        };
+        let mut cache = Cache::default();
+        for request in server.incoming_requests() {
+            let started = Instant::now();
+            let (method, url) = (request.method().clone(), request.url().to_string());
+            let (reply, note) = self.handle(g, &repo, &mut cache, &request);
+            eprintln!(
+                "{method} {url} → {} ({note}, {} ms)",
+                reply.status,
+                started.elapsed().as_millis()
+            );
+            reply.send(request);
+        }
+        Ok(())
+    }
+}
+
+/// Responses by the revisions they were computed from, the side files
+/// read alongside them (see [`side_files`]) and the argument list, so a
+/// moved branch or `HEAD` or an edited `.mailmap` is a miss.
+#[derive(Default)]
+struct Cache {
+    entries: HashMap<(ResolvedRevs, u64, Vec<OsString>), String>,
+}
+
+struct Reply {
+    status: u16,
+    body: String,
+    tip: Option<git2::Oid>,
+    cached: bool,
+}
+
+impl Reply {
+    fn error(status: u16, message: impl std::fmt::Display) -> Self {
+        Self {
+            status,
+            body: json!({ "error": message.to_string() }).to_string(),
+            tip: None,
+            cached: false,
+        }
+    }
+
+    fn send(self, request: Request) {
+        let mut response = Response::from_string(self.body)
+            .with_status_code(self.status)
+            .with_header(header("Content-Type", "application/json"));
+        if let Some(tip) = self.tip {
+            response.add_header(header("X-Gitrivia-Tip", &tip.to_string()));
+            response.add_header(header(
+                "X-Gitrivia-Cache",
+                if self.cached { "hit" } else { "miss" },
+            ));
+        }
+        // The client may have gone away; nothing to do about it.
+        let _ = request.respond(response);
+    }
+}
+
+fn header(name: &str, value: &str) -> Header {
+    Header::from_bytes(name, value).expect("valid header")
+}
+
+impl Serve {
+    fn handle(
+        &self,
+        g: &Global,
+        repo: &Repository,
+        cache: &mut Cache,
+        request: &Request,
+    ) -> (Reply, &'static str) {
+        if *request.method() != Method::Get {
+            return (Reply::error(405, "only GET is supported"), "error");
+        }
+        let (route, query) = request.url().split_once('?').unwrap_or((request.url(), ""));
+        let endpoint = route.trim_matches('/');
+        if endpoint.is_empty() {
+            let index = Reply {
+                status: 200,
+                body: json!({ "endpoints": ENDPOINTS.map(|e| format!("/{e}")) }).to_string(),
+                tip: None,
+                cached: false,
+            };
+            return (index, "index");
+        }
+        if !ENDPOINTS.contains(&endpoint) {
+            return (
+                Reply::error(404, format!("no endpoint /{endpoint}")),
+                "error",
+            );
+        }
+
+        let args = match self.args(g, endpoint, query) {
+            Ok(args) => args,
+            Err(e) => return (Reply::error(400, format!("{e:#}")), "error"),
+        };
+        let (cli, request) = match parse(g, &args) {
+            Ok(parsed) => parsed,
+            Err(e) => return (Reply::error(400, format!("{e:#}")), "error"),
+        };
+        let revs = match ResolvedRevs::resolve(repo, &request.repo.revs) {
+            Ok(revs) => revs,
+            Err(e) => return (Reply::error(400, format!("{e:#}")), "error"),
+        };
+        let tip = Some(revs.tip);
+        let key = (revs, side_files(repo, &request), args);
+        if let Some(body) = cache.entries.get(&key) {
+            let reply = Reply {
+                status: 200,
+                body: body.clone(),
+                tip,
+                cached: true,
+            };
+            return (reply, "cached");
+        }
+
+        match run(cli, &request) {
+            Ok(body) => {
+                if cache.entries.len() >= CACHE_LIMIT {
+                    cache.entries.clear();
+                }
+                cache.entries.insert(key, body.clone());
+                let reply = Reply {
+                    status: 200,
+                    body,
+                    tip,
+                    cached: false,
+                };
+                (reply, "computed")
+            }
+            Err(e) => (Reply::error(400, format!("{e:#}")), "error"),
+        }
+    }
+
+    /// Command line equivalent to `GET /<endpoint>?<query>`, config
+    /// settings included.
+    fn args(&self, g: &Global, endpoint: &str, query: &str) -> Result<Vec<OsString>> {
+        let cli = Cli::command();
+        let cmd = cli
+            .find_subcommand(endpoint)
+            .expect("endpoint is a command");
+        let mut args: Vec<OsString> = vec!["gitrivia".into(), "--json".into(), endpoint.into()];
+        args.push(format!("--path={}", self.path).into());
+        let mut positional = Vec::new();
+        for (key, value) in form_urlencoded::parse(query.as_bytes()) {
+            let key = key.replace('_', "-");
+            if RESERVED.contains(&key.as_str()) {
+                bail!("parameter '{key}' cannot be set over HTTP");
+            }
+            if cmd
+                .get_positionals()
+                .any(|a| a.get_id().as_str().replace('_', "-") == key)
+            {
+                positional.push(value.into_owned().into());
+                continue;
+            }
+            let (section, arg) = match long_arg(cmd, &key) {
+                Some(a) => (endpoint, a),
+                None => (
+                    cfg::GLOBAL,
+                    long_arg(&cli, &key)
+                        .with_context(|| format!("unknown parameter '{key}' for /{endpoint}"))?,
+                ),
+            };
+            if arg.get_action().takes_values() {
+                args.push(format!("--{key}={value}").into());
+                continue;
+            }
+            let on = match value.as_ref() {
+                "" | "true" | "1" => true,
+                "false" | "0" => false,
+                v => bail!("parameter '{key}' is a flag: expected true or false, got '{v}'"),
+            };
+            // A flag the config sets takes a value, so `false` can undo it.
+            if g.config.get(section, &key).is_some() {
+                args.push(format!("--{key}={on}").into());
+            } else if on {
+                args.push(format!("--{key}").into());
+            }
+        }
+        if !positional.is_empty() {
+            args.push("--".into());
+            args.extend(positional);
+        }
+        Ok(args)
+    }
+}
+
+/// The server's config with its own effective global flags layered on
+/// top, so they become every request's defaults; a query parameter still
+/// overrides them like a command-line flag overrides a config file.
+fn server_config(g: &Global) -> cfg::Config {
+    let mut config = g.config.clone();
+    let cli = Cli::command();
+    for arg in cli.get_arguments().filter(|a| a.is_global_set()) {
+        let Some(long) = arg.get_long() else { continue };
+        if RESERVED.contains(&long) {
+            continue;
+        }
+        let Some(value) = g.options.get(arg.get_id().as_str()).and_then(setting) else {
+            continue;
+        };
+        let setting = cfg::Setting {
+            value,
+            source: "serve command line".into(),
+        };
+        config
+            .sections
+            .entry(cfg::GLOBAL.into())
+            .or_default()
+            .insert(long.to_string(), setting);
+    }
+    config
+}
+
+/// An effective option as a config value; `None` for unset ones.
+fn setting(v: &serde_json::Value) -> Option<cfg::Value> {
+    use serde_json::Value as J;
+    Some(match v {
+        J::Bool(true) => cfg::Value::Bool(true),
+        J::String(s) => cfg::Value::Str(s.clone()),
+        J::Number(n) => match n.as_i64() {
+            Some(i) => cfg::Value::Int(i),
+            None => cfg::Value::Float(n.as_f64()?),
+        },
+        J::Array(xs) if !xs.is_empty() => {
+            cfg::Value::Array(xs.iter().filter_map(setting).collect())
+        }
+        _ => return None,
+    })
+}
+
+/// Fingerprint of the files besides the commits that change results: the
+/// mailmaps and the blame ignore lists in the work tree.
+fn side_files(repo: &Repository, request: &Global) -> u64 {
+    use std::hash::{Hash, Hasher};
+    let config = repo.config().ok();
+    let configured = |key: &str| config.as_ref().and_then(|c| c.get_path(key).ok());
+    let in_workdir = |p: std::path::PathBuf| match repo.workdir() {
+        Some(w) if p.is_relative() => w.join(p),
+        _ => p,
+    };
+    let mut files: Vec<std::path::PathBuf> = [".mailmap", IGNORE_REVS_FILE]
+        .into_iter()
+        .map(|f| in_workdir(f.into()))
+        .collect();
+    files.extend(configured("mailmap.file"));
+    files.extend(configured("blame.ignoreRevsFile").map(in_workdir));
+    files.extend(request.repo.mailmap.clone());
+    files.extend(request.repo.blame.ignore_revs_files.iter().cloned());
+
+    let mut hasher = std::collections::hash_map::DefaultHasher::new();
+    for file in files {
+        file.hash(&mut hasher);
+        std::fs::read(&file).ok().hash(&mut hasher);
+    }
+    hasher.finish()
+}
+
+fn long_arg<'a>(cmd: &'a Command, long: &str) -> Option<&'a clap::Arg> {
+    cmd.get_arguments().find(|a| a.get_long() == Some(long))
+}
+
+/// Parse one request's command line into the command and its settings,
+/// with JSON output captured instead of printed.
+fn parse(g: &Global, args: &[OsString]) -> Result<(Cli, Global)> {
+    // A window set over HTTP replaces the server's, however it is spelled.
+    let mut config = g.config.clone();
+    for (set, other) in [("since", "last"), ("last", "since")] {
+        let given = format!("--{set}=");
+        if args.iter().any(|a| a.to_string_lossy().starts_with(&given)) {
+            if let Some(global) = config.sections.get_mut(cfg::GLOBAL) {
+                global.remove(other);
+            }
+        }
+    }
+    let cli = Cli::parse_with(&config, args).map_err(|e| {
+        // clap's message without the usage hint meant for terminals.
+        let msg = e.to_string();
+        let lines: Vec<_> = msg
+            .lines()
+            .take_while(|l| !l.is_empty())
+            .map(str::trim)
+            .collect();
+        anyhow!("{}", lines.join(" ").trim_start_matches("error: "))
+    })?;
+    let mut request = Global::new(&cli, config)?;
+    request.capture = Some(Arc::new(Mutex::new(String::new())));
+    Ok((cli, request))
+}
+
+/// Run one parsed command and return the JSON it would print.
+fn run(cli: Cli, request: &Global) -> Result<String> {
+    cli.command.run(request)?;
+    let capture = request.capture.as_ref().expect("set by parse");
+    let body = std::mem::take(&mut *capture.lock().expect("capture"));
+    Ok(body)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::config::{Config, Setting, Value};
+    use clap::Parser;
+
+    fn serve(path: &str) -> Serve {
+        Serve {
+            path: path.into(),
+            addr: "127.0.0.1:0".into(),
+        }
+    }
+
+    fn global(config: Config) -> Global {
+        let cli = Cli::try_parse_from(["gitrivia", "serve"]).unwrap();
+        Global::new(&cli, config).unwrap()
+    }
+
+    fn argv(args: Result<Vec<OsString>>) -> Vec<String> {
+        let args = args.unwrap();
+        args.iter()
+            .map(|a| a.to_string_lossy().into_owned())
+            .collect()
+    }
+
+    #[test]
+    fn query_parameters_become_options() {
+        let g = global(Config::default());
+        let s = serve(".");
+        assert_eq!(
+            argv(s.args(&g, "churn", "last=30d&limit=5&all=true&include_ext=lua")),
+            [
+                "gitrivia",
+                "--json",
+                "churn",
+                "--path=.",
+                "--last=30d",
+                "--limit=5",
+                "--all",
+                "--include-ext=lua"
+            ]
+        );
+        assert_eq!(
+            argv(s.args(&g, "churn", "all=false")),
+            ["gitrivia", "--json", "churn", "--path=."]
+        );
+        assert_eq!(
+            argv(s.args(&g, "suggest-reviewers", "range=main...feature&limit=3")),
+            [
+                "gitrivia",
+                "--json",
+                "suggest-reviewers",
+                "--path=.",
+                "--limit=3",
+                "--",
+                "main...feature"
+            ]
+        );
+    }
+
+    #[test]
+    fn bad_parameters_are_rejected() {
+        let g = global(Config::default());
+        let s = serve(".");
+        for (endpoint, query) in [
+            ("stats", "path=/etc"),
+            ("stats", "format=csv"),
+            ("stats", "nope=1"),
+            ("churn", "all=maybe"),
+        ] {
+            assert!(s.args(&g, endpoint, query).is_err(), "{query}");
+        }
+    }
+
+    #[test]
+    fn flags_set_in_config_can_be_turned_off() {
+        let mut config = Config::default();
+        config.sections.entry("churn".into()).or_default().insert(
+            "all".into(),
+            Setting {
+                value: Value::Bool(true),
+                source: "test.toml".into(),
+            },
+        );
+        let g = global(config);
+        let args = argv(serve(".").args(&g, "churn", "all=false"));
+        assert_eq!(args.last().unwrap(), "--all=false");
+    }
+
+    /// A repository with one empty commit by Ann.
+    fn repo() -> (tempfile::TempDir, git2::Oid) {
+        let dir = tempfile::TempDir::new().unwrap();
+        let repo = Repository::init(dir.path()).unwrap();
+        let sig = git2::Signature::now("Ann", "ann@x.io").unwrap();
+        let tree = repo
+            .find_tree(repo.index().unwrap().write_tree().unwrap())
+            .unwrap();
+        let tip = repo
+            .commit(Some("HEAD"), &sig, &sig, "root", &tree, &[])
+            .unwrap();
+        (dir, tip)
+    }
+
+    /// `GET /<endpoint>?<query>` against `dir`, as JSON.
+    fn get(g: &Global, dir: &tempfile::TempDir, endpoint: &str, query: &str) -> serde_json::Value {
+        let args = serve(dir.path().to_str().unwrap())
+            .args(g, endpoint, query)
+            .unwrap();
+        let (cli, request) = parse(g, &args).unwrap();
+        serde_json::from_str(&run(cli, &request).unwrap()).unwrap()
+    }
+
+    #[test]
+    fn requests_run_the_command_and_capture_its_json() {
+        let (dir, tip) = repo();
+        let body = get(&global(Config::default()), &dir, "stats", "");
+        assert_eq!(body["command"], "stats");
+        assert_eq!(body["head_oid"], tip.to_string());
+        assert_eq!(body["summary"]["total_commits"], 1);
+    }
+
+    #[test]
+    fn server_flags_are_request_defaults() {
+        let (dir, _) = repo();
+        let cli = Cli::try_parse_from([
+            "gitrivia",
+            "--bots=exclude",
+            "--all-refs",
+            "--pathspec=src,!*.lock",
+            "--since=2020-01-01",
+            "serve",
+        ])
+        .unwrap();
+        let server = Global::new(&cli, Config::default()).unwrap();
+        let g = Global {
+            config: server_config(&server),
+            ..server
+        };
+
+        let options = &get(&g, &dir, "stats", "")["options"]["global"];
+        assert_eq!(options["bots"], "exclude");
+        assert_eq!(options["all_refs"], true);
+        assert_eq!(options["pathspec"], serde_json::json!(["src", "!*.lock"]));
+        assert_eq!(options["since"], "2020-01-01");
+
+        let query = "bots=separate&all_refs=false&pathspec=lib&last=30d";
+        let options = &get(&g, &dir, "stats", query)["options"]["global"];
+        assert_eq!(options["bots"], "separate");
+        assert_eq!(options["all_refs"], false);
+        assert_eq!(options["pathspec"], serde_json::json!(["lib"]));
+        assert_eq!(options["last"], "30d");
+        assert!(
+            options["since"].is_null(),
+            "--last replaces the server's --since"
+        );
+    }
+
+    #[test]
+    fn side_files_are_part_of_the_cache_key() {
+        let (dir, _) = repo();
+        let git = Repository::open(dir.path()).unwrap();
+        let g = global(Config::default());
+        let before = side_files(&git, &g);
+        assert_eq!(side_files(&git, &g), before);
+        std::fs::write(dir.path().join(".mailmap"), "Ann <ann@x.io> <old@x.io>\n").unwrap();
+        let mailmap = side_files(&git, &g);
+        assert_ne!(mailmap, before);
+        std::fs::write(dir.path().join(IGNORE_REVS_FILE), "# none\n").unwrap();
+        assert_ne!(side_files(&git, &g), mailmap);
+    }
+}
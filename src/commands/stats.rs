@@ -1,12 +1,23 @@
 use anyhow::Result;
+use chrono::NaiveDate;
 use clap::Args;
-use serde_json::json;
+use serde::Serialize;
 
 use crate::{
     commands::Global,
-    domain::{git::RepoExt, stats as d},
-    presentation::table,
-    utils::fmt_date,
+    domain::{
+        git::RepoExt,
+        stats::{self as d, PeakDay, RecentTop},
+        window::TimeWindow,
+    },
+    presentation::{
+        flat::{Format, Rows},
+        json::{self, AuthorRow, BotRow},
+        markdown,
+        schema::schema,
+        table,
+    },
+    utils::{fmt_credit, fmt_date},
 };
 
 /// Summarise repository health and highlight top contributors.
@@ -14,7 +25,7 @@ use crate::{
 /// The command scans commit history to produce high‑level metrics such as
 /// active period, momentum and merge/revert rates. It also prints the top
 /// five authors overall and for the last 12 months.
-#[derive(Debug, Args)]
+#[derive(Debug, Args, Serialize)]
 pub struct Stats {
     /// Path to the Git repository to inspect.
     /// Defaults to the current working directory.
@@ -31,88 +42,197 @@ pub struct Stats {
     /// global `--desc` setting.
     #[arg(long)]
     pub sort_desc: bool,
+
+    /// Emit JSON even when the global flag is not set.
+    #[arg(long)]
+    pub json: bool,
+}
+
+/// `stats --json` payload.
+#[derive(Debug, Serialize)]
+pub struct StatsJson {
+    pub window: TimeWindow,
+    pub summary: Summary,
+    pub top_5_authors: Vec<AuthorRow>,
+    pub top_5_authors_last_12m: Vec<AuthorRow>,
+    pub bots: Vec<BotRow>,
+}
+schema!(StatsJson {
+    window: TimeWindow,
+    summary: Summary,
+    top_5_authors: Vec<AuthorRow>,
+    top_5_authors_last_12m: Vec<AuthorRow>,
+    bots: Vec<BotRow>,
+});
+
+#[derive(Debug, Serialize)]
+pub struct Summary {
+    pub first_commit: CommitRef,
+    pub last_commit: CommitRef,
+    pub total_commits: usize,
+    pub contributors_total: usize,
+    pub active_days: i64,
+    pub avg_commits_per_day: f64,
+    pub peak_day: Option<PeakDay>,
+    pub longest_idle_gap_days: i64,
+    pub momentum_90d_pct: f64,
+    pub active_authors_last_90d: usize,
+    pub contributors: Contributors,
+    pub activity_patterns: ActivityPatterns,
+    pub merge_revert: MergeRevert,
+    pub messages: Messages,
+    pub top_recent_30d: Option<RecentTop>,
+}
+schema!(Summary {
+    first_commit: CommitRef,
+    last_commit: CommitRef,
+    total_commits: usize,
+    contributors_total: usize,
+    active_days: i64,
+    avg_commits_per_day: f64,
+    peak_day: Option<PeakDay>,
+    longest_idle_gap_days: i64,
+    momentum_90d_pct: f64,
+    active_authors_last_90d: usize,
+    contributors: Contributors,
+    activity_patterns: ActivityPatterns,
+    merge_revert: MergeRevert,
+    messages: Messages,
+    top_recent_30d: Option<RecentTop>,
+});
+
+#[derive(Debug, Serialize)]
+pub struct CommitRef {
+    pub date: NaiveDate,
+    pub author: String,
+}
+schema!(CommitRef {
+    date: NaiveDate,
+    author: String,
+});
+
+#[derive(Debug, Serialize)]
+pub struct Contributors {
+    pub drive_by_ratio_pct: f64,
+    pub core_size_80pct: usize,
+    pub concentration_hhi: f64,
+    pub concentration_gini: f64,
+}
+schema!(Contributors {
+    drive_by_ratio_pct: f64,
+    core_size_80pct: usize,
+    concentration_hhi: f64,
+    concentration_gini: f64,
+});
+
+#[derive(Debug, Serialize)]
+pub struct ActivityPatterns {
+    pub weekday_counts_mon_sun: [usize; 7],
+    pub work_hours_pct_9_18: f64,
+}
+schema!(ActivityPatterns {
+    weekday_counts_mon_sun: [usize; 7],
+    work_hours_pct_9_18: f64,
+});
+
+#[derive(Debug, Serialize)]
+pub struct MergeRevert {
+    pub merge_rate_pct: f64,
+    pub revert_rate_pct: f64,
+}
+schema!(MergeRevert {
+    merge_rate_pct: f64,
+    revert_rate_pct: f64,
+});
+
+#[derive(Debug, Serialize)]
+pub struct Messages {
+    pub median_subject_len: usize,
+    pub body_present_pct: f64,
+    pub conventional_commit_pct: f64,
+}
+schema!(Messages {
+    median_subject_len: usize,
+    body_present_pct: f64,
+    conventional_commit_pct: f64,
+});
+
+impl StatsJson {
+    fn new(scan: &d::RepoScan, window: TimeWindow) -> Self {
+        let s = &scan.summary;
+        Self {
+            window,
+            summary: Summary {
+                first_commit: CommitRef {
+                    date: s.first_date.date_naive(),
+                    author: s.first_author.clone(),
+                },
+                last_commit: CommitRef {
+                    date: s.last_date.date_naive(),
+                    author: s.last_author.clone(),
+                },
+                total_commits: s.total_commits,
+                contributors_total: s.contributors_total,
+                active_days: s.active_days,
+                avg_commits_per_day: s.avg_commits_per_day,
+                peak_day: s.peak_day,
+                longest_idle_gap_days: s.longest_idle_gap_days,
+                momentum_90d_pct: s.momentum_90d_pct,
+                active_authors_last_90d: s.active_authors_last_90d,
+                contributors: Contributors {
+                    drive_by_ratio_pct: s.drive_by_ratio,
+                    core_size_80pct: s.core_size_80pct,
+                    concentration_hhi: s.hhi,
+                    concentration_gini: s.gini,
+                },
+                activity_patterns: ActivityPatterns {
+                    weekday_counts_mon_sun: s.weekday_counts,
+                    work_hours_pct_9_18: s.work_hours_pct,
+                },
+                merge_revert: MergeRevert {
+                    merge_rate_pct: s.merge_rate,
+                    revert_rate_pct: s.revert_rate,
+                },
+                messages: Messages {
+                    median_subject_len: s.msg_median_len,
+                    body_present_pct: s.msg_body_pct,
+                    conventional_commit_pct: s.conv_commit_pct,
+                },
+                top_recent_30d: s.top_recent_30d.clone(),
+            },
+            top_5_authors: AuthorRow::top(&scan.stats, true, 5),
+            top_5_authors_last_12m: AuthorRow::top(&scan.recent12, true, 5),
+            bots: BotRow::all(&scan.stats),
+        }
+    }
 }
 
 impl super::Runnable for Stats {
     fn run(self, g: &Global) -> Result<()> {
-        let repo = RepoExt::open(&self.path)?;
-        let scan = d::scan_repo(repo.repo(), self.limit);
-
-        if g.json {
-            // Build top-5 authors sorted desc by count
-            let mut top_vec = scan
-                .stats
-                .data
-                .iter()
-                .map(|(email, m)| (email.clone(), m.count, m.first, m.last))
-                .collect::<Vec<_>>();
-            top_vec.sort_by(|a, b| b.1.cmp(&a.1));
-            let top_vec = top_vec.into_iter().take(5).map(|(email, count, first, last)| {
-                json!({"email": email, "count": count, "first": fmt_date(first), "last": fmt_date(last)})
-            }).collect::<Vec<_>>();
-
-            let mut top12 = scan
-                .recent12
-                .data
-                .iter()
-                .map(|(email, m)| (email.clone(), m.count, m.first, m.last))
-                .collect::<Vec<_>>();
-            top12.sort_by(|a, b| b.1.cmp(&a.1));
-            let top12 = top12.into_iter().take(5).map(|(email, count, first, last)| {
-                json!({"email": email, "count": count, "first": fmt_date(first), "last": fmt_date(last)})
-            }).collect::<Vec<_>>();
-
-            let s = &scan.summary;
-            let payload = json!({
-                "summary": {
-                    "first_commit": { "date": fmt_date(s.first_date), "author": s.first_author },
-                    "last_commit":  { "date": fmt_date(s.last_date),  "author": s.last_author  },
-                    "total_commits": s.total_commits,
-                    "contributors_total": s.contributors_total,
-                    "active_days": s.active_days,
-                    "avg_commits_per_day": s.avg_commits_per_day,
-                    "peak_day": s.peak_day.as_ref().map(|(d,c)| json!({"date": d.to_string(), "commits": c})),
-                    "longest_idle_gap_days": s.longest_idle_gap_days,
-                    "momentum_90d_pct": s.momentum_90d_pct,
-                    "active_authors_last_90d": s.active_authors_last_90d,
-
-                    "contributors": {
-                        "drive_by_ratio_pct": s.drive_by_ratio,
-                        "core_size_80pct": s.core_size_80pct,
-                        "concentration_hhi": s.hhi,
-                        "concentration_gini": s.gini
-                    },
-
-                    "activity_patterns": {
-                        "weekday_counts_mon_sun": s.weekday_counts,
-                        "work_hours_pct_9_18": s.work_hours_pct
-                    },
-
-                    "merge_revert": {
-                        "merge_rate_pct": s.merge_rate,
-                        "revert_rate_pct": s.revert_rate
-                    },
-
-                    "messages": {
-                        "median_subject_len": s.msg_median_len,
-                        "body_present_pct": s.msg_body_pct,
-                        "conventional_commit_pct": s.conv_commit_pct
-                    },
-
-                    "top_recent_30d": s.top_recent_30d.as_ref()
-                        .map(|(a,c)| json!({"author": a, "commits": c}))
-                },
-                    "top_5_authors_last_12m": top12,
-                    "top_5_authors": top_vec
-            });
-            println!("{}", serde_json::to_string_pretty(&payload)?);
+        let repo = RepoExt::open(&self.path, &g.repo)?;
+        let scan = d::scan_repo(&repo, self.limit)?;
+
+        if g.format == Format::Markdown {
+            print!("{}", markdown::stats_report(&scan, repo.window()));
             return Ok(());
         }
+        if g.format.uses_rows() {
+            flat_rows(&scan).print(g.format);
+            return Ok(());
+        }
+
+        if g.json || self.json {
+            let data = StatsJson::new(&scan, *repo.window());
+            return json::print(g, "stats", Some(&repo), &self, &data);
+        }
 
         // Human-friendly with quick explanations
         let s = &scan.summary;
 
         println!("✨ Repo summary");
+        if !repo.window().is_unbounded() {
+            println!("  Window:           {}", repo.window());
+        }
         println!(
             "  First commit:     {} by {}",
             fmt_date(s.first_date),
@@ -127,8 +247,8 @@ impl super::Runnable for Stats {
         println!("  Contributors:     {}", s.contributors_total);
         println!("  Active period:    {} days", s.active_days);
         println!("  Avg commits/day:  {:.2}", s.avg_commits_per_day);
-        if let Some((d, c)) = s.peak_day {
-            println!("  Peak day:         {} ({} commits)", d, c);
+        if let Some(p) = s.peak_day {
+            println!("  Peak day:         {} ({} commits)", p.date, p.commits);
         }
         println!(
             "  Longest idle gap: {} days (largest pause between commits)",
@@ -138,8 +258,12 @@ impl super::Runnable for Stats {
             "  Momentum (90d):   {:.1}% of all commits, {} authors active",
             s.momentum_90d_pct, s.active_authors_last_90d
         );
-        if let Some((a, c)) = &s.top_recent_30d {
-            println!("  Top last 30d:     {} ({} commits)", a, c);
+        if let Some(t) = &s.top_recent_30d {
+            println!(
+                "  Top last 30d:     {} ({} commits)",
+                t.author,
+                fmt_credit(t.commits)
+            );
         }
 
         println!();
@@ -189,6 +313,12 @@ impl super::Runnable for Stats {
         // Force DESC for “Top 5”
         println!("{}", table::author_stats_top(&scan.stats, true, 5));
 
+        if !scan.stats.bots.is_empty() {
+            println!();
+            println!("🤖 Bots (not counted above):");
+            println!("{}", table::bot_stats(&scan.stats));
+        }
+
         // Tiny legend
         println!("\nLegend:");
         println!(
@@ -200,3 +330,106 @@ impl super::Runnable for Stats {
         Ok(())
     }
 }
+
+/// Every figure as a `section, name, value` row; author sections list
+/// one author per row with their commit count.
+fn flat_rows(scan: &d::RepoScan) -> Rows {
+    let s = &scan.summary;
+    let mut rows = Rows::new(&["section", "name", "value"]).typed(&["value"]);
+    let mut add = |section: &str, name: &str, value: String| {
+        rows.push([section, name, &value]);
+    };
+
+    add("summary", "first_commit_date", fmt_date(s.first_date));
+    add("summary", "first_commit_author", s.first_author.clone());
+    add("summary", "last_commit_date", fmt_date(s.last_date));
+    add("summary", "last_commit_author", s.last_author.clone());
+    add("summary", "total_commits", s.total_commits.to_string());
+    add(
+        "summary",
+        "contributors_total",
+        s.contributors_total.to_string(),
+    );
+    add("summary", "active_days", s.active_days.to_string());
+    add(
+        "summary",
+        "avg_commits_per_day",
+        s.avg_commits_per_day.to_string(),
+    );
+    if let Some(p) = &s.peak_day {
+        add("summary", "peak_day_date", p.date.to_string());
+        add("summary", "peak_day_commits", p.commits.to_string());
+    }
+    add(
+        "summary",
+        "longest_idle_gap_days",
+        s.longest_idle_gap_days.to_string(),
+    );
+    add(
+        "summary",
+        "momentum_90d_pct",
+        s.momentum_90d_pct.to_string(),
+    );
+    add(
+        "summary",
+        "active_authors_last_90d",
+        s.active_authors_last_90d.to_string(),
+    );
+    if let Some(t) = &s.top_recent_30d {
+        add("summary", "top_recent_30d_author", t.author.clone());
+        add("summary", "top_recent_30d_commits", t.commits.to_string());
+    }
+
+    add(
+        "contributors",
+        "drive_by_ratio_pct",
+        s.drive_by_ratio.to_string(),
+    );
+    add(
+        "contributors",
+        "core_size_80pct",
+        s.core_size_80pct.to_string(),
+    );
+    add("contributors", "concentration_hhi", s.hhi.to_string());
+    add("contributors", "concentration_gini", s.gini.to_string());
+
+    let days = ["mon", "tue", "wed", "thu", "fri", "sat", "sun"];
+    for (day, n) in days.iter().zip(s.weekday_counts) {
+        add(
+            "activity_patterns",
+            &format!("weekday_{day}"),
+            n.to_string(),
+        );
+    }
+    add(
+        "activity_patterns",
+        "work_hours_pct_9_18",
+        s.work_hours_pct.to_string(),
+    );
+
+    add("merge_revert", "merge_rate_pct", s.merge_rate.to_string());
+    add("merge_revert", "revert_rate_pct", s.revert_rate.to_string());
+
+    add(
+        "messages",
+        "median_subject_len",
+        s.msg_median_len.to_string(),
+    );
+    add("messages", "body_present_pct", s.msg_body_pct.to_string());
+    add(
+        "messages",
+        "conventional_commit_pct",
+        s.conv_commit_pct.to_string(),
+    );
+
+    for (author, m) in scan.recent12.sorted(true).into_iter().take(5) {
+        add("top_5_authors_last_12m", author, m.count.to_string());
+    }
+    for (author, m) in scan.stats.sorted(true).into_iter().take(5) {
+        add("top_5_authors", author, m.count.to_string());
+    }
+    for (bot, m) in &scan.stats.bots {
+        add("bots", bot, m.commits.to_string());
+    }
+    rows
+}
@@ -0,0 +1,227 @@
+use anyhow::{bail, Context, Result};
+use clap::Args;
+use comfy_table::{presets::UTF8_HORIZONTAL_ONLY, Table};
+use serde::Serialize;
+use std::io::Read;
+
+use crate::commands::Global;
+use crate::domain::{
+    git::RepoExt,
+    reviewers::{self, ChangeSource, ReviewOpts, Reviewer},
+    window::TimeWindow,
+};
+use crate::presentation::{flat::Rows, json, schema::schema};
+use crate::utils::{fmt_credit, fmt_date};
+
+/// Recommend reviewers for a change.
+///
+/// Candidates are ranked by how many of the changed lines they last wrote
+/// (blame at the change's base) and how often they recently changed the
+/// same files. The change's own authors, bots and inactive contributors are
+/// left out.
+#[derive(Debug, Args, Serialize)]
+pub struct SuggestReviewers {
+    /// Path to the Git repository.
+    #[arg(short, long, default_value = ".")]
+    pub path: String,
+
+    /// Revision range (`main..feature`, `main...HEAD`) or one commit.
+    #[arg(conflicts_with_all = ["file", "patch"])]
+    pub range: Option<String>,
+
+    /// Files or directories the change touches (comma‑separated or
+    /// repeated), as they are at the tip.
+    #[arg(long, value_delimiter = ',', conflicts_with = "patch")]
+    pub file: Vec<String>,
+
+    /// Read a unified diff or `git format-patch` output from this file, or
+    /// from standard input when no file is given.
+    #[arg(long, value_name = "FILE", num_args = 0..=1, default_missing_value = "-")]
+    pub patch: Option<String>,
+
+    /// The change's author, never suggested (repeatable). Defaults to the
+    /// range's commit authors, the patch's `From:`, or your `user.email`.
+    #[arg(long, value_name = "EMAIL")]
+    pub author: Vec<String>,
+
+    /// Recent touches count over this many days. Ignored when the global
+    /// `--since` / `--last` is given.
+    #[arg(long, default_value = "90")]
+    pub window_days: i64,
+
+    /// Leave out authors without a commit in this many days. 0 keeps
+    /// everyone.
+    #[arg(long, default_value = "180")]
+    pub inactive_days: i64,
+
+    /// Weight of line ownership in the score, against recent touches (0..1).
+    #[arg(long, default_value = "0.6")]
+    pub blame_weight: f64,
+
+    /// Number of reviewers to list.
+    #[arg(long, default_value = "5")]
+    pub limit: usize,
+
+    /// Emit JSON even when the global flag is not set.
+    #[arg(long)]
+    pub json: bool,
+}
+
+/// `suggest-reviewers --json` payload.
+#[derive(Debug, Serialize)]
+pub struct SuggestReviewersJson {
+    pub window: TimeWindow,
+    /// Commit the changed lines were blamed at.
+    pub base: String,
+    /// Commits in the range (empty for files and patches).
+    pub commits: usize,
+    /// Changed files; `dir/` for directories of added files.
+    pub paths: Vec<String>,
+    /// Emails left out as the change's authors.
+    pub excluded: Vec<String>,
+    pub reviewers: Vec<Reviewer>,
+}
+schema!(SuggestReviewersJson {
+    window: TimeWindow,
+    base: String,
+    commits: usize,
+    paths: Vec<String>,
+    excluded: Vec<String>,
+    reviewers: Vec<Reviewer>,
+});
+
+impl super::Runnable for SuggestReviewers {
+    fn run(self, g: &Global) -> Result<()> {
+        if !(0.0..=1.0).contains(&self.blame_weight) {
+            bail!("--blame-weight must be between 0 and 1");
+        }
+        let repo = RepoExt::open(&self.path, &g.repo)?;
+        let source = if let Some(range) = &self.range {
+            ChangeSource::Range(range.clone())
+        } else if let Some(patch) = &self.patch {
+            let mut text = String::new();
+            if patch == "-" {
+                std::io::stdin()
+                    .read_to_string(&mut text)
+                    .context("reading the patch from standard input")?;
+            } else {
+                text =
+                    std::fs::read_to_string(patch).with_context(|| format!("reading {patch}"))?;
+            }
+            ChangeSource::Patch(text)
+        } else if !self.file.is_empty() {
+            ChangeSource::Paths(self.file.clone())
+        } else {
+            bail!("give a revision range, --file or --patch");
+        };
+        let mut change = reviewers::resolve(&repo, &source)?;
+        if change.authors.is_empty() && self.author.is_empty() {
+            // Whoever asks is most likely the author.
+            if let Ok(me) = repo
+                .repo()
+                .config()
+                .and_then(|c| c.get_string("user.email"))
+            {
+                change.authors.push(me);
+            }
+        }
+        let opts = ReviewOpts {
+            window_days: self.window_days,
+            inactive_days: self.inactive_days,
+            blame_weight: self.blame_weight,
+            exclude: self.author.clone(),
+        };
+        let mut ranked = reviewers::suggest(&repo, &change, &opts)?;
+        ranked.truncate(self.limit);
+
+        let mut excluded: Vec<String> =
+            change.authors.iter().chain(&self.author).cloned().collect();
+        excluded.sort();
+        excluded.dedup();
+
+        if g.format.uses_rows() {
+            let mut rows = Rows::new(&[
+                "reviewer",
+                "score",
+                "lines",
+                "line_share",
+                "touches",
+                "touch_share",
+                "files",
+                "last_commit",
+            ])
+            .titled(format!("Suggested reviewers ({})", repo.window()))
+            .typed(&[
+                "score",
+                "lines",
+                "line_share",
+                "touches",
+                "touch_share",
+                "files",
+            ]);
+            for r in &ranked {
+                rows.push([
+                    r.author.clone(),
+                    format!("{:.3}", r.score),
+                    r.lines.to_string(),
+                    g.format.ratio(r.line_share),
+                    fmt_credit(r.touches),
+                    g.format.ratio(r.touch_share),
+                    r.files.to_string(),
+                    r.last_commit.map(fmt_date).unwrap_or_default(),
+                ]);
+            }
+            rows.print(g.format);
+        } else if g.json || self.json {
+            let data = SuggestReviewersJson {
+                window: *repo.window(),
+                base: change.base.to_string(),
+                commits: change.commits.len(),
+                paths: change.paths(),
+                excluded,
+                reviewers: ranked,
+            };
+            json::print(g, "suggest-reviewers", Some(&repo), &self, &data)?;
+        } else {
+            let paths = change.paths();
+            println!(
+                "👀 Reviewers for {} path{}{}",
+                paths.len(),
+                if paths.len() == 1 { "" } else { "s" },
+                if change.commits.is_empty() {
+                    String::new()
+                } else {
+                    format!(" in {} commits", change.commits.len())
+                }
+            );
+            if !excluded.is_empty() {
+                println!("Excluding {}", excluded.join(", "));
+            }
+            if ranked.is_empty() {
+                println!("No active contributor owns or recently touched these paths.");
+                return Ok(());
+            }
+            let mut t = Table::new();
+            t.load_preset(UTF8_HORIZONTAL_ONLY).set_header(vec![
+                "Reviewer",
+                "Score",
+                "Lines",
+                "Touches",
+                "Files",
+                "Last commit",
+            ]);
+            for r in &ranked {
+                t.add_row(vec![
+                    r.author.clone(),
+                    format!("{:.2}", r.score),
+                    format!("{} ({:.0}%)", r.lines, r.line_share * 100.0),
+                    format!("{} ({:.0}%)", fmt_credit(r.touches), r.touch_share * 100.0),
+                    r.files.to_string(),
+                    r.last_commit.map(fmt_date).unwrap_or_default(),
+                ]);
+            }
+            println!("{t}");
+        }
+        Ok(())
+    }
+}
@@ -1,61 +1,94 @@
-use crate::domain::{git::RepoExt, stats as d};
-use crate::{commands::Global, utils::fmt_date};
+use crate::domain::{git::RepoExt, stats as d, window::TimeWindow};
+use crate::{
+    commands::Global,
+    presentation::{
+        flat::Rows,
+        json::{self, AuthorRow, BotRow},
+        schema::schema,
+        table,
+    },
+    utils::fmt_date,
+};
 use anyhow::Result;
-use chrono::{Local, NaiveDate, TimeZone};
 use clap::Args;
-use serde_json::json;
+use serde::Serialize;
 
 /// Rank authors by number of commits.
 ///
 /// Useful for identifying the most active contributors over the entire
-/// history or since a specific date.
-#[derive(Debug, Args)]
+/// history or, with the global `--since` / `--until` / `--last`, a period.
+#[derive(Debug, Args, Serialize)]
 pub struct TopAuthors {
     /// Path to the Git repository to analyse.
     /// Defaults to the current directory.
     #[arg(short, long, default_value = ".")]
     pub path: String,
 
-    /// Only include commits on or after this date (YYYY-MM-DD).
-    /// If omitted, the full commit history is considered.
-    #[arg(short, long)]
-    pub since: Option<NaiveDate>,
+    /// Emit JSON even when the global flag is not set.
+    #[arg(long)]
+    pub json: bool,
 }
 
+/// `top-authors --json` payload.
+#[derive(Debug, Serialize)]
+pub struct TopAuthorsJson {
+    pub window: TimeWindow,
+    pub authors_sorted_desc: bool,
+    pub authors: Vec<AuthorRow>,
+    pub bots: Vec<BotRow>,
+}
+schema!(TopAuthorsJson {
+    window: TimeWindow,
+    authors_sorted_desc: bool,
+    authors: Vec<AuthorRow>,
+    bots: Vec<BotRow>,
+});
+
 impl super::Runnable for TopAuthors {
     fn run(self, g: &Global) -> Result<()> {
-        let repo = RepoExt::open(&self.path)?;
-        let since_dt = self.since.map(|d| {
-            Local
-                .from_local_datetime(&d.and_hms_opt(0, 0, 0).unwrap())
-                .unwrap()
-        });
-        let stats = d::collect_commits(repo.repo(), usize::MAX, since_dt);
+        let repo = RepoExt::open(&self.path, &g.repo)?;
+        let stats = d::collect_commits(&repo, usize::MAX)?;
 
-        if g.json {
-            let authors: Vec<_> = stats
-                .data
-                .iter()
-                .map(|(email, m)| {
-                    json!({
-                        "email": email,
-                        "count": m.count,
-                        "first": fmt_date(m.first),
-                        "last":  fmt_date(m.last),
-                    })
-                })
-                .collect();
-            let payload = json!({
-                "since": self.since.map(|d| d.to_string()),
-                "authors_sorted_desc": g.desc,
-                "authors": authors
-            });
-            println!("{}", serde_json::to_string_pretty(&payload)?);
+        if g.format.uses_rows() {
+            let mut rows = Rows::new(&["author", "commits", "first", "last", "bot"])
+                .titled(format!("Authors ({})", repo.window()))
+                .typed(&["commits", "bot"]);
+            for (author, m) in stats.sorted(g.desc) {
+                rows.push([
+                    author.clone(),
+                    m.count.to_string(),
+                    fmt_date(m.first),
+                    fmt_date(m.last),
+                    "false".into(),
+                ]);
+            }
+            for (bot, m) in &stats.bots {
+                rows.push([
+                    bot.clone(),
+                    m.commits.to_string(),
+                    fmt_date(m.first),
+                    fmt_date(m.last),
+                    "true".into(),
+                ]);
+            }
+            rows.print(g.format);
+        } else if g.json || self.json {
+            let data = TopAuthorsJson {
+                window: *repo.window(),
+                authors_sorted_desc: g.desc,
+                authors: AuthorRow::top(&stats, g.desc, usize::MAX),
+                bots: BotRow::all(&stats),
+            };
+            json::print(g, "top-authors", Some(&repo), &self, &data)?;
         } else {
-            println!("Authors since {:?}:", self.since);
+            println!("Authors ({}):", repo.window());
             for line in stats.formatted_lines(g.desc) {
                 println!("{line}");
             }
+            if !stats.bots.is_empty() {
+                println!("\nBots (not counted above):");
+                println!("{}", table::bot_stats(&stats));
+            }
         }
         Ok(())
     }
@@ -1,12 +1,17 @@
 use crate::commands::Global;
-use crate::domain::{coauthors, git::RepoExt};
+use crate::domain::{coauthors, git::RepoExt, window::TimeWindow};
+use crate::presentation::{flat::Rows, json, schema::schema};
 use anyhow::Result;
 use clap::Args;
+use serde::Serialize;
+use std::collections::BTreeMap;
 
 /// Identify author pairs that frequently touch the same files.
 ///
 /// Helps uncover collaboration clusters or potential review buddies.
-#[derive(Debug, Args)]
+/// Pairs named together in `Co-authored-by:` trailers are listed
+/// separately as real pairing data.
+#[derive(Debug, Args, Serialize)]
 pub struct TopCoauthors {
     /// Path to the Git repository.
     #[arg(short, long, default_value = ".")]
@@ -17,17 +22,98 @@ pub struct TopCoauthors {
     pub json: bool,
 }
 
+/// `top-coauthors --json` payload; pairs by count, most first.
+#[derive(Debug, Serialize)]
+pub struct TopCoauthorsJson {
+    pub window: TimeWindow,
+    /// Pairs who changed the same files (count = shared files).
+    pub pairs: Vec<Pair>,
+    /// Pairs named together in `Co-authored-by:` trailers (count = commits).
+    pub trailer_pairs: Vec<Pair>,
+}
+schema!(TopCoauthorsJson {
+    window: TimeWindow,
+    pairs: Vec<Pair>,
+    trailer_pairs: Vec<Pair>,
+});
+
+#[derive(Debug, Serialize)]
+pub struct Pair {
+    pub author_a: String,
+    pub author_b: String,
+    pub count: usize,
+}
+schema!(Pair {
+    author_a: String,
+    author_b: String,
+    count: usize,
+});
+
+impl Pair {
+    fn list(pairs: &BTreeMap<String, usize>) -> Vec<Self> {
+        let mut out: Vec<_> = pairs
+            .iter()
+            .map(|(pair, &count)| {
+                let (a, b) = split_pair(pair);
+                Pair {
+                    author_a: a.to_string(),
+                    author_b: b.to_string(),
+                    count,
+                }
+            })
+            .collect();
+        out.sort_by_key(|p| std::cmp::Reverse(p.count));
+        out
+    }
+}
+
 impl super::Runnable for TopCoauthors {
     fn run(self, g: &Global) -> Result<()> {
-        let repo = RepoExt::open(&self.path)?;
-        let pairs = coauthors::top_coauthors(repo.repo())?;
-        if g.json || self.json {
-            println!("{}", serde_json::to_string_pretty(&pairs)?);
+        let repo = RepoExt::open(&self.path, &g.repo)?;
+        let found = coauthors::top_coauthors(&repo)?;
+        if g.format.uses_rows() {
+            let mut rows = Rows::new(&["source", "author_a", "author_b", "count"])
+                .titled("Co-authors")
+                .typed(&["count"])
+                .writer(g.format)?;
+            let sources = [
+                ("shared_files", found.shared_files),
+                ("trailers", found.trailers),
+            ];
+            for (source, pairs) in sources {
+                for (pair, count) in pairs {
+                    let (a, b) = split_pair(&pair);
+                    rows.push([source, a, b, &count.to_string()])?;
+                }
+            }
+            rows.finish()?;
+        } else if g.json || self.json {
+            let data = TopCoauthorsJson {
+                window: *repo.window(),
+                pairs: Pair::list(&found.shared_files),
+                trailer_pairs: Pair::list(&found.trailers),
+            };
+            json::print(g, "top-coauthors", Some(&repo), &self, &data)?;
         } else {
-            for (pair, count) in pairs {
+            for (pair, count) in found.shared_files {
                 println!("{:<50} {:>3} shared files", pair, count);
             }
+            if !found.trailers.is_empty() {
+                println!();
+                println!("🤝 Paired via Co-authored-by:");
+                for (pair, count) in found.trailers {
+                    println!("{:<50} {:>3} commits", pair, count);
+                }
+            }
         }
         Ok(())
     }
 }
+
+/// `Name <a> + Other <b>` back into its two identities.
+fn split_pair(pair: &str) -> (&str, &str) {
+    match pair.split_once("> + ") {
+        Some((a, b)) => (&pair[..a.len() + 1], b),
+        None => pair.split_once(" + ").unwrap_or((pair, "")),
+    }
+}
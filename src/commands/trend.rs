@@ -0,0 +1,145 @@
+use anyhow::Result;
+use clap::Args;
+use comfy_table::{presets::UTF8_HORIZONTAL_ONLY, Table};
+use serde::Serialize;
+
+use crate::commands::Global;
+use crate::domain::{
+    git::RepoExt,
+    trend::{self, Period, TrendPoint},
+    window::TimeWindow,
+};
+use crate::presentation::{flat::Rows, json, schema::schema};
+
+/// Track repository health metrics over time.
+///
+/// Computes the `stats` metrics (concentration, merge/revert rates,
+/// message hygiene, active authors) for every calendar week, month,
+/// quarter or year, so improvements or regressions show up as a series.
+#[derive(Debug, Args, Serialize)]
+pub struct Trend {
+    /// Path to the Git repository.
+    #[arg(short, long, default_value = ".")]
+    pub path: String,
+
+    /// Bucket size.
+    #[arg(long, value_parser = ["week", "month", "quarter", "year"], default_value = "month")]
+    pub by: String,
+
+    /// Only show the most recent `N` buckets.
+    #[arg(long)]
+    pub limit: Option<usize>,
+
+    /// Emit JSON even when the global flag is not set.
+    #[arg(long)]
+    pub json: bool,
+}
+
+/// `trend --json` payload; buckets oldest first.
+#[derive(Debug, Serialize)]
+pub struct TrendJson {
+    pub window: TimeWindow,
+    pub by: &'static str,
+    pub buckets: Vec<TrendPoint>,
+}
+schema!(TrendJson {
+    window: TimeWindow,
+    by: &'static str,
+    buckets: Vec<TrendPoint>,
+});
+
+const HEADER: [&str; 15] = [
+    "period",
+    "start",
+    "commits",
+    "active_authors",
+    "new_authors",
+    "core_size_80pct",
+    "drive_by_ratio_pct",
+    "concentration_hhi",
+    "concentration_gini",
+    "merge_rate_pct",
+    "revert_rate_pct",
+    "conventional_commit_pct",
+    "body_present_pct",
+    "median_subject_len",
+    "work_hours_pct",
+];
+
+impl super::Runnable for Trend {
+    fn run(self, g: &Global) -> Result<()> {
+        let repo = RepoExt::open(&self.path, &g.repo)?;
+        let period = Period::parse(&self.by)?;
+        let mut points = trend::trend(&repo, period)?;
+        if let Some(n) = self.limit {
+            points.drain(..points.len().saturating_sub(n));
+        }
+
+        if g.format.uses_rows() {
+            let mut rows = Rows::new(&HEADER)
+                .titled(format!("Trend by {} ({})", period.as_str(), repo.window()))
+                .typed(&HEADER[2..]);
+            let f = g.format;
+            for p in &points {
+                rows.push([
+                    p.period.clone(),
+                    p.start.to_string(),
+                    p.commits.to_string(),
+                    p.active_authors.to_string(),
+                    p.new_authors.to_string(),
+                    p.core_size_80pct.to_string(),
+                    f.float(p.drive_by_ratio_pct, 0),
+                    f.float(p.concentration_hhi, 2),
+                    f.float(p.concentration_gini, 2),
+                    f.float(p.merge_rate_pct, 0),
+                    f.float(p.revert_rate_pct, 1),
+                    f.float(p.conventional_commit_pct, 0),
+                    f.float(p.body_present_pct, 0),
+                    p.median_subject_len.to_string(),
+                    f.float(p.work_hours_pct, 0),
+                ]);
+            }
+            rows.print(g.format);
+            return Ok(());
+        }
+
+        if g.json || self.json {
+            let data = TrendJson {
+                window: *repo.window(),
+                by: period.as_str(),
+                buckets: points,
+            };
+            return json::print(g, "trend", Some(&repo), &self, &data);
+        }
+
+        let mut t = Table::new();
+        t.load_preset(UTF8_HORIZONTAL_ONLY).set_header(vec![
+            "Period", "Commits", "Authors", "New", "Core", "Drive-by", "HHI", "Gini", "Merge",
+            "Revert", "Conv", "Body", "Subj",
+        ]);
+        for p in &points {
+            t.add_row(vec![
+                p.period.clone(),
+                p.commits.to_string(),
+                p.active_authors.to_string(),
+                p.new_authors.to_string(),
+                p.core_size_80pct.to_string(),
+                format!("{:.0}%", p.drive_by_ratio_pct),
+                format!("{:.2}", p.concentration_hhi),
+                format!("{:.2}", p.concentration_gini),
+                format!("{:.0}%", p.merge_rate_pct),
+                format!("{:.1}%", p.revert_rate_pct),
+                format!("{:.0}%", p.conventional_commit_pct),
+                format!("{:.0}%", p.body_present_pct),
+                p.median_subject_len.to_string(),
+            ]);
+        }
+        println!("📈 Trend by {} ({})", period.as_str(), repo.window());
+        println!("{t}");
+        println!("\nLegend:");
+        println!("  Core = authors covering 80% of commits; Drive-by = authors with ≤2 commits.");
+        println!("  Conv = Conventional Commits subjects; Body = commits with a message body;");
+        println!("  Subj = median subject length. Buckets without commits are omitted.");
+        Ok(())
+    }
+}
@@ -0,0 +1,57 @@
+use anyhow::{bail, Result};
+use clap::Args;
+use serde::Serialize;
+use std::io::IsTerminal;
+
+use crate::commands::Global;
+use crate::domain::{bus_factor::ScanOpts, explore, git::RepoExt};
+use crate::presentation::tui::App;
+
+/// Explore the repository interactively in the terminal.
+///
+/// Three panes: the `stats` summary, the author list, and a file tree with
+/// owner, ownership share, touches and churn per file and directory.
+/// Enter descends into a directory and from a file to its blame
+/// breakdown; `s` cycles the sort column, `r` reverses it and `/` filters
+/// as you type.
+#[derive(Debug, Args, Serialize)]
+pub struct Tui {
+    /// Path to the Git repository.
+    #[arg(short, long, default_value = ".")]
+    pub path: String,
+
+    /// Churn window in days. Ignored when the global `--since` / `--last`
+    /// is given.
+    #[arg(long, default_value = "90")]
+    pub window_days: i64,
+
+    /// Include all files, even those normally filtered out.
+    #[arg(long)]
+    pub all: bool,
+
+    /// Additional file extensions to include (comma‑separated).
+    #[arg(long, value_delimiter = ',')]
+    pub include_ext: Vec<String>,
+}
+
+impl super::Runnable for Tui {
+    fn run(self, g: &Global) -> Result<()> {
+        if !std::io::stdout().is_terminal() {
+            bail!("tui needs an interactive terminal");
+        }
+        let repo = RepoExt::open(&self.path, &g.repo)?;
+        let opts = ScanOpts {
+            all: self.all,
+            extensions: g.extensions.clone(),
+            include_ext: self.include_ext.clone(),
+            min_total: 1,
+        };
+        eprintln!("🔍 Scanning history…");
+        let data = explore::collect(&repo, self.window_days, &opts)?;
+
+        let mut terminal = ratatui::init();
+        let result = App::new(&repo, data, repo.name()).run(&mut terminal);
+        ratatui::restore();
+        result
+    }
+}
@@ -0,0 +1,410 @@
+//! Shared analysis settings from `.gitrivia.toml` (repo root) and a user
+//! config (`$XDG_CONFIG_HOME/gitrivia/config.toml`).
+//!
+//! Each `[section]` is named after a subcommand (`[bus-factor]`,
+//! `[churn]`, …) and its keys are that command's long flags; `[global]` (or
+//! keys before any section) holds global flags. Settings become the
+//! *defaults* of those flags, so anything typed on the command line wins:
+//! a list given on the command line replaces the configured one, and a
+//! boolean flag set in a config file can be turned off with `--flag=false`.
+
+use anyhow::{bail, Context, Result};
+use clap::{ArgAction, Command};
+use std::collections::BTreeMap;
+use std::fmt;
+use std::fs;
+use std::path::{Path, PathBuf};
+
+pub const REPO_FILE: &str = ".gitrivia.toml";
+pub const GLOBAL: &str = "global";
+
+/// A config value (the TOML types flags can take).
+#[derive(Debug, Clone, PartialEq)]
+pub enum Value {
+    Str(String),
+    Int(i64),
+    Float(f64),
+    Bool(bool),
+    Array(Vec<Value>),
+}
+
+impl fmt::Display for Value {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Value::Str(s) => write!(f, "{s:?}"),
+            Value::Int(n) => write!(f, "{n}"),
+            Value::Float(x) => write!(f, "{x}"),
+            Value::Bool(b) => write!(f, "{b}"),
+            Value::Array(xs) => {
+                let items: Vec<_> = xs.iter().map(|x| x.to_string()).collect();
+                write!(f, "[{}]", items.join(", "))
+            }
+        }
+    }
+}
+
+impl Value {
+    /// As a single command-line value.
+    fn as_arg(&self) -> String {
+        match self {
+            Value::Str(s) => s.clone(),
+            other => other.to_string(),
+        }
+    }
+
+    /// Command-line values: one per array item.
+    fn as_args(&self) -> Vec<String> {
+        match self {
+            Value::Array(xs) => xs.iter().map(Value::as_arg).collect(),
+            v => vec![v.as_arg()],
+        }
+    }
+}
+
+/// One setting and the file it came from.
+#[derive(Debug, Clone)]
+pub struct Setting {
+    pub value: Value,
+    pub source: PathBuf,
+}
+
+/// Merged settings: section -> flag -> setting. Later files override
+/// earlier ones key by key.
+#[derive(Debug, Clone, Default)]
+pub struct Config {
+    /// Files looked at, in load order, and whether they existed.
+    pub files: Vec<(PathBuf, bool)>,
+    pub sections: BTreeMap<String, BTreeMap<String, Setting>>,
+}
+
+impl Config {
+    /// User config, then `.gitrivia.toml` at the root of the repository
+    /// containing `path` (if any).
+    pub fn load(path: &str) -> Result<Self> {
+        let mut cfg = Config::default();
+        if let Some(user) = user_file() {
+            cfg.merge_file(&user)?;
+        }
+        if let Some(root) = repo_root(path) {
+            cfg.merge_file(&root.join(REPO_FILE))?;
+        }
+        Ok(cfg)
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.sections.values().all(|s| s.is_empty())
+    }
+
+    fn merge_file(&mut self, file: &Path) -> Result<()> {
+        let text = match fs::read_to_string(file) {
+            Ok(t) => t,
+            Err(_) => {
+                self.files.push((file.to_path_buf(), false));
+                return Ok(());
+            }
+        };
+        let parsed = parse(&text).with_context(|| format!("in {}", file.display()))?;
+        self.merge(parsed, file);
+        self.files.push((file.to_path_buf(), true));
+        Ok(())
+    }
+
+    fn merge(&mut self, parsed: BTreeMap<String, BTreeMap<String, Value>>, file: &Path) {
+        for (section, keys) in parsed {
+            let dst = self.sections.entry(section).or_default();
+            for (key, value) in keys {
+                let source = file.to_path_buf();
+                dst.insert(key.replace('_', "-"), Setting { value, source });
+            }
+        }
+    }
+
+    pub fn get(&self, section: &str, key: &str) -> Option<&Setting> {
+        self.sections.get(section)?.get(key)
+    }
+
+    /// Check every section, key and value against the CLI definition.
+    pub fn validate(&self, cli: &Command) -> Result<()> {
+        for (section, keys) in &self.sections {
+            let cmd = section_command(cli, section)
+                .with_context(|| format!("unknown config section [{section}]"))?;
+            for (key, s) in keys {
+                let Some(arg) = find_arg(cmd, key) else {
+                    bail!(
+                        "unknown key '{key}' in [{section}] of {}",
+                        s.source.display()
+                    );
+                };
+                let flag = !arg.get_action().takes_values();
+                let many = matches!(arg.get_action(), ArgAction::Append)
+                    || arg.get_num_args().is_some_and(|n| n.max_values() > 1);
+                match &s.value {
+                    Value::Bool(_) => {}
+                    _ if flag => bail!(
+                        "'{key}' in [{section}] of {} is a flag: use true or false",
+                        s.source.display()
+                    ),
+                    Value::Array(_) if !many => bail!(
+                        "'{key}' in [{section}] of {} takes one value, not a list",
+                        s.source.display()
+                    ),
+                    _ => {}
+                }
+            }
+        }
+        Ok(())
+    }
+
+    /// `cli` with every setting as the default of its flag. Flags that a
+    /// setting turns on also accept `--flag=false`.
+    pub fn apply(&self, mut cli: Command) -> Command {
+        for (section, keys) in &self.sections {
+            if section == GLOBAL {
+                cli = apply_section(cli, keys);
+            } else if cli.find_subcommand(section).is_some() {
+                cli = cli.mut_subcommand(section, |sub| apply_section(sub, keys));
+            }
+        }
+        cli
+    }
+}
+
+fn apply_section(mut cmd: Command, keys: &BTreeMap<String, Setting>) -> Command {
+    for (key, s) in keys {
+        let Some(arg) = find_arg(&cmd, key) else {
+            continue;
+        };
+        let id = arg.get_id().clone();
+        let flag = !arg.get_action().takes_values();
+        let values = s.value.as_args();
+        cmd = cmd.mut_arg(id, |a| {
+            let a = a.required(false);
+            if flag {
+                a.action(ArgAction::Set)
+                    .num_args(0..=1)
+                    .require_equals(true)
+                    .default_missing_value("true")
+                    .default_value(values[0].clone())
+            } else {
+                a.default_values(values)
+            }
+        });
+    }
+    cmd
+}
+
+/// The command a section configures (`global` = the top-level command).
+pub fn section_command<'a>(cli: &'a Command, section: &str) -> Option<&'a Command> {
+    if section == GLOBAL {
+        Some(cli)
+    } else {
+        cli.find_subcommand(section)
+    }
+}
+
+fn find_arg<'a>(cmd: &'a Command, key: &str) -> Option<&'a clap::Arg> {
+    cmd.get_arguments()
+        .find(|a| a.get_long() == Some(key) && !matches!(a.get_action(), ArgAction::Help))
+}
+
+fn user_file() -> Option<PathBuf> {
+    let base = std::env::var_os("XDG_CONFIG_HOME")
+        .map(PathBuf::from)
+        .or_else(|| std::env::var_os("HOME").map(|h| PathBuf::from(h).join(".config")))?;
+    Some(base.join("gitrivia").join("config.toml"))
+}
+
+fn repo_root(path: &str) -> Option<PathBuf> {
+    let repo = git2::Repository::discover(path).ok()?;
+    repo.workdir().map(Path::to_path_buf)
+}
+
+/// Parse a config file: `[section]` tables of flag settings, and keys
+/// before the first table for `[global]`.
+pub fn parse(text: &str) -> Result<BTreeMap<String, BTreeMap<String, Value>>> {
+    let table: toml::Table = text.parse()?;
+    let mut out: BTreeMap<String, BTreeMap<String, Value>> = BTreeMap::new();
+    for (key, v) in table {
+        match v {
+            toml::Value::Table(t) => {
+                let section = out.entry(key.clone()).or_default();
+                for (k, v) in t {
+                    let v = value(v).with_context(|| format!("bad value for '{k}' in [{key}]"))?;
+                    section.insert(k, v);
+                }
+            }
+            v => {
+                let v = value(v).with_context(|| format!("bad value for '{key}'"))?;
+                out.entry(GLOBAL.to_string()).or_default().insert(key, v);
+            }
+        }
+    }
+    Ok(out)
+}
+
+fn value(v: toml::Value) -> Result<Value> {
+    Ok(match v {
+        toml::Value::String(s) => Value::Str(s),
+        toml::Value::Integer(n) => Value::Int(n),
+        toml::Value::Float(x) => Value::Float(x),
+        toml::Value::Boolean(b) => Value::Bool(b),
+        // `since = 2025-01-01` is as good as the quoted form.
+        toml::Value::Datetime(d) => Value::Str(d.to_string()),
+        toml::Value::Array(xs) => Value::Array(
+            xs.into_iter()
+                .map(|x| match x {
+                    toml::Value::Array(_) | toml::Value::Table(_) => {
+                        bail!("lists of lists or tables are not supported")
+                    }
+                    x => value(x),
+                })
+                .collect::<Result<_>>()?,
+        ),
+        toml::Value::Table(_) => bail!("nested tables are not supported"),
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::cli::{Cli, CliCommand};
+    use clap::CommandFactory;
+    use std::ffi::OsString;
+
+    fn config(text: &str) -> Config {
+        let mut cfg = Config::default();
+        cfg.merge(parse(text).unwrap(), Path::new("test.toml"));
+        cfg
+    }
+
+    fn cli(cfg: &Config, args: &[&str]) -> Cli {
+        let args: Vec<OsString> = args.iter().map(Into::into).collect();
+        Cli::parse_with(cfg, &args).unwrap()
+    }
+
+    #[test]
+    fn quoting() {
+        let p = parse(
+            r#"
+            a = "x # not a comment" # a comment
+            b = 'C:\path\no escapes'
+            c = "tab\tquote\"end"
+            "quoted key" = 1
+            "#,
+        )
+        .unwrap();
+        let g = &p[GLOBAL];
+        assert_eq!(g["a"], Value::Str("x # not a comment".into()));
+        assert_eq!(g["b"], Value::Str(r"C:\path\no escapes".into()));
+        assert_eq!(g["c"], Value::Str("tab\tquote\"end".into()));
+        assert_eq!(g["quoted key"], Value::Int(1));
+        assert!(parse("a = \"unterminated").is_err());
+    }
+
+    #[test]
+    fn arrays_and_scalars() {
+        let p = parse(
+            "exts = [\n  \"lua\", # trailing comment\n  'vim',\n]\nn = 1_000\nx = 0.5\nb = false\nd = 2025-01-01\n",
+        )
+        .unwrap();
+        let g = &p[GLOBAL];
+        assert_eq!(
+            g["exts"],
+            Value::Array(vec![Value::Str("lua".into()), Value::Str("vim".into())])
+        );
+        assert_eq!(g["n"], Value::Int(1000));
+        assert_eq!(g["x"], Value::Float(0.5));
+        assert_eq!(g["b"], Value::Bool(false));
+        assert_eq!(g["d"], Value::Str("2025-01-01".into()));
+        assert!(parse("a = [[1]]").is_err());
+    }
+
+    #[test]
+    fn sections_and_top_level_keys() {
+        let cfg = config("desc = true\n[global]\nbots = 'exclude'\n[churn]\nmin_total = 5\n");
+        assert_eq!(cfg.get(GLOBAL, "desc").unwrap().value, Value::Bool(true));
+        assert_eq!(
+            cfg.get(GLOBAL, "bots").unwrap().value,
+            Value::Str("exclude".into())
+        );
+        // `_` and `-` are the same in keys.
+        assert_eq!(cfg.get("churn", "min-total").unwrap().value, Value::Int(5));
+        assert!(parse("[churn.deep]\nx = 1\n").is_err());
+    }
+
+    #[test]
+    fn unknown_sections_keys_and_bad_types_are_rejected() {
+        let cmd = Cli::command();
+        assert!(config("[churn]\nlimit = 3\n").validate(&cmd).is_ok());
+        assert!(config("[nope]\nlimit = 3\n").validate(&cmd).is_err());
+        assert!(config("[churn]\nnope = 3\n").validate(&cmd).is_err());
+        assert!(config("[churn]\nall = 'yes'\n").validate(&cmd).is_err());
+        assert!(config("[churn]\nlimit = [1, 2]\n").validate(&cmd).is_err());
+        assert!(config("[churn]\ninclude-ext = ['a', 'b']\n")
+            .validate(&cmd)
+            .is_ok());
+    }
+
+    #[test]
+    fn command_line_overrides_config() {
+        let cfg = config(
+            "desc = true\nsince = '2025-01-01'\n[churn]\nall = true\nlimit = 3\ninclude-ext = ['lua']\n",
+        );
+        let c = cli(&cfg, &["gitrivia", "churn"]);
+        assert!(c.desc);
+        assert_eq!(c.since.as_deref(), Some("2025-01-01"));
+        let CliCommand::Churn(ch) = c.command else {
+            panic!()
+        };
+        assert!(ch.all);
+        assert_eq!(ch.limit, 3);
+        assert_eq!(ch.include_ext, ["lua"]);
+
+        let c = cli(
+            &cfg,
+            &[
+                "gitrivia",
+                "--desc=false",
+                "churn",
+                "--all=false",
+                "--limit",
+                "9",
+                "--include-ext",
+                "vim",
+                "--last",
+                "30d",
+            ],
+        );
+        assert!(!c.desc);
+        // `--last` conflicts with `--since`, but not with its default.
+        assert_eq!(c.last.as_deref(), Some("30d"));
+        let CliCommand::Churn(ch) = c.command else {
+            panic!()
+        };
+        assert!(!ch.all);
+        assert_eq!(ch.limit, 9);
+        assert_eq!(ch.include_ext, ["vim"]);
+
+        // A bare flag still turns it on; global flags work after the
+        // subcommand.
+        let c = cli(
+            &cfg,
+            &["gitrivia", "churn", "--all", "--since", "2024-06-01"],
+        );
+        assert_eq!(c.since.as_deref(), Some("2024-06-01"));
+        let CliCommand::Churn(ch) = c.command else {
+            panic!()
+        };
+        assert!(ch.all);
+    }
+
+    #[test]
+    fn required_flags_can_come_from_config() {
+        let cfg = config("[author-activity]\nauthor = 'ann@x.io'\n");
+        let CliCommand::AuthorActivity(a) = cli(&cfg, &["gitrivia", "author-activity"]).command
+        else {
+            panic!()
+        };
+        assert_eq!(a.author, "ann@x.io");
+    }
+}
@@ -0,0 +1,357 @@
+//! How old the code at the tip is: blamed lines' commit times per file and
+//! directory, and how many of the lines written in each period survive.
+
+use anyhow::{bail, Context, Result};
+use chrono::{DateTime, Local, NaiveDate, TimeZone};
+use git2::Repository;
+use rayon::prelude::*;
+use serde::Serialize;
+use std::collections::{BTreeMap, HashMap};
+use std::path::Path;
+
+use crate::domain::blame;
+use crate::domain::bus_factor::ScanOpts;
+use crate::domain::churn::dir_key;
+use crate::domain::codeowners::email_of;
+use crate::domain::engine::{self, Analyser, CommitCtx};
+use crate::domain::git::{commit_time, RepoExt};
+use crate::domain::trend::Period;
+
+/// Who a survival curve is drawn for.
+#[derive(Debug, Clone, Default)]
+pub enum Cohort {
+    /// One curve for everyone.
+    #[default]
+    All,
+    Author,
+    Team(Teams),
+}
+
+impl Cohort {
+    fn of<'a>(&'a self, author: &'a str) -> &'a str {
+        match self {
+            Cohort::All => "all",
+            Cohort::Author => author,
+            Cohort::Team(t) => t.team_of(author),
+        }
+    }
+}
+
+/// Team membership from `--team NAME=EMAIL,…`: members are emails, or
+/// `@domain` for everyone at a domain. The first matching team wins.
+#[derive(Debug, Clone, Default)]
+pub struct Teams {
+    members: Vec<(String, String)>,
+}
+
+/// Team of authors matching no `--team`.
+pub const NO_TEAM: &str = "(no team)";
+
+impl Teams {
+    pub fn parse(specs: &[String]) -> Result<Self> {
+        let mut members = Vec::new();
+        for spec in specs {
+            let (team, list) = spec
+                .split_once('=')
+                .with_context(|| format!("expected TEAM=EMAIL,…, got '{spec}'"))?;
+            if team.trim().is_empty() {
+                bail!("team name missing in '{spec}'");
+            }
+            for m in list.split(',').map(str::trim).filter(|m| !m.is_empty()) {
+                members.push((team.trim().to_string(), m.to_lowercase()));
+            }
+        }
+        Ok(Self { members })
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.members.is_empty()
+    }
+
+    /// Team of an author (`Name <email>`), or [`NO_TEAM`].
+    pub fn team_of(&self, author: &str) -> &str {
+        let email = email_of(author).to_lowercase();
+        self.members
+            .iter()
+            .find(|(_, m)| match m.strip_prefix('@') {
+                Some(domain) => email.rsplit_once('@').is_some_and(|(_, d)| d == domain),
+                None => *m == email,
+            })
+            .map_or(NO_TEAM, |(t, _)| t.as_str())
+    }
+}
+
+#[derive(Debug, Clone)]
+pub struct AgeOpts {
+    pub scan: ScanOpts,
+    /// Directory depth of the per-directory rows; `None` for per-file rows.
+    pub depth: Option<usize>,
+    /// Lines older than this count as old.
+    pub old_years: f64,
+    pub period: Period,
+    pub cohort: Cohort,
+}
+
+/// Ages of the lines of a file or directory, in days before the tip.
+#[derive(Debug, Clone, Serialize)]
+pub struct PathAge {
+    pub path: String,
+    pub lines: usize,
+    pub median_days: i64,
+    /// 90 % of the lines are younger than this.
+    pub p90_days: i64,
+    /// Share of lines older than `--old-years` (0..1).
+    pub old_share: f64,
+}
+
+/// Lines one cohort wrote in one period, and how many the tip still has.
+#[derive(Debug, Clone, Serialize)]
+pub struct Survival {
+    /// Author, team or `all`.
+    pub cohort: String,
+    /// Period label (`2025-Q1`, …).
+    pub period: String,
+    pub start: NaiveDate,
+    /// Lines added by the period's commits (merges left out).
+    pub written: usize,
+    /// Lines blamed on the period's commits at the tip.
+    pub alive: usize,
+    /// `alive / written` (0..1); can exceed 1 when lines were moved in
+    /// from files the scan leaves out.
+    pub survival: f64,
+}
+
+#[derive(Debug, Clone)]
+pub struct AgeReport {
+    pub tip_time: DateTime<Local>,
+    /// The whole scanned tree (`path` is `.`).
+    pub total: PathAge,
+    /// Per directory or file, oldest median first.
+    pub paths: Vec<PathAge>,
+    /// By cohort, then period.
+    pub survival: Vec<Survival>,
+}
+
+/// Blame every selected file for line ages and survivors, and walk the
+/// history once for the lines each period wrote.
+pub fn analyse(repo: &RepoExt, opts: &AgeOpts) -> Result<AgeReport> {
+    let tip_time = commit_time(&repo.repo().find_commit(repo.tip())?);
+    let repo_path = repo.repo().path();
+    let mailmap = repo.mailmap();
+    let bots = repo.bots();
+    let tip = repo.tip();
+    let tuning = repo.blame_tuning();
+    let window = *repo.window();
+    let files: Vec<_> = repo
+        .tracked_files()?
+        .into_par_iter()
+        .filter_map(|file| {
+            if !opts.scan.ext_ok(&file) {
+                return None;
+            }
+            let git = Repository::open(repo_path).ok()?;
+            let hunks = blame::hunks(&git, tip, Path::new(&file), None, tuning).ok()?;
+            // (age in days, lines) and (cohort, period start) → lines
+            let mut ages = Vec::new();
+            let mut alive: HashMap<(String, NaiveDate), usize> = HashMap::new();
+            for h in hunks {
+                let author = mailmap.resolve_signature(&h.signature);
+                if bots.skips(&author) {
+                    continue;
+                }
+                let when = Local
+                    .timestamp_opt(h.signature.when().seconds(), 0)
+                    .single()
+                    .unwrap_or(tip_time);
+                ages.push(((tip_time - when).num_days().max(0), h.lines));
+                if window.contains(when) {
+                    let author = author.to_string();
+                    let key = (
+                        opts.cohort.of(&author).to_string(),
+                        opts.period.start(when.date_naive()),
+                    );
+                    *alive.entry(key).or_default() += h.lines;
+                }
+            }
+            Some((file, ages, alive))
+        })
+        .collect();
+
+    let old_days = (opts.old_years * 365.25).round() as i64;
+    let mut groups: BTreeMap<String, Vec<(i64, usize)>> = BTreeMap::new();
+    let mut everything = Vec::new();
+    let mut alive: HashMap<(String, NaiveDate), usize> = HashMap::new();
+    for (file, ages, a) in files {
+        let key = match opts.depth {
+            Some(d) => dir_key(&file, d),
+            None => file,
+        };
+        everything.extend_from_slice(&ages);
+        groups.entry(key).or_default().extend(ages);
+        for (k, n) in a {
+            *alive.entry(k).or_default() += n;
+        }
+    }
+    let mut paths: Vec<PathAge> = groups
+        .into_iter()
+        .map(|(path, ages)| path_age(path, ages, old_days))
+        .filter(|p| p.lines >= opts.scan.min_total.max(1))
+        .collect();
+    paths.sort_by(|a, b| {
+        b.median_days
+            .cmp(&a.median_days)
+            .then_with(|| b.lines.cmp(&a.lines))
+            .then_with(|| a.path.cmp(&b.path))
+    });
+
+    let mut written = Written {
+        opts,
+        lines: HashMap::new(),
+    };
+    engine::scan(repo, &mut [&mut written])?;
+    let mut survival: Vec<Survival> = written
+        .lines
+        .into_iter()
+        .map(|((cohort, start), written)| {
+            let alive = alive.remove(&(cohort.clone(), start)).unwrap_or(0);
+            Survival {
+                period: opts.period.label(start),
+                cohort,
+                start,
+                written,
+                alive,
+                survival: alive as f64 / written as f64,
+            }
+        })
+        .collect();
+    survival.sort_by(|a, b| a.cohort.cmp(&b.cohort).then(a.start.cmp(&b.start)));
+
+    Ok(AgeReport {
+        tip_time,
+        total: path_age(".".into(), everything, old_days),
+        paths,
+        survival,
+    })
+}
+
+fn path_age(path: String, mut ages: Vec<(i64, usize)>, old_days: i64) -> PathAge {
+    ages.sort_unstable();
+    let lines: usize = ages.iter().map(|(_, n)| n).sum();
+    // Age below which `q` of the lines are.
+    let quantile = |q: f64| {
+        let mut seen = 0;
+        ages.iter()
+            .find(|(_, n)| {
+                seen += n;
+                seen as f64 >= q * lines as f64
+            })
+            .map_or(0, |(d, _)| *d)
+    };
+    let old: usize = ages
+        .iter()
+        .filter(|(d, _)| *d > old_days)
+        .map(|(_, n)| n)
+        .sum();
+    PathAge {
+        median_days: quantile(0.5),
+        p90_days: quantile(0.9),
+        old_share: if lines > 0 {
+            old as f64 / lines as f64
+        } else {
+            0.0
+        },
+        path,
+        lines,
+    }
+}
+
+/// Lines added per (cohort, period start), from each commit's diff against
+/// its first parent; root commits count the files they add.
+struct Written<'o> {
+    opts: &'o AgeOpts,
+    lines: HashMap<(String, NaiveDate), usize>,
+}
+
+impl Analyser for Written<'_> {
+    fn visit(&mut self, c: &CommitCtx) {
+        // A merge's diff repeats the lines its branch wrote.
+        if c.is_merge() {
+            return;
+        }
+        let added: usize = c
+            .all_changes()
+            .filter(|f| self.opts.scan.ext_ok(&f.path))
+            .map(|f| f.adds)
+            .sum();
+        if added > 0 {
+            let key = (
+                self.opts.cohort.of(&c.author).to_string(),
+                self.opts.period.start(c.time.date_naive()),
+            );
+            *self.lines.entry(key).or_default() += added;
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::domain::test_repo::TestRepo;
+
+    #[test]
+    fn quantiles_weigh_each_line() {
+        // Five lines a day old, four at 100 days, one at 1000.
+        let p = path_age("src".into(), vec![(1000, 1), (100, 4), (1, 5)], 365);
+        assert_eq!((p.lines, p.median_days, p.p90_days), (10, 1, 100));
+        assert_eq!(p.old_share, 0.1);
+
+        let empty = path_age(".".into(), Vec::new(), 365);
+        assert_eq!(
+            (empty.lines, empty.median_days, empty.old_share),
+            (0, 0, 0.0)
+        );
+    }
+
+    #[test]
+    fn teams_match_emails_then_domains_first_wins() {
+        let teams = Teams::parse(&[
+            "infra = Ann@x.io, bob@y.io".to_string(),
+            "web=@x.io".to_string(),
+        ])
+        .unwrap();
+        assert_eq!(teams.team_of("Ann <ann@X.io>"), "infra");
+        assert_eq!(teams.team_of("Bob <bob@y.io>"), "infra");
+        assert_eq!(teams.team_of("Cy <cy@x.io>"), "web");
+        assert_eq!(teams.team_of("Di <di@sub.x.io>"), NO_TEAM);
+        assert!(Teams::parse(&["infra".to_string()]).is_err());
+        assert!(Teams::parse(&[" =a@x.io".to_string()]).is_err());
+        assert!(Teams::parse(&[]).unwrap().is_empty());
+    }
+
+    #[test]
+    fn survival_counts_written_and_surviving_lines() {
+        let mut t = TestRepo::new();
+        t.commit("Ann <ann@x.io>", "add", &[("a.rs", Some("1\n2\n3\n4\n"))]);
+        t.commit("Ben <ben@x.io>", "edit", &[("a.rs", Some("1\nb\nc\n4\n"))]);
+        let opts = AgeOpts {
+            scan: ScanOpts::default().min_total(1),
+            depth: None,
+            old_years: 2.0,
+            period: Period::Year,
+            cohort: Cohort::Author,
+        };
+        let report = analyse(&t.open(), &opts).unwrap();
+        assert_eq!(report.total.lines, 4);
+        assert_eq!(report.paths.len(), 1);
+        let rows: Vec<_> = report
+            .survival
+            .iter()
+            .map(|s| (email_of(&s.cohort), s.period.as_str(), s.written, s.alive))
+            .collect();
+        assert_eq!(
+            rows,
+            [("ann@x.io", "2023", 4, 2), ("ben@x.io", "2023", 2, 2)]
+        );
+        assert_eq!(report.survival[0].survival, 0.5);
+    }
+}
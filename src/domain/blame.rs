@@ -1,16 +1,1104 @@
-use anyhow::Result;
-use git2::{BlameOptions, Repository};
-use std::collections::BTreeMap;
-use std::path::Path;
+use anyhow::{bail, Context, Result};
+use chrono::{DateTime, Local, TimeZone};
+use git2::{BlameOptions, Commit, DiffFindOptions, DiffOptions, Oid, Patch, Repository, Signature};
+use serde::Serialize;
+use std::collections::{BTreeMap, HashMap, HashSet};
+use std::path::{Path, PathBuf};
+use std::rc::Rc;
 
-pub fn blame_counts(repo: &Repository, file: &Path) -> Result<BTreeMap<String, usize>> {
-    let mut opts = BlameOptions::new();
-    let blame = repo.blame_file(file, Some(&mut opts))?;
+use crate::domain::git::{blame_options_at, commit_time, RepoExt};
 
+/// The file git reads revisions to skip from, by convention (and what
+/// `blame.ignoreRevsFile` usually points to).
+pub const IGNORE_REVS_FILE: &str = ".git-blame-ignore-revs";
+
+/// How far blame looks for moved or copied lines, like `git blame -M` /
+/// `-C`.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum MoveDetection {
+    /// Lines belong to the commit that added them where they are.
+    #[default]
+    Off,
+    /// Lines moved or copied within the file keep their author.
+    File,
+    /// Also lines moved or copied from other files changed in the same
+    /// commit.
+    Commit,
+}
+
+impl MoveDetection {
+    pub fn parse(s: &str) -> Result<Self> {
+        Ok(match s.to_ascii_lowercase().as_str() {
+            "off" => Self::Off,
+            "file" => Self::File,
+            "commit" => Self::Commit,
+            _ => bail!("unknown move detection '{s}' (use off, file or commit)"),
+        })
+    }
+}
+
+/// How blame attributes lines, as given on the command line.
+#[derive(Debug, Clone, Default)]
+pub struct BlameSettings {
+    /// Revisions whose lines go to whoever wrote them before (anything
+    /// `git rev-parse` takes).
+    pub ignore_revs: Vec<String>,
+    /// Files listing more such revisions, one full hash per line.
+    pub ignore_revs_files: Vec<PathBuf>,
+    /// Don't read the repository's own list (`blame.ignoreRevsFile`, else
+    /// `.git-blame-ignore-revs`).
+    pub no_default_ignore_revs: bool,
+    /// Changes that only touch whitespace don't take lines over.
+    pub ignore_whitespace: bool,
+    pub moves: MoveDetection,
+}
+
+/// [`BlameSettings`] resolved against a repository; plain data, so blame
+/// workers on other threads can share it.
+#[derive(Debug, Clone, Default)]
+pub struct BlameTuning {
+    pub ignore_revs: HashSet<Oid>,
+    pub ignore_whitespace: bool,
+    pub moves: MoveDetection,
+}
+
+impl BlameTuning {
+    /// Resolve `settings`; the default ignore list is read from the work
+    /// tree or, failing that, from `tip`. Revisions in list files that this
+    /// clone lacks are skipped, unknown `--ignore-rev`s are an error.
+    pub fn resolve(git: &Repository, tip: Oid, settings: &BlameSettings) -> Result<Self> {
+        let mut ignore_revs = HashSet::new();
+        for rev in &settings.ignore_revs {
+            let commit = git
+                .revparse_single(rev)
+                .and_then(|o| o.peel_to_commit())
+                .with_context(|| format!("unknown revision to ignore '{rev}'"))?;
+            ignore_revs.insert(commit.id());
+        }
+        let mut lists = Vec::new();
+        if !settings.no_default_ignore_revs {
+            lists.extend(default_ignore_list(git, tip));
+        }
+        for f in &settings.ignore_revs_files {
+            lists.push(
+                std::fs::read_to_string(f).with_context(|| format!("reading {}", f.display()))?,
+            );
+        }
+        for line in lists.iter().flat_map(|l| l.lines()) {
+            let rev = line.split('#').next().unwrap_or_default().trim();
+            if rev.is_empty() {
+                continue;
+            }
+            if let Ok(c) = git.revparse_single(rev).and_then(|o| o.peel_to_commit()) {
+                ignore_revs.insert(c.id());
+            }
+        }
+        Ok(Self {
+            ignore_revs,
+            ignore_whitespace: settings.ignore_whitespace,
+            moves: settings.moves,
+        })
+    }
+
+    /// Blame options for `tip` with these settings.
+    pub fn options_at(&self, tip: Oid) -> BlameOptions {
+        let mut opts = blame_options_at(tip);
+        opts.ignore_whitespace(self.ignore_whitespace);
+        opts
+    }
+
+    /// Whether some lines may have to be traced past the commit blame
+    /// gives them to.
+    fn looks_past(&self) -> bool {
+        !self.ignore_revs.is_empty() || self.moves != MoveDetection::Off
+    }
+}
+
+/// `blame.ignoreRevsFile` (relative to the work tree), else
+/// `.git-blame-ignore-revs` in the work tree or committed at `tip`.
+fn default_ignore_list(git: &Repository, tip: Oid) -> Option<String> {
+    let configured = git
+        .config()
+        .and_then(|c| c.get_path("blame.ignoreRevsFile"))
+        .ok()
+        .filter(|p| !p.as_os_str().is_empty());
+    if let Some(path) = &configured {
+        let path = match git.workdir() {
+            Some(w) if path.is_relative() => w.join(path),
+            _ => path.clone(),
+        };
+        return std::fs::read_to_string(path).ok();
+    }
+    if let Some(text) = git
+        .workdir()
+        .and_then(|w| std::fs::read_to_string(w.join(IGNORE_REVS_FILE)).ok())
+    {
+        return Some(text);
+    }
+    let blob = git
+        .find_commit(tip)
+        .ok()?
+        .tree()
+        .ok()?
+        .get_path(Path::new(IGNORE_REVS_FILE))
+        .ok()?
+        .to_object(git)
+        .ok()?
+        .peel_to_blob()
+        .ok()?;
+    Some(String::from_utf8_lossy(blob.content()).into_owned())
+}
+
+/// Lines of a blamed file last changed by one commit.
+#[derive(Clone)]
+pub struct Hunk {
+    pub commit: Oid,
+    /// The commit's author as recorded (resolve through the mailmap).
+    pub signature: Signature<'static>,
+    /// First line (1-based) in the blamed version of the file.
+    pub start: usize,
+    pub lines: usize,
+}
+
+/// Blame `file` at `tip`, restricted to `range` when given. Lines of
+/// ignored revisions go to the author of the lines they replaced, and with
+/// move detection moved or copied lines to the author of the original.
+pub fn hunks(
+    git: &Repository,
+    tip: Oid,
+    file: &Path,
+    range: Option<LineSpan>,
+    tuning: &BlameTuning,
+) -> Result<Vec<Hunk>> {
+    let mut blamer = Blamer {
+        git,
+        tuning,
+        files: HashMap::new(),
+        texts: HashMap::new(),
+        changed: HashMap::new(),
+        sources: HashMap::new(),
+        authors: HashMap::new(),
+    };
+    let runs = blamer.runs(tip, file, range, 0)?;
+    Ok(runs
+        .into_iter()
+        .map(|(commit, start, lines)| Hunk {
+            commit,
+            signature: blamer.authors[&commit].clone(),
+            start,
+            lines,
+        })
+        .collect())
+}
+
+/// Commits a line is traced back through at most.
+const MAX_DEPTH: usize = 50;
+
+/// Alphanumeric characters a block of lines needs to count as moved
+/// within a file, or copied from another one (git's default `-M` and `-C`
+/// scores).
+const MOVE_SCORE: usize = 20;
+const COPY_SCORE: usize = 40;
+
+/// Files changed by one commit searched for copied lines at most; bigger
+/// (usually mechanical) commits are only searched for moves within a file.
+const MAX_COPY_SOURCES: usize = 200;
+
+/// One `hunks` call: whole-file attributions of the older versions it had
+/// to look at, and what move detection read, cached per commit and path.
+struct Blamer<'r> {
+    git: &'r Repository,
+    tuning: &'r BlameTuning,
+    /// (commit, path) → commit of every line.
+    files: HashMap<(Oid, PathBuf), Rc<Vec<Oid>>>,
+    /// (commit, path) → normalised lines.
+    texts: HashMap<(Oid, PathBuf), Rc<Vec<String>>>,
+    /// Commit → old paths of the files it changed.
+    changed: HashMap<Oid, Rc<Vec<PathBuf>>>,
+    /// (commit, old path) → where the commit's new lines may come from.
+    sources: HashMap<(Oid, PathBuf), Rc<MoveSources>>,
+    authors: HashMap<Oid, Signature<'static>>,
+}
+
+/// Lines in a commit's parent that its new lines may have been moved or
+/// copied from.
+struct MoveSources {
+    paths: Vec<PathBuf>,
+    lines: Vec<Rc<Vec<String>>>,
+    /// Normalised line → (source, 0-based line).
+    index: HashMap<String, Vec<(usize, usize)>>,
+}
+
+impl Blamer<'_> {
+    /// `path` at `at` as (commit, first line, lines) runs.
+    fn runs(
+        &mut self,
+        at: Oid,
+        path: &Path,
+        range: Option<LineSpan>,
+        depth: usize,
+    ) -> Result<Vec<(Oid, usize, usize)>> {
+        let mut opts = self.tuning.options_at(at);
+        if let Some(r) = range {
+            opts.min_line(r.start).max_line(r.end);
+        }
+        let blame = self.git.blame_file(path, Some(&mut opts))?;
+        let mut out: Vec<(Oid, usize, usize)> = Vec::new();
+        for h in blame.iter() {
+            let commit = h.final_commit_id();
+            let (start, n) = (h.final_start_line(), h.lines_in_hunk());
+            self.authors
+                .entry(commit)
+                .or_insert_with(|| h.final_signature().to_owned());
+            if !self.tuning.looks_past() || depth >= MAX_DEPTH {
+                out.push((commit, start, n));
+                continue;
+            }
+            let at_path = h.path().unwrap_or(path).to_path_buf();
+            let origins = self.look_past(commit, &at_path, h.orig_start_line(), n, depth)?;
+            for (i, o) in origins.into_iter().enumerate() {
+                match out.last_mut() {
+                    Some((c, s, len)) if *c == o && *s + *len == start + i => *len += 1,
+                    _ => out.push((o, start + i, 1)),
+                }
+            }
+        }
+        Ok(out)
+    }
+
+    /// Commit of every line of `path` at `at`.
+    fn file(&mut self, at: Oid, path: &Path, depth: usize) -> Result<Rc<Vec<Oid>>> {
+        let key = (at, path.to_path_buf());
+        if let Some(lines) = self.files.get(&key) {
+            return Ok(lines.clone());
+        }
+        let mut lines = Vec::new();
+        for (commit, _, n) in self.runs(at, path, None, depth)? {
+            lines.extend(std::iter::repeat_n(commit, n));
+        }
+        let lines = Rc::new(lines);
+        self.files.insert(key, lines.clone());
+        Ok(lines)
+    }
+
+    /// Who really wrote `n` lines from `start` of `path` that blame gives
+    /// to `commit`: for an ignored commit, the lines its diff replaced;
+    /// with move detection, identical lines in the parent. Lines with no
+    /// such origin stay with `commit`.
+    fn look_past(
+        &mut self,
+        commit: Oid,
+        path: &Path,
+        start: usize,
+        n: usize,
+        depth: usize,
+    ) -> Result<Vec<Oid>> {
+        let mut out = vec![commit; n];
+        let ignored = self.tuning.ignore_revs.contains(&commit);
+        if !ignored && self.tuning.moves == MoveDetection::Off {
+            return Ok(out);
+        }
+        let c = self.git.find_commit(commit)?;
+        let Ok(parent) = c.parent(0) else {
+            return Ok(out);
+        };
+        let old_path = old_path(self.git, &c, &parent, path)?;
+        let mut open = vec![true; n];
+
+        if ignored {
+            if let Some(old) = &old_path {
+                let hunks = self.diff_hunks(&c, &parent, old, path)?;
+                let prior = self.file(parent.id(), old, depth + 1)?;
+                for (i, slot) in out.iter_mut().enumerate() {
+                    if let Some(o) = old_line(&hunks, start + i).and_then(|l| prior.get(l - 1)) {
+                        *slot = *o;
+                        open[i] = false;
+                    }
+                }
+            }
+        }
+
+        if self.tuning.moves != MoveDetection::Off && open.contains(&true) {
+            let sources = self.move_sources(&c, &parent, old_path.as_deref())?;
+            let text = self.text(&c, path)?;
+            let targets = text.get(start - 1..(start - 1 + n).min(text.len()));
+            let min_score = |s: usize| {
+                if Some(sources.paths[s].as_path()) == old_path.as_deref() {
+                    MOVE_SCORE
+                } else {
+                    COPY_SCORE
+                }
+            };
+            let found = find_moves(targets.unwrap_or_default(), &open, &sources, min_score);
+            for (i, found) in found.into_iter().enumerate() {
+                if let Some((s, line)) = found {
+                    let prior = self.file(parent.id(), &sources.paths[s], depth + 1)?;
+                    if let Some(o) = prior.get(line) {
+                        out[i] = *o;
+                    }
+                }
+            }
+        }
+        Ok(out)
+    }
+
+    /// `(old_start, old_lines, new_start, new_lines)` of every change from
+    /// `old` in `parent` to `new` in `commit`.
+    fn diff_hunks(
+        &self,
+        commit: &Commit,
+        parent: &Commit,
+        old: &Path,
+        new: &Path,
+    ) -> Result<Vec<(usize, usize, usize, usize)>> {
+        let old_blob = blob(self.git, parent, old)?;
+        let new_blob = blob(self.git, commit, new)?;
+        let mut opts = DiffOptions::new();
+        opts.context_lines(0)
+            .ignore_whitespace(self.tuning.ignore_whitespace);
+        let patch = Patch::from_blobs(&old_blob, Some(old), &new_blob, Some(new), Some(&mut opts))?;
+        let mut out = Vec::new();
+        for i in 0..patch.num_hunks() {
+            let (h, _) = patch.hunk(i)?;
+            out.push((
+                h.old_start() as usize,
+                h.old_lines() as usize,
+                h.new_start() as usize,
+                h.new_lines() as usize,
+            ));
+        }
+        Ok(out)
+    }
+
+    /// `path` in `commit`, normalised for move detection.
+    fn text(&mut self, commit: &Commit, path: &Path) -> Result<Rc<Vec<String>>> {
+        let key = (commit.id(), path.to_path_buf());
+        if let Some(t) = self.texts.get(&key) {
+            return Ok(t.clone());
+        }
+        let blob = blob(self.git, commit, path)?;
+        let ws = self.tuning.ignore_whitespace;
+        let text: Vec<String> = String::from_utf8_lossy(blob.content())
+            .lines()
+            .map(|l| normalise(l, ws))
+            .collect();
+        let text = Rc::new(text);
+        self.texts.insert(key, text.clone());
+        Ok(text)
+    }
+
+    /// The old version of the file itself and, with `--detect-moves
+    /// commit`, of the other files `commit` changed.
+    fn move_sources(
+        &mut self,
+        commit: &Commit,
+        parent: &Commit,
+        old_path: Option<&Path>,
+    ) -> Result<Rc<MoveSources>> {
+        let key = (commit.id(), old_path.unwrap_or(Path::new("")).to_path_buf());
+        if let Some(s) = self.sources.get(&key) {
+            return Ok(s.clone());
+        }
+        let mut paths: Vec<PathBuf> = old_path.map(Path::to_path_buf).into_iter().collect();
+        if self.tuning.moves == MoveDetection::Commit {
+            for p in self.changed(commit, parent)?.iter() {
+                if !paths.contains(p) {
+                    paths.push(p.clone());
+                }
+            }
+        }
+        let mut lines = Vec::new();
+        let mut index: HashMap<String, Vec<(usize, usize)>> = HashMap::new();
+        for (s, path) in paths.iter().enumerate() {
+            let text = self.text(parent, path)?;
+            for (l, line) in text.iter().enumerate() {
+                if !line.is_empty() {
+                    index.entry(line.clone()).or_default().push((s, l));
+                }
+            }
+            lines.push(text);
+        }
+        let sources = Rc::new(MoveSources {
+            paths,
+            lines,
+            index,
+        });
+        self.sources.insert(key, sources.clone());
+        Ok(sources)
+    }
+
+    /// Old paths of the files `commit` changed or deleted, unless it
+    /// changed too many to search.
+    fn changed(&mut self, commit: &Commit, parent: &Commit) -> Result<Rc<Vec<PathBuf>>> {
+        if let Some(s) = self.changed.get(&commit.id()) {
+            return Ok(s.clone());
+        }
+        let old_tree = parent.tree()?;
+        let diff = self
+            .git
+            .diff_tree_to_tree(Some(&old_tree), Some(&commit.tree()?), None)?;
+        let paths: Vec<PathBuf> = if diff.deltas().len() > MAX_COPY_SOURCES {
+            Vec::new()
+        } else {
+            diff.deltas()
+                .filter_map(|d| d.old_file().path().map(Path::to_path_buf))
+                .filter(|p| old_tree.get_path(p).is_ok())
+                .collect()
+        };
+        let paths = Rc::new(paths);
+        self.changed.insert(commit.id(), paths.clone());
+        Ok(paths)
+    }
+}
+
+/// Where `path` in `commit` was in `parent`: the same path, or the source
+/// of a rename; `None` for a new file.
+fn old_path(
+    git: &Repository,
+    commit: &Commit,
+    parent: &Commit,
+    path: &Path,
+) -> Result<Option<PathBuf>> {
+    let old_tree = parent.tree()?;
+    if old_tree.get_path(path).is_ok() {
+        return Ok(Some(path.to_path_buf()));
+    }
+    let mut diff = git.diff_tree_to_tree(Some(&old_tree), Some(&commit.tree()?), None)?;
+    diff.find_similar(Some(DiffFindOptions::new().renames(true)))?;
+    Ok(diff
+        .deltas()
+        .find(|d| d.new_file().path() == Some(path) && d.old_file().path() != Some(path))
+        .and_then(|d| d.old_file().path().map(Path::to_path_buf))
+        .filter(|p| old_tree.get_path(p).is_ok()))
+}
+
+/// The line (1-based) in the old version that new line `line` replaced,
+/// matching lines of a changed block in proportion; `None` for lines that
+/// were only added.
+fn old_line(hunks: &[(usize, usize, usize, usize)], line: usize) -> Option<usize> {
+    // An empty side's start is the line before the change.
+    let end = |start: usize, len: usize| if len == 0 { start + 1 } else { start + len };
+    let mut shift = 0i64;
+    for &(os, ol, ns, nl) in hunks {
+        if line < ns || nl == 0 && line == ns {
+            break;
+        }
+        if line < ns + nl {
+            return (ol > 0).then(|| os + (line - ns) * ol / nl);
+        }
+        shift = end(os, ol) as i64 - end(ns, nl) as i64;
+    }
+    usize::try_from(line as i64 + shift).ok().filter(|l| *l > 0)
+}
+
+/// Blocks of `targets` still `open` found verbatim in `sources`, as
+/// (source, 0-based line) per target line. A block needs `min_score(source)`
+/// alphanumeric characters so stray braces and blank lines don't count.
+fn find_moves(
+    targets: &[String],
+    open: &[bool],
+    sources: &MoveSources,
+    min_score: impl Fn(usize) -> usize,
+) -> Vec<Option<(usize, usize)>> {
+    let mut found = vec![None; targets.len()];
+    let mut i = 0;
+    while i < targets.len() {
+        let Some(candidates) = sources.index.get(&targets[i]).filter(|_| open[i]) else {
+            i += 1;
+            continue;
+        };
+        let (mut len, mut from) = (0, (0, 0));
+        for &(s, l) in candidates.iter().take(64) {
+            let k = (0..)
+                .take_while(|&k| {
+                    i + k < targets.len()
+                        && open[i + k]
+                        && sources.lines[s].get(l + k) == Some(&targets[i + k])
+                })
+                .count();
+            if k > len {
+                (len, from) = (k, (s, l));
+            }
+        }
+        let score: usize = targets[i..i + len]
+            .iter()
+            .map(|t| t.chars().filter(|c| c.is_alphanumeric()).count())
+            .sum();
+        if len > 0 && score >= min_score(from.0) {
+            for k in 0..len {
+                found[i + k] = Some((from.0, from.1 + k));
+            }
+            i += len;
+        } else {
+            i += 1;
+        }
+    }
+    found
+}
+
+/// A line as compared for moves: without trailing whitespace, or without
+/// any whitespace when blame ignores it.
+fn normalise(line: &str, ignore_whitespace: bool) -> String {
+    if ignore_whitespace {
+        line.split_whitespace().collect()
+    } else {
+        line.trim_end().to_string()
+    }
+}
+
+fn blob<'r>(git: &'r Repository, commit: &Commit, path: &Path) -> Result<git2::Blob<'r>> {
+    Ok(commit
+        .tree()?
+        .get_path(path)?
+        .to_object(git)?
+        .peel_to_blob()?)
+}
+
+pub fn blame_counts(repo: &RepoExt, file: &Path) -> Result<BTreeMap<String, usize>> {
     let mut counts: BTreeMap<String, usize> = BTreeMap::new();
-    for h in blame.iter() {
-        let email = h.final_signature().email().unwrap_or("unknown").to_string();
-        *counts.entry(email).or_default() += h.lines_in_hunk() as usize;
+    for h in blame_lines(repo, file, None)? {
+        *counts.entry(h.author).or_default() += h.lines;
     }
     Ok(counts)
 }
+
+/// A run of lines last changed by the same commit.
+#[derive(Debug, Clone)]
+pub struct BlamedLines {
+    pub author: String,
+    pub lines: usize,
+    pub commit: Oid,
+    /// Author time of `commit`.
+    pub time: DateTime<Local>,
+}
+
+/// Blame `file` at the tip, restricted to `range` (1-based, inclusive)
+/// when given. Bot lines are skipped per `--bots`.
+pub fn blame_lines(
+    repo: &RepoExt,
+    file: &Path,
+    range: Option<LineSpan>,
+) -> Result<Vec<BlamedLines>> {
+    let mut out = Vec::new();
+    if repo.is_empty() {
+        return Ok(out);
+    }
+    for h in hunks(repo.repo(), repo.tip(), file, range, repo.blame_tuning())? {
+        let author = repo.identity(&h.signature);
+        if repo.bots().skips(&author) {
+            continue;
+        }
+        out.push(BlamedLines {
+            author: author.to_string(),
+            lines: h.lines,
+            commit: h.commit,
+            time: Local
+                .timestamp_opt(h.signature.when().seconds(), 0)
+                .single()
+                .unwrap_or_else(Local::now),
+        });
+    }
+    Ok(out)
+}
+
+/// Lines `start..=end` of a file (1-based).
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Serialize)]
+pub struct LineSpan {
+    pub start: usize,
+    pub end: usize,
+}
+
+/// Text of `file` at the tip.
+fn tip_text(repo: &RepoExt, file: &Path) -> Result<String> {
+    if repo.is_empty() {
+        bail!("{} is not in the selected tip", file.display());
+    }
+    let tree = repo.repo().find_commit(repo.tip())?.tree()?;
+    let entry = tree
+        .get_path(file)
+        .with_context(|| format!("{} is not in the selected tip", file.display()))?;
+    let blob = entry.to_object(repo.repo())?.peel_to_blob()?;
+    Ok(String::from_utf8_lossy(blob.content()).into_owned())
+}
+
+/// Resolve `git blame -L` specs against the file at the tip: `120,200`,
+/// `120,+40`, `120,-40` (the 40 lines ending at 120), `120,` (to the end)
+/// or `:name` for the function `name`.
+/// Overlapping spans are merged.
+pub fn line_spans(repo: &RepoExt, file: &Path, specs: &[String]) -> Result<Vec<LineSpan>> {
+    let text = tip_text(repo, file)?;
+    let lines: Vec<&str> = text.lines().collect();
+    let mut spans = Vec::new();
+    for spec in specs {
+        let span = match spec.strip_prefix(':') {
+            Some(name) => function_span(&lines, name)
+                .with_context(|| format!("no function '{name}' in {}", file.display()))?,
+            None => numeric_span(spec, lines.len())?,
+        };
+        spans.push(span);
+    }
+    spans.sort();
+    let mut merged: Vec<LineSpan> = Vec::new();
+    for s in spans {
+        match merged.last_mut() {
+            Some(last) if s.start <= last.end + 1 => last.end = last.end.max(s.end),
+            _ => merged.push(s),
+        }
+    }
+    Ok(merged)
+}
+
+fn numeric_span(spec: &str, len: usize) -> Result<LineSpan> {
+    let bad = || {
+        format!(
+            "bad line range '{spec}' (expected START,END, START,+COUNT, START,-COUNT or :FUNCTION)"
+        )
+    };
+    let (start, end) = spec.split_once(',').with_context(bad)?;
+    let start: usize = if start.trim().is_empty() {
+        1
+    } else {
+        start.trim().parse().with_context(bad)?
+    };
+    if start == 0 {
+        bail!(bad());
+    }
+    let end = end.trim();
+    let count = |n: &str| -> Result<usize> {
+        match n.parse::<usize>().with_context(bad)? {
+            0 => bail!("line range '{spec}' is empty"),
+            n => Ok(n),
+        }
+    };
+    // `START,-COUNT` ends at START, like git; the others start there.
+    let (start, end) = if let Some(n) = end.strip_prefix('+') {
+        (start, start + count(n)? - 1)
+    } else if let Some(n) = end.strip_prefix('-') {
+        ((start + 1).saturating_sub(count(n)?).max(1), start)
+    } else if end.is_empty() {
+        (start, len.max(start))
+    } else {
+        let end: usize = end.parse().with_context(bad)?;
+        if end < start {
+            bail!("line range '{spec}' ends before it starts");
+        }
+        (start, end)
+    };
+    if start > len {
+        bail!("line {start} is past the end of the file ({len} lines)");
+    }
+    Ok(LineSpan {
+        start,
+        end: end.min(len),
+    })
+}
+
+/// Keywords that introduce a named definition in common languages.
+const DEFINERS: &[&str] = &[
+    "fn",
+    "def",
+    "func",
+    "function",
+    "sub",
+    "class",
+    "struct",
+    "enum",
+    "trait",
+    "impl",
+    "interface",
+    "module",
+    "macro_rules!",
+];
+
+/// The definition of `name`: its first line, through the matching closing
+/// brace or, for indentation-based languages, the last line indented
+/// deeper than it.
+fn function_span(lines: &[&str], name: &str) -> Option<LineSpan> {
+    let defines = |line: &str| {
+        let words: Vec<&str> = line
+            .split(|c: char| !(c.is_alphanumeric() || c == '_' || c == '!'))
+            .filter(|w| !w.is_empty())
+            .collect();
+        words
+            .windows(2)
+            .any(|w| DEFINERS.contains(&w[0]) && w[1] == name)
+    };
+    // A C-style definition: `name(` not ending in `;`, not a call in an
+    // expression (no leading `=`/`return`).
+    let c_style = |line: &str| {
+        let t = line.trim_end();
+        let Some(i) = t.find(&format!("{name}(")) else {
+            return false;
+        };
+        let before = &t[..i];
+        let word_start = before
+            .chars()
+            .last()
+            .is_none_or(|c| !(c.is_alphanumeric() || c == '_'));
+        word_start
+            && !t.ends_with(';')
+            && !before.contains('=')
+            && !before.trim_start().starts_with("return")
+            && !line.starts_with([' ', '\t'])
+    };
+    let start = lines
+        .iter()
+        .position(|l| defines(l))
+        .or_else(|| lines.iter().position(|l| c_style(l)))?;
+
+    let indent = |l: &str| l.len() - l.trim_start().len();
+    let colon = lines[start].trim_end().ends_with(':');
+    let brace = (start..lines.len().min(start + 3)).find(|&i| lines[i].contains('{'));
+    let end = match brace {
+        Some(open) if !colon => {
+            let mut depth = 0i64;
+            (open..lines.len())
+                .find(|&i| {
+                    for c in lines[i].chars() {
+                        match c {
+                            '{' => depth += 1,
+                            '}' => depth -= 1,
+                            _ => {}
+                        }
+                    }
+                    depth <= 0
+                })
+                .map_or(lines.len(), |i| i + 1)
+        }
+        // Indentation-based: up to the last line indented deeper than the
+        // definition, before the next one that isn't.
+        _ => {
+            let base = indent(lines[start]);
+            let stop = (start + 1..lines.len())
+                .find(|&i| !lines[i].trim().is_empty() && indent(lines[i]) <= base)
+                .unwrap_or(lines.len());
+            (start + 1..stop)
+                .rev()
+                .find(|&i| !lines[i].trim().is_empty())
+                .map_or(start + 1, |i| i + 1)
+        }
+    };
+    Some(LineSpan {
+        start: start + 1,
+        end,
+    })
+}
+
+/// Line-age buckets, youngest first: upper bound in days and label.
+pub const AGE_BUCKETS: [(i64, &str); 6] = [
+    (30, "< 1 month"),
+    (182, "1–6 months"),
+    (365, "6–12 months"),
+    (730, "1–2 years"),
+    (1826, "2–5 years"),
+    (i64::MAX, "5+ years"),
+];
+
+/// Lines in one age bucket.
+#[derive(Debug, Clone, Serialize)]
+pub struct AgeBucket {
+    pub label: String,
+    pub lines: usize,
+    pub share: f64,
+}
+
+/// The last commit an author made to the blamed lines.
+#[derive(Debug, Clone, Serialize)]
+pub struct LastChange {
+    pub commit: String,
+    pub date: DateTime<Local>,
+    pub summary: String,
+}
+
+/// One author's part of the blamed lines.
+#[derive(Debug, Clone, Serialize)]
+pub struct AuthorShare {
+    pub author: String,
+    pub lines: usize,
+    /// Of the blamed lines (0..1).
+    pub share: f64,
+    /// Median age of the author's lines, in days before the tip.
+    pub median_age_days: i64,
+    pub last_change: LastChange,
+}
+
+/// Ownership and age of a file, or of some of its lines.
+#[derive(Debug, Clone)]
+pub struct BlameSummary {
+    pub lines: usize,
+    /// Most lines first.
+    pub authors: Vec<AuthorShare>,
+    pub ages: Vec<AgeBucket>,
+}
+
+/// Blame `spans` of `file` (the whole file when empty) and summarise
+/// per-author shares and line ages, measured from the tip's commit time.
+pub fn summarize(repo: &RepoExt, file: &Path, spans: &[LineSpan]) -> Result<BlameSummary> {
+    let mut hunks = Vec::new();
+    if spans.is_empty() {
+        hunks = blame_lines(repo, file, None)?;
+    }
+    for s in spans {
+        hunks.extend(blame_lines(repo, file, Some(*s))?);
+    }
+    let now = commit_time(&repo.repo().find_commit(repo.tip())?);
+    let age = |h: &BlamedLines| (now - h.time).num_days().max(0);
+
+    let total: usize = hunks.iter().map(|h| h.lines).sum();
+    let mut ages = vec![0usize; AGE_BUCKETS.len()];
+    let mut by_author: HashMap<&str, Vec<&BlamedLines>> = HashMap::new();
+    for h in &hunks {
+        let b = AGE_BUCKETS
+            .iter()
+            .position(|(max, _)| age(h) < *max)
+            .unwrap_or(AGE_BUCKETS.len() - 1);
+        ages[b] += h.lines;
+        by_author.entry(&h.author).or_default().push(h);
+    }
+
+    let share = |n: usize| {
+        if total > 0 {
+            n as f64 / total as f64
+        } else {
+            0.0
+        }
+    };
+    let mut authors = Vec::new();
+    for (author, mut hs) in by_author {
+        let lines: usize = hs.iter().map(|h| h.lines).sum();
+        // Median over lines, not hunks.
+        hs.sort_by_key(|h| age(h));
+        let mut seen = 0;
+        let median_age_days = hs
+            .iter()
+            .find(|h| {
+                seen += h.lines;
+                seen * 2 >= lines
+            })
+            .map_or(0, |h| age(h));
+        let newest = hs.iter().max_by_key(|h| h.time).expect("author has lines");
+        let summary = repo
+            .repo()
+            .find_commit(newest.commit)
+            .ok()
+            .and_then(|c| c.summary().map(str::to_string))
+            .unwrap_or_default();
+        authors.push(AuthorShare {
+            author: author.to_string(),
+            lines,
+            share: share(lines),
+            median_age_days,
+            last_change: LastChange {
+                commit: newest.commit.to_string(),
+                date: newest.time,
+                summary,
+            },
+        });
+    }
+    authors.sort_by(|a, b| b.lines.cmp(&a.lines).then_with(|| a.author.cmp(&b.author)));
+
+    Ok(BlameSummary {
+        lines: total,
+        authors,
+        ages: AGE_BUCKETS
+            .iter()
+            .zip(ages)
+            .map(|((_, label), lines)| AgeBucket {
+                label: label.to_string(),
+                lines,
+                share: share(lines),
+            })
+            .collect(),
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::domain::git::RepoOptions;
+    use crate::domain::test_repo::TestRepo;
+
+    fn span(start: usize, end: usize) -> LineSpan {
+        LineSpan { start, end }
+    }
+
+    /// Commit of every line of `file` at HEAD.
+    fn lines_by(t: &TestRepo, file: &str, tuning: &BlameTuning) -> Vec<Oid> {
+        let tip = t.repo.head().unwrap().target().unwrap();
+        hunks(&t.repo, tip, Path::new(file), None, tuning)
+            .unwrap()
+            .into_iter()
+            .flat_map(|h| std::iter::repeat_n(h.commit, h.lines))
+            .collect()
+    }
+
+    #[test]
+    fn numeric_spans_follow_git_blame() {
+        assert_eq!(numeric_span("3,7", 10).unwrap(), span(3, 7));
+        assert_eq!(numeric_span("3,+2", 10).unwrap(), span(3, 4));
+        assert_eq!(numeric_span("5,-2", 10).unwrap(), span(4, 5));
+        assert_eq!(numeric_span("2,-9", 10).unwrap(), span(1, 2));
+        assert_eq!(numeric_span("8,", 10).unwrap(), span(8, 10));
+        assert_eq!(numeric_span(",2", 10).unwrap(), span(1, 2));
+        // Clamped to the file, but it must start inside it.
+        assert_eq!(numeric_span("9,+5", 10).unwrap(), span(9, 10));
+        assert!(numeric_span("11,", 10).is_err());
+    }
+
+    #[test]
+    fn bad_numeric_spans_are_errors() {
+        for spec in ["3,+0", "3,-0", "7,3", "0,2", "3", "a,4", "3,x", "3,+-1"] {
+            assert!(numeric_span(spec, 10).is_err(), "{spec}");
+        }
+    }
+
+    #[test]
+    fn function_spans_by_braces_or_indentation() {
+        let rust = [
+            "use x;",
+            "",
+            "pub fn parse(s: &str)",
+            "    -> u32",
+            "{",
+            "    if s.is_empty() { 0 } else { 1 }",
+            "}",
+            "fn other() {}",
+        ];
+        assert_eq!(function_span(&rust, "parse"), Some(span(3, 7)));
+        assert_eq!(function_span(&rust, "other"), Some(span(8, 8)));
+        assert_eq!(function_span(&rust, "pars"), None);
+
+        let python = [
+            "class A:",
+            "    def run(self):",
+            "        x = 1",
+            "",
+            "        return x",
+            "",
+            "    def stop(self):",
+            "        pass",
+        ];
+        assert_eq!(function_span(&python, "run"), Some(span(2, 5)));
+        assert_eq!(function_span(&python, "A"), Some(span(1, 8)));
+
+        let c = [
+            "int x = add(1, 2);",
+            "static int add(int a, int b) {",
+            "  return a + b;",
+            "}",
+        ];
+        assert_eq!(function_span(&c, "add"), Some(span(2, 4)));
+    }
+
+    #[test]
+    fn line_specs_resolve_against_the_tip_and_merge() {
+        let mut t = TestRepo::new();
+        let text = "fn a() {\n    1\n}\n\nfn b() {\n    2\n}\n";
+        t.commit("A <a@x.io>", "add", &[("lib.rs", Some(text))]);
+        let repo = t.open();
+        let file = Path::new("lib.rs");
+        let specs = |s: &[&str]| s.iter().map(|s| s.to_string()).collect::<Vec<_>>();
+
+        assert_eq!(
+            line_spans(&repo, file, &specs(&[":b"])).unwrap(),
+            [span(5, 7)]
+        );
+        assert_eq!(
+            line_spans(&repo, file, &specs(&[":a", "3,5", "7,+1"])).unwrap(),
+            [span(1, 5), span(7, 7)]
+        );
+        assert!(line_spans(&repo, file, &specs(&[":c"])).is_err());
+        assert!(line_spans(&repo, Path::new("nope.rs"), &specs(&["1,2"])).is_err());
+    }
+
+    #[test]
+    fn ignored_revisions_give_lines_back_to_the_parent() {
+        let mut t = TestRepo::new();
+        let a = t.commit("A <a@x.io>", "add", &[("f.rs", Some("one\ntwo\nthree\n"))]);
+        let fmt = t.commit(
+            "B <b@x.io>",
+            "fmt",
+            &[("f.rs", Some("one\nTWO\nthree\nfour\n"))],
+        );
+        assert_eq!(
+            lines_by(&t, "f.rs", &BlameTuning::default()),
+            [a, fmt, a, fmt]
+        );
+
+        let settings = BlameSettings {
+            ignore_revs: vec![fmt.to_string()],
+            ..BlameSettings::default()
+        };
+        let repo = t.open_with(RepoOptions::default().blame(settings));
+        // The rewritten line goes to its author; the added one has none.
+        assert_eq!(lines_by(&t, "f.rs", repo.blame_tuning()), [a, a, a, fmt]);
+    }
+
+    #[test]
+    fn moved_blocks_keep_their_author() {
+        let block = "let first_value = compute_something();\nlet second_value = compute_other();\n";
+        let rest = "fn unrelated_function_name() {}\nfn another_unrelated_name() {}\n";
+        let mut t = TestRepo::new();
+        let a = t.commit(
+            "A <a@x.io>",
+            "add",
+            &[("f.rs", Some(&format!("{block}{rest}")))],
+        );
+        let mv = t.commit(
+            "B <b@x.io>",
+            "move",
+            &[("f.rs", Some(&format!("{rest}{block}")))],
+        );
+
+        let plain = lines_by(&t, "f.rs", &BlameTuning::default());
+        assert_eq!(plain[2..], [mv, mv]);
+        let tuning = BlameTuning {
+            moves: MoveDetection::File,
+            ..BlameTuning::default()
+        };
+        assert_eq!(lines_by(&t, "f.rs", &tuning), [a, a, a, a]);
+    }
+
+    #[test]
+    fn whitespace_only_changes_can_be_skipped() {
+        let mut t = TestRepo::new();
+        let a = t.commit("A <a@x.io>", "add", &[("f.py", Some("if x:\n  y()\n"))]);
+        let ws = t.commit(
+            "B <b@x.io>",
+            "indent",
+            &[("f.py", Some("if x:\n    y()\n"))],
+        );
+        assert_eq!(lines_by(&t, "f.py", &BlameTuning::default()), [a, ws]);
+        let tuning = BlameTuning {
+            ignore_whitespace: true,
+            ..BlameTuning::default()
+        };
+        assert_eq!(lines_by(&t, "f.py", &tuning), [a, a]);
+    }
+
+    #[test]
+    fn long_chains_of_ignored_revisions_stop_at_the_depth_limit() {
+        let mut t = TestRepo::new();
+        let a = t.commit("A <a@x.io>", "add", &[("f.rs", Some("keep\nv0\n"))]);
+        let mut ignored = Vec::new();
+        for i in 1..=MAX_DEPTH + 5 {
+            let text = format!("keep\nv{i}\n");
+            ignored.push(t.commit("B <b@x.io>", "bump", &[("f.rs", Some(&text))]));
+        }
+        let tuning = BlameTuning {
+            ignore_revs: ignored.iter().copied().collect(),
+            ..BlameTuning::default()
+        };
+        // Traced back MAX_DEPTH commits, then left where blame put it.
+        let lines = lines_by(&t, "f.rs", &tuning);
+        assert_eq!(lines[0], a);
+        assert_eq!(lines[1], ignored[ignored.len() - 1 - MAX_DEPTH]);
+
+        // Within the limit the chain is followed to the end.
+        let tuning = BlameTuning {
+            ignore_revs: ignored[ignored.len() - 3..].iter().copied().collect(),
+            ..BlameTuning::default()
+        };
+        assert_eq!(lines_by(&t, "f.rs", &tuning)[1], ignored[ignored.len() - 4]);
+    }
+}
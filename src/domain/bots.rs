@@ -0,0 +1,206 @@
+use anyhow::{bail, Context, Result};
+use chrono::{DateTime, Local};
+use regex_automata::meta::Regex;
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
+
+use crate::domain::engine::{Analyser, CommitCtx};
+use crate::domain::identity::Identity;
+
+/// Names (or email local parts) of well-known automation accounts.
+pub const KNOWN_BOTS: &[&str] = &[
+    "dependabot",
+    "dependabot-preview",
+    "renovate",
+    "renovate-bot",
+    "github-actions",
+    "actions-user",
+    "greenkeeper",
+    "snyk-bot",
+    "mergify",
+    "semantic-release-bot",
+    "release-please",
+    "pre-commit-ci",
+    "allcontributors",
+    "imgbot",
+    "codecov",
+    "gitlab-bot",
+    "crowdin-bot",
+    "weblate",
+];
+
+/// What to do with commits (and blamed lines) by bots.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum BotMode {
+    /// Treat bots like everyone else.
+    #[default]
+    Include,
+    /// Drop bot commits and lines from every analysis.
+    Exclude,
+    /// Drop them from the analyses but list bot activity on its own.
+    Separate,
+}
+
+impl BotMode {
+    pub fn parse(s: &str) -> Result<Self> {
+        Ok(match s.to_ascii_lowercase().as_str() {
+            "include" => Self::Include,
+            "exclude" => Self::Exclude,
+            "separate" => Self::Separate,
+            _ => bail!("unknown bot mode '{s}' (use include, exclude or separate)"),
+        })
+    }
+}
+
+/// Decides whether an identity is an automation account: a `[bot]`
+/// suffix, a known bot name/email, or one of the user's patterns
+/// (matched against `Name <email>`).
+#[derive(Debug, Clone, Default)]
+pub struct BotFilter {
+    mode: BotMode,
+    patterns: Vec<Regex>,
+}
+
+impl BotFilter {
+    pub fn new(mode: BotMode, patterns: &[String]) -> Result<Self> {
+        let patterns = patterns
+            .iter()
+            .map(|p| Regex::new(p).with_context(|| format!("bad bot pattern '{p}'")))
+            .collect::<Result<_>>()?;
+        Ok(Self { mode, patterns })
+    }
+
+    pub fn mode(&self) -> BotMode {
+        self.mode
+    }
+
+    /// True when bots are kept out of the analyses.
+    pub fn filters(&self) -> bool {
+        self.mode != BotMode::Include
+    }
+
+    /// Bot that should be left out of the analyses.
+    pub fn skips(&self, id: &Identity) -> bool {
+        self.filters() && self.is_bot(id)
+    }
+
+    pub fn is_bot(&self, id: &Identity) -> bool {
+        let name = id.name.to_ascii_lowercase();
+        let email = id.email.to_ascii_lowercase();
+        // GitHub noreply addresses look like `12345+dependabot[bot]@users...`.
+        let local = email.split('@').next().unwrap_or("");
+        let local = local.split_once('+').map_or(local, |(_, l)| l);
+
+        if name.ends_with("[bot]") || local.ends_with("[bot]") {
+            return true;
+        }
+        if KNOWN_BOTS.iter().any(|b| name == *b || local == *b) {
+            return true;
+        }
+        let key = id.to_string();
+        self.patterns.iter().any(|re| re.is_match(&key))
+    }
+}
+
+/// Commits by bots, for `--bots separate` reports.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct BotMeta {
+    pub commits: usize,
+    pub first: DateTime<Local>,
+    pub last: DateTime<Local>,
+}
+
+/// Collects bot commits the engine kept away from the other analysers.
+#[derive(Debug, Default)]
+pub struct BotActivity {
+    pub bots: BTreeMap<String, BotMeta>,
+}
+
+impl Analyser for BotActivity {
+    fn wants_bots(&self) -> bool {
+        true
+    }
+
+    fn visit(&mut self, c: &CommitCtx) {
+        if !c.by_bot {
+            return;
+        }
+        let e = self.bots.entry(c.author.clone()).or_insert(BotMeta {
+            commits: 0,
+            first: c.time,
+            last: c.time,
+        });
+        e.commits += 1;
+        e.first = e.first.min(c.time);
+        e.last = e.last.max(c.time);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::domain::git::RepoOptions;
+    use crate::domain::stats::collect_commits;
+    use crate::domain::test_repo::TestRepo;
+
+    fn id(name: &str, email: &str) -> Identity {
+        Identity {
+            name: name.into(),
+            email: email.into(),
+        }
+    }
+
+    #[test]
+    fn recognises_bot_accounts() {
+        let f = BotFilter::new(BotMode::Exclude, &["^CI <".to_string()]).unwrap();
+        assert!(f.is_bot(&id(
+            "dependabot[bot]",
+            "49699333+dependabot[bot]@users.noreply.github.com"
+        )));
+        assert!(f.is_bot(&id("Renovate", "renovate@whitesourcesoftware.com")));
+        assert!(f.is_bot(&id("someone", "12+github-actions@users.noreply.github.com")));
+        assert!(f.is_bot(&id("CI", "ci@example.com")));
+        assert!(!f.is_bot(&id("Bot Builder", "bob@example.com")));
+        assert!(!f.is_bot(&id("robert", "renovated@example.com")));
+    }
+
+    #[test]
+    fn modes_and_bad_patterns() {
+        let bot = id("dependabot[bot]", "x@y");
+        assert!(!BotFilter::new(BotMode::Include, &[]).unwrap().skips(&bot));
+        assert!(BotFilter::new(BotMode::Separate, &[]).unwrap().skips(&bot));
+        assert!(BotFilter::new(BotMode::Exclude, &["(".to_string()]).is_err());
+        assert_eq!(BotMode::parse("SEPARATE").unwrap(), BotMode::Separate);
+        assert!(BotMode::parse("drop").is_err());
+    }
+
+    #[test]
+    fn separate_mode_reports_bots_apart() {
+        let mut t = TestRepo::new();
+        t.commit("Ann <ann@x.io>", "one", &[("a", Some("1\n"))]);
+        t.commit(
+            "renovate[bot] <bot@renovateapp.com>",
+            "deps",
+            &[("a", Some("2\n"))],
+        );
+        t.commit(
+            "renovate[bot] <bot@renovateapp.com>",
+            "deps",
+            &[("a", Some("3\n"))],
+        );
+
+        let all = collect_commits(&t.open(), usize::MAX).unwrap();
+        assert_eq!(all.data.len(), 2);
+
+        let repo = t.open_with(RepoOptions::default().bots(BotMode::Separate));
+        let stats = collect_commits(&repo, usize::MAX).unwrap();
+        assert_eq!(stats.data.len(), 1);
+        let bot = &stats.bots["renovate[bot] <bot@renovateapp.com>"];
+        assert_eq!(bot.commits, 2);
+
+        let repo = t.open_with(RepoOptions::default().bots(BotMode::Exclude));
+        let stats = collect_commits(&repo, usize::MAX).unwrap();
+        assert_eq!(stats.data.len(), 1);
+        assert!(stats.bots.is_empty());
+    }
+}
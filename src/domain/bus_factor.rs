@@ -1,22 +1,21 @@
-use anyhow::{bail, Context, Result};
-use git2::{BlameOptions, DiffOptions, Repository, Sort};
-use serde_json::json;
+use anyhow::{bail, Result};
+use git2::Repository;
+use serde::{Deserialize, Serialize};
 use std::collections::{BTreeMap, HashMap};
 use std::path::{Component, Path};
-use std::process::Command;
 
 use rayon::prelude::*;
 
-pub const ALLOWED_EXT: &[&str] = &[
-    "rs", "ts", "tsx", "js", "jsx", "java", "kt", "kts", "go", "py", "rb", "swift", "c", "h",
-    "cpp", "hpp", "cc", "hh", "cs", "php", "scala", "m", "mm", "sh", "bash", "zsh", "fish", "sql",
-    "xml", "yml", "yaml", "toml", "json", "lock", "lua", "vim", "conf", "ini", "cfg", "md", "txt",
-];
+use crate::domain::blame;
+use crate::domain::engine::{self, Analyser, CommitCtx};
+use crate::domain::git::RepoExt;
+use crate::domain::paths;
 
 /// Default minimum size to report (lines in blame mode / touches in fast mode).
 const DEFAULT_MIN_TOTAL: usize = 25;
 
-#[derive(Debug, Clone)]
+/// Ownership of one file by its dominant author.
+#[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct BusScore {
     pub file: String,
     pub top_author: String,
@@ -25,7 +24,7 @@ pub struct BusScore {
 }
 
 /// Directory-level score
-#[derive(Debug, Clone)]
+#[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct DirScore {
     pub dir: String,
     pub top_author: String,
@@ -33,37 +32,59 @@ pub struct DirScore {
     pub total: usize, // sum of lines/touches for the directory
 }
 
+/// File selection for ownership and churn scans. Build with
+/// `ScanOpts::default()` and the chained setters.
 #[derive(Debug, Clone)]
 pub struct ScanOpts {
     pub all: bool,
+    /// Replaces the built-in extension allow-list when non-empty.
+    pub extensions: Vec<String>,
     pub include_ext: Vec<String>,
     pub min_total: usize, // lines (blame) or touches (fast)
 }
 
+impl ScanOpts {
+    /// Include every file, ignoring the extension allow-list.
+    pub fn all(mut self, all: bool) -> Self {
+        self.all = all;
+        self
+    }
+
+    /// Replace the built-in extension allow-list.
+    pub fn extensions(mut self, exts: impl IntoIterator<Item = impl Into<String>>) -> Self {
+        self.extensions = exts.into_iter().map(Into::into).collect();
+        self
+    }
+
+    /// Allow these extensions on top of the allow-list.
+    pub fn include_ext(mut self, exts: impl IntoIterator<Item = impl Into<String>>) -> Self {
+        self.include_ext = exts.into_iter().map(Into::into).collect();
+        self
+    }
+
+    /// Skip files with fewer lines (blame) or touches (fast) than this.
+    pub fn min_total(mut self, n: usize) -> Self {
+        self.min_total = n;
+        self
+    }
+
+    /// Extension allow-list check (see `paths::ALLOWED_EXT`).
+    pub fn ext_ok(&self, file: &str) -> bool {
+        paths::ext_ok(file, self.all, &self.extensions, &self.include_ext)
+    }
+}
+
 impl Default for ScanOpts {
     fn default() -> Self {
         Self {
             all: false,
+            extensions: Vec::new(),
             include_ext: Vec::new(),
             min_total: DEFAULT_MIN_TOTAL,
         }
     }
 }
 
-fn ext_ok(file: &str, opts: &ScanOpts) -> bool {
-    if opts.all {
-        return true;
-    }
-    let ext = Path::new(file).extension().and_then(|e| e.to_str());
-    match ext {
-        Some(e) => {
-            let e = e.to_ascii_lowercase();
-            ALLOWED_EXT.contains(&e.as_str()) || opts.include_ext.iter().any(|x| x == &e)
-        }
-        None => false,
-    }
-}
-
 /// Directory key of depth ("a/b") for a file path.
 fn dir_key(path_str: &str, depth: usize) -> String {
     let p = Path::new(path_str);
@@ -84,37 +105,30 @@ fn dir_key(path_str: &str, depth: usize) -> String {
     parts[..d].join("/")
 }
 
-/// List tracked files
-fn list_repo_files(repo_path: &str) -> Result<Vec<String>> {
-    let output = Command::new("git")
-        .arg("-C")
-        .arg(repo_path)
-        .arg("ls-files")
-        .output()
-        .context("failed to run `git ls-files`")?;
-    let files = String::from_utf8_lossy(&output.stdout);
-    Ok(files.lines().map(|s| s.to_string()).collect())
-}
-
 /// Parallel blame across files (accurate).
-pub fn compute_scores_parallel(repo_path: &str, opts: &ScanOpts) -> Result<Vec<BusScore>> {
-    let files = list_repo_files(repo_path)?;
+pub fn compute_scores_parallel(repo: &RepoExt, opts: &ScanOpts) -> Result<Vec<BusScore>> {
+    let repo_path = repo.repo().path();
+    let mailmap = repo.mailmap();
+    let bots = repo.bots();
+    let tip = repo.tip();
+    let tuning = repo.blame_tuning();
+    let files = repo.tracked_files()?;
     let scores: Vec<_> = files
         .par_iter()
         .filter_map(|file| {
-            if !ext_ok(file, opts) {
+            if !opts.ext_ok(file) {
                 return None;
             }
-            let repo = Repository::discover(repo_path).ok()?;
-            let mut blame_opts = BlameOptions::new();
-            let blame = repo
-                .blame_file(Path::new(file), Some(&mut blame_opts))
-                .ok()?;
+            let repo = Repository::open(repo_path).ok()?;
+            let hunks = blame::hunks(&repo, tip, Path::new(file), None, tuning).ok()?;
 
             let mut counts: BTreeMap<String, usize> = BTreeMap::new();
-            for h in blame.iter() {
-                let email = h.final_signature().email().unwrap_or("unknown").to_string();
-                *counts.entry(email).or_default() += h.lines_in_hunk() as usize;
+            for h in hunks {
+                let author = mailmap.resolve_signature(&h.signature);
+                if bots.skips(&author) {
+                    continue;
+                }
+                *counts.entry(author.to_string()).or_default() += h.lines;
             }
             let total: usize = counts.values().copied().sum();
             if total < opts.min_total {
@@ -142,113 +156,113 @@ pub fn compute_scores_parallel(repo_path: &str, opts: &ScanOpts) -> Result<Vec<B
     Ok(scores)
 }
 
-/// SUPER FAST heuristic: ownership by "touch counts" per author per file.
-pub fn compute_scores_fast(
-    repo: &Repository,
+/// Touch counts per author per file over the newest `max_commits` commits.
+#[derive(Debug)]
+pub struct TouchOwnership {
+    opts: ScanOpts,
     max_commits: Option<usize>,
-    opts: &ScanOpts,
-) -> Result<Vec<BusScore>> {
-    // file -> author -> touches
-    let mut touches: HashMap<String, HashMap<String, usize>> = HashMap::new();
-
-    let mut walk = repo.revwalk()?;
-    walk.push_head()?;
-    walk.set_sorting(Sort::TIME)?;
-
-    let mut seen = 0usize;
-    for oid in walk.flatten() {
-        if let Some(m) = max_commits {
-            if seen >= m {
-                break;
-            }
+    seen: usize,
+    pub touches: HashMap<String, FileTouches>,
+}
+
+/// Commits touching one file and each author's credit for them.
+#[derive(Debug, Default)]
+pub struct FileTouches {
+    pub commits: usize,
+    /// author -> touches (co-authors credited per `--coauthor-credit`)
+    pub by_author: HashMap<String, f64>,
+}
+
+impl FileTouches {
+    /// Top author and their share of the credit.
+    fn top(&self) -> Option<(String, f64)> {
+        top_share(&self.by_author)
+    }
+}
+
+fn top_share(credit: &HashMap<String, f64>) -> Option<(String, f64)> {
+    let sum: f64 = credit.values().sum();
+    credit
+        .iter()
+        .max_by(|a, b| a.1.total_cmp(b.1))
+        .filter(|_| sum > 0.0)
+        .map(|(a, n)| (a.clone(), n / sum))
+}
+
+impl TouchOwnership {
+    pub fn new(max_commits: Option<usize>, opts: ScanOpts) -> Self {
+        Self {
+            opts,
+            max_commits,
+            seen: 0,
+            touches: HashMap::new(),
         }
-        let commit = match repo.find_commit(oid) {
-            Ok(c) => c,
-            Err(_) => continue,
-        };
-        let email = commit.author().email().unwrap_or("unknown").to_string();
+    }
 
-        let tree = match commit.tree() {
-            Ok(t) => t,
-            Err(_) => continue,
-        };
-        if let Ok(parent) = commit.parent(0) {
-            let parent_tree = match parent.tree() {
-                Ok(t) => t,
-                Err(_) => continue,
-            };
-            let mut opt = DiffOptions::new();
-            if let Ok(diff) =
-                repo.diff_tree_to_tree(Some(&parent_tree), Some(&tree), Some(&mut opt))
-            {
-                for d in diff.deltas() {
-                    if let Some(path) = d.new_file().path().or_else(|| d.old_file().path()) {
-                        if let Some(p) = path.to_str() {
-                            if !ext_ok(p, opts) {
-                                continue;
-                            }
-                            *touches
-                                .entry(p.to_string())
-                                .or_default()
-                                .entry(email.clone())
-                                .or_default() += 1;
-                        }
-                    }
-                }
+    /// File scores (touches ≥ `min_total`), most dominated first.
+    pub fn into_scores(self) -> Vec<BusScore> {
+        let mut scores = Vec::<BusScore>::new();
+        for (file, t) in self.touches {
+            if t.commits < self.opts.min_total {
+                continue;
+            }
+            if let Some((top_author, ratio)) = t.top() {
+                scores.push(BusScore {
+                    file,
+                    top_author,
+                    ratio,
+                    total: t.commits,
+                });
             }
         }
-        seen += 1;
+
+        scores.sort_by(|a, b| {
+            b.ratio
+                .partial_cmp(&a.ratio)
+                .unwrap_or(std::cmp::Ordering::Equal)
+                .then_with(|| b.total.cmp(&a.total))
+        });
+        scores
     }
+}
 
-    let mut scores = Vec::<BusScore>::new();
-    for (file, by_author) in touches {
-        let total: usize = by_author.values().sum();
-        if total < opts.min_total {
-            continue;
-        }
-        if let Some((top_author, top)) = by_author.into_iter().max_by_key(|(_, n)| *n) {
-            scores.push(BusScore {
-                file,
-                top_author,
-                ratio: top as f64 / total as f64,
-                total,
-            });
+impl Analyser for TouchOwnership {
+    fn visit(&mut self, c: &CommitCtx) {
+        self.seen += 1;
+        for f in c.changes() {
+            if !self.opts.ext_ok(&f.path) {
+                continue;
+            }
+            let t = self.touches.entry(f.path.clone()).or_default();
+            t.commits += 1;
+            for (who, w) in &c.credits {
+                *t.by_author.entry(who.clone()).or_default() += w;
+            }
         }
     }
 
-    scores.sort_by(|a, b| {
-        b.ratio
-            .partial_cmp(&a.ratio)
-            .unwrap_or(std::cmp::Ordering::Equal)
-            .then_with(|| b.total.cmp(&a.total))
-    });
-    Ok(scores)
+    fn done(&self) -> bool {
+        self.max_commits.is_some_and(|m| self.seen >= m)
+    }
 }
 
-/// Backward-compatible map of warnings: only files above `threshold` (blame mode).
-pub fn bus_factor(
-    repo_path: &str,
-    _repo: &Repository,
-    threshold: f64,
+/// SUPER FAST heuristic: ownership by "touch counts" per author per file.
+pub fn compute_scores_fast(
+    repo: &RepoExt,
+    max_commits: Option<usize>,
     opts: &ScanOpts,
-) -> Result<BTreeMap<String, (String, f64)>> {
+) -> Result<Vec<BusScore>> {
+    Ok(engine::run(repo, TouchOwnership::new(max_commits, opts.clone()))?.into_scores())
+}
+
+/// Files whose top author owns more than `threshold` of the lines (blame mode).
+pub fn bus_factor(repo: &RepoExt, threshold: f64, opts: &ScanOpts) -> Result<Vec<BusScore>> {
     if !(0.0..=1.0).contains(&threshold) {
         bail!("threshold must be in [0.0, 1.0]");
     }
-    let scores = compute_scores_parallel(repo_path, opts)?;
-    let mut warnings = BTreeMap::new();
-    for s in scores.into_iter().filter(|s| s.ratio > threshold) {
-        warnings.insert(s.file, (s.top_author, s.ratio));
-    }
-    Ok(warnings)
-}
-
-pub fn as_busfactor_json(map: &BTreeMap<String, (String, f64)>) -> String {
-    let as_json: BTreeMap<_, _> = map
-        .iter()
-        .map(|(f, (a, r))| (f.clone(), json!({ "author": a, "ownership": r })))
-        .collect();
-    serde_json::to_string_pretty(&as_json).unwrap()
+    let mut scores = compute_scores_parallel(repo, opts)?;
+    scores.retain(|s| s.ratio > threshold);
+    Ok(scores)
 }
 
 // ---------------------- NEW: directory-level aggregation -------------------
@@ -292,27 +306,32 @@ pub fn aggregate_dir_from_file_scores(scores: &[BusScore], depth: usize) -> Vec<
 
 /// Accurate dir scores via blame (sum per-author line counts across files in the directory).
 pub fn compute_dir_scores_parallel(
-    repo_path: &str,
+    repo: &RepoExt,
     opts: &ScanOpts,
     depth: usize,
 ) -> Result<Vec<DirScore>> {
-    let files = list_repo_files(repo_path)?;
+    let repo_path = repo.repo().path();
+    let mailmap = repo.mailmap();
+    let bots = repo.bots();
+    let tip = repo.tip();
+    let tuning = repo.blame_tuning();
+    let files = repo.tracked_files()?;
     // Produce per-file author->lines maps in parallel
     let per_file: Vec<_> = files
         .par_iter()
         .filter_map(|file| {
-            if !ext_ok(file, opts) {
+            if !opts.ext_ok(file) {
                 return None;
             }
-            let repo = Repository::discover(repo_path).ok()?;
-            let mut blame_opts = BlameOptions::new();
-            let blame = repo
-                .blame_file(Path::new(file), Some(&mut blame_opts))
-                .ok()?;
+            let repo = Repository::open(repo_path).ok()?;
+            let hunks = blame::hunks(&repo, tip, Path::new(file), None, tuning).ok()?;
             let mut counts: HashMap<String, usize> = HashMap::new();
-            for h in blame.iter() {
-                let email = h.final_signature().email().unwrap_or("unknown").to_string();
-                *counts.entry(email).or_default() += h.lines_in_hunk() as usize;
+            for h in hunks {
+                let author = mailmap.resolve_signature(&h.signature);
+                if bots.skips(&author) {
+                    continue;
+                }
+                *counts.entry(author.to_string()).or_default() += h.lines;
             }
             let total: usize = counts.values().copied().sum();
             if total < opts.min_total {
@@ -359,75 +378,24 @@ pub fn compute_dir_scores_parallel(
 
 /// Directory scores in FAST mode (touch counts aggregated).
 pub fn compute_dir_scores_fast(
-    repo: &Repository,
+    repo: &RepoExt,
     max_commits: Option<usize>,
     opts: &ScanOpts,
     depth: usize,
 ) -> Result<Vec<DirScore>> {
-    // file -> author -> touches
-    let mut touches: HashMap<String, HashMap<String, usize>> = HashMap::new();
-
-    let mut walk = repo.revwalk()?;
-    walk.push_head()?;
-    walk.set_sorting(Sort::TIME)?;
-
-    let mut seen = 0usize;
-    for oid in walk.flatten() {
-        if let Some(m) = max_commits {
-            if seen >= m {
-                break;
-            }
-        }
-        let commit = match repo.find_commit(oid) {
-            Ok(c) => c,
-            Err(_) => continue,
-        };
-        let email = commit.author().email().unwrap_or("unknown").to_string();
-
-        let tree = match commit.tree() {
-            Ok(t) => t,
-            Err(_) => continue,
-        };
-        if let Ok(parent) = commit.parent(0) {
-            let parent_tree = match parent.tree() {
-                Ok(t) => t,
-                Err(_) => continue,
-            };
-            let mut opt = DiffOptions::new();
-            if let Ok(diff) =
-                repo.diff_tree_to_tree(Some(&parent_tree), Some(&tree), Some(&mut opt))
-            {
-                for d in diff.deltas() {
-                    if let Some(path) = d.new_file().path().or_else(|| d.old_file().path()) {
-                        if let Some(p) = path.to_str() {
-                            if !ext_ok(p, opts) {
-                                continue;
-                            }
-                            *touches
-                                .entry(p.to_string())
-                                .or_default()
-                                .entry(email.clone())
-                                .or_default() += 1;
-                        }
-                    }
-                }
-            }
-        }
-        seen += 1;
-    }
+    let touches = engine::run(repo, TouchOwnership::new(max_commits, opts.clone()))?.touches;
 
     // fold into directories
-    let mut dir_author: HashMap<String, HashMap<String, usize>> = HashMap::new();
+    let mut dir_author: HashMap<String, HashMap<String, f64>> = HashMap::new();
     let mut dir_total: HashMap<String, usize> = HashMap::new();
-    for (file, by_auth) in touches {
-        let total: usize = by_auth.values().sum();
-        if total < opts.min_total {
+    for (file, t) in touches {
+        if t.commits < opts.min_total {
             continue;
         }
         let key = dir_key(&file, depth);
-        *dir_total.entry(key.clone()).or_default() += total;
+        *dir_total.entry(key.clone()).or_default() += t.commits;
         let da = dir_author.entry(key).or_default();
-        for (a, n) in by_auth {
+        for (a, n) in t.by_author {
             *da.entry(a).or_default() += n;
         }
     }
@@ -438,11 +406,13 @@ pub fn compute_dir_scores_fast(
         if total == 0 {
             continue;
         }
-        let (top_author, top) = authors.into_iter().max_by_key(|(_, n)| *n).unwrap();
+        let Some((top_author, ratio)) = top_share(&authors) else {
+            continue;
+        };
         out.push(DirScore {
             dir,
             top_author,
-            ratio: top as f64 / total as f64,
+            ratio,
             total,
         });
     }
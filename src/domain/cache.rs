@@ -0,0 +1,337 @@
+use anyhow::{Context, Result};
+use git2::{Commit, DiffFindOptions, Oid, Patch, Repository};
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+use std::fs::{self, File, OpenOptions};
+use std::io::{BufRead, BufReader, BufWriter, Write};
+use std::path::{Path, PathBuf};
+use std::rc::Rc;
+
+/// Bump when `CommitFacts` changes shape; old files are simply ignored.
+const FACTS_FILE: &str = "facts-v1.jsonl";
+
+/// One changed path in a commit (diff against the first parent).
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct FileChange {
+    pub path: String,
+    /// Previous path when the change is a detected rename.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub old_path: Option<String>,
+    pub adds: usize,
+    pub dels: usize,
+}
+
+/// Everything the history scanners need from a commit, computed once.
+/// Authors are stored raw; the mailmap is applied when reading so mailmap
+/// edits never invalidate the cache.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct CommitFacts {
+    pub oid: String,
+    pub author_name: String,
+    pub author_email: String,
+    pub time: i64,
+    pub parents: Vec<String>,
+    pub files: Vec<FileChange>,
+}
+
+impl CommitFacts {
+    /// Diff the commit against its first parent (or the empty tree) with
+    /// rename detection and per-file line stats.
+    pub fn compute(repo: &Repository, commit: &Commit) -> Result<Self> {
+        let tree = commit.tree()?;
+        let parent_tree = match commit.parent(0) {
+            Ok(p) => Some(p.tree()?),
+            Err(_) => None,
+        };
+        let mut diff = repo.diff_tree_to_tree(parent_tree.as_ref(), Some(&tree), None)?;
+        diff.find_similar(Some(DiffFindOptions::new().renames(true)))?;
+
+        let mut files = Vec::with_capacity(diff.deltas().len());
+        for (i, d) in diff.deltas().enumerate() {
+            let new = d.new_file().path().and_then(|p| p.to_str());
+            let old = d.old_file().path().and_then(|p| p.to_str());
+            let Some(path) = new.or(old) else { continue };
+            let (adds, dels) = match Patch::from_diff(&diff, i) {
+                Ok(Some(patch)) => patch.line_stats().map(|(_, a, d)| (a, d)).unwrap_or((0, 0)),
+                _ => (0, 0),
+            };
+            files.push(FileChange {
+                path: path.to_string(),
+                old_path: old.filter(|o| *o != path).map(str::to_string),
+                adds,
+                dels,
+            });
+        }
+
+        let author = commit.author();
+        Ok(Self {
+            oid: commit.id().to_string(),
+            author_name: author.name().unwrap_or("unknown").to_string(),
+            author_email: author.email().unwrap_or("unknown").to_string(),
+            time: commit.time().seconds(),
+            parents: commit.parent_ids().map(|p| p.to_string()).collect(),
+            files,
+        })
+    }
+
+    pub fn is_root(&self) -> bool {
+        self.parents.is_empty()
+    }
+}
+
+/// Append-only on-disk store of `CommitFacts` keyed by commit id.
+///
+/// Lives in `.git/gitrivia/` (or `$XDG_CACHE_HOME/gitrivia/<repo>/` when
+/// the git dir is not writable). New facts are appended on `flush`/drop,
+/// so each run only diffs commits it has never seen.
+pub struct FactsCache {
+    /// `None` keeps everything in memory (`--no-cache`).
+    file: Option<PathBuf>,
+    map: HashMap<Oid, Rc<CommitFacts>>,
+    pending: Vec<Rc<CommitFacts>>,
+    /// The file ends in a torn line; the next append starts a new one.
+    torn: bool,
+}
+
+impl FactsCache {
+    pub fn in_memory() -> Self {
+        Self {
+            file: None,
+            map: HashMap::new(),
+            pending: Vec::new(),
+            torn: false,
+        }
+    }
+
+    pub fn open(repo: &Repository) -> Result<Self> {
+        let file = cache_dir(repo)?.join(FACTS_FILE);
+        let mut map = HashMap::new();
+        let mut torn = false;
+        if let Ok(f) = File::open(&file) {
+            let mut reader = BufReader::new(f);
+            let mut line = String::new();
+            while reader.read_line(&mut line).unwrap_or(0) > 0 {
+                torn = !line.ends_with('\n');
+                // A torn last line (interrupted run) is just dropped.
+                if let Ok(facts) = serde_json::from_str::<CommitFacts>(&line) {
+                    if let Ok(oid) = Oid::from_str(&facts.oid) {
+                        map.insert(oid, Rc::new(facts));
+                    }
+                }
+                line.clear();
+            }
+        }
+        Ok(Self {
+            file: Some(file),
+            map,
+            pending: Vec::new(),
+            torn,
+        })
+    }
+
+    pub fn path(&self) -> Option<&Path> {
+        self.file.as_deref()
+    }
+
+    pub fn len(&self) -> usize {
+        self.map.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.map.is_empty()
+    }
+
+    pub fn contains(&self, oid: Oid) -> bool {
+        self.map.contains_key(&oid)
+    }
+
+    /// Cached facts for `commit`, computing (and queueing) them if missing.
+    pub fn get(&mut self, repo: &Repository, commit: &Commit) -> Result<Rc<CommitFacts>> {
+        if let Some(f) = self.map.get(&commit.id()) {
+            return Ok(f.clone());
+        }
+        let facts = Rc::new(CommitFacts::compute(repo, commit)?);
+        self.map.insert(commit.id(), facts.clone());
+        if self.file.is_some() {
+            self.pending.push(facts.clone());
+        }
+        Ok(facts)
+    }
+
+    /// Append newly computed facts to disk.
+    pub fn flush(&mut self) -> Result<()> {
+        let Some(file) = &self.file else {
+            return Ok(());
+        };
+        if self.pending.is_empty() {
+            return Ok(());
+        }
+        let f = OpenOptions::new()
+            .create(true)
+            .append(true)
+            .open(file)
+            .with_context(|| format!("cannot write cache {}", file.display()))?;
+        let mut w = BufWriter::new(f);
+        if std::mem::take(&mut self.torn) {
+            w.write_all(b"\n")?;
+        }
+        for facts in self.pending.drain(..) {
+            serde_json::to_writer(&mut w, &*facts)?;
+            w.write_all(b"\n")?;
+        }
+        w.flush()?;
+        Ok(())
+    }
+
+    /// Drop every entry (in memory and on disk).
+    pub fn clear(&mut self) -> Result<()> {
+        self.map.clear();
+        self.pending.clear();
+        self.torn = false;
+        if let Some(file) = self.file.as_ref().filter(|f| f.exists()) {
+            fs::remove_file(file)?;
+        }
+        Ok(())
+    }
+
+    /// Keep only entries for which `keep` is true; rewrites the file.
+    /// Returns the number of removed entries.
+    pub fn retain(&mut self, keep: impl Fn(Oid) -> bool) -> Result<usize> {
+        let before = self.map.len();
+        self.map.retain(|oid, _| keep(*oid));
+        self.pending.clear();
+        self.torn = false;
+        let Some(file) = &self.file else {
+            return Ok(before - self.map.len());
+        };
+        let tmp = file.with_extension("jsonl.tmp");
+        {
+            let mut w = BufWriter::new(File::create(&tmp)?);
+            for facts in self.map.values() {
+                serde_json::to_writer(&mut w, &**facts)?;
+                w.write_all(b"\n")?;
+            }
+            w.flush()?;
+        }
+        fs::rename(&tmp, file)?;
+        Ok(before - self.map.len())
+    }
+}
+
+impl Drop for FactsCache {
+    fn drop(&mut self) {
+        // Best effort: a failed write only costs recomputation next time.
+        let _ = self.flush();
+    }
+}
+
+fn cache_dir(repo: &Repository) -> Result<PathBuf> {
+    let in_git = repo.path().join("gitrivia");
+    if fs::create_dir_all(&in_git).is_ok() {
+        return Ok(in_git);
+    }
+    let base = std::env::var_os("XDG_CACHE_HOME")
+        .map(PathBuf::from)
+        .or_else(|| std::env::var_os("HOME").map(|h| PathBuf::from(h).join(".cache")))
+        .context("no writable cache directory (.git is read-only and $HOME is unset)")?;
+    let key: String = repo
+        .path()
+        .to_string_lossy()
+        .chars()
+        .map(|c| if c.is_ascii_alphanumeric() { c } else { '_' })
+        .collect();
+    let dir = base.join("gitrivia").join(key);
+    fs::create_dir_all(&dir).with_context(|| format!("cannot create {}", dir.display()))?;
+    Ok(dir)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::domain::test_repo::TestRepo;
+
+    #[test]
+    fn facts_diff_against_the_first_parent_with_renames() {
+        let mut t = TestRepo::new();
+        let root = t.commit("A <a@x.io>", "root", &[("a.txt", Some("1\n2\n3\n4\n"))]);
+        let moved = t.commit(
+            "A <a@x.io>",
+            "move",
+            &[("a.txt", None), ("b.txt", Some("1\n2\n3\n4\n5\n"))],
+        );
+        let facts = CommitFacts::compute(&t.repo, &t.repo.find_commit(root).unwrap()).unwrap();
+        assert!(facts.is_root());
+        assert_eq!((facts.files[0].adds, facts.files[0].dels), (4, 0));
+
+        let facts = CommitFacts::compute(&t.repo, &t.repo.find_commit(moved).unwrap()).unwrap();
+        assert_eq!(facts.files.len(), 1);
+        let f = &facts.files[0];
+        assert_eq!(f.path, "b.txt");
+        assert_eq!(f.old_path.as_deref(), Some("a.txt"));
+        assert_eq!((f.adds, f.dels), (1, 0));
+    }
+
+    #[test]
+    fn facts_persist_and_torn_lines_are_dropped() {
+        let mut t = TestRepo::new();
+        let one = t.commit("A <a@x.io>", "one", &[("a", Some("1\n"))]);
+        let two = t.commit("A <a@x.io>", "two", &[("a", Some("2\n"))]);
+        let path = {
+            let mut cache = FactsCache::open(&t.repo).unwrap();
+            assert!(cache.is_empty());
+            cache
+                .get(&t.repo, &t.repo.find_commit(one).unwrap())
+                .unwrap();
+            cache.path().unwrap().to_path_buf()
+            // Dropping flushes.
+        };
+        assert!(path.starts_with(t.repo.path()));
+        // An interrupted run leaves half a line behind.
+        let mut f = OpenOptions::new().append(true).open(&path).unwrap();
+        f.write_all(br#"{"oid":"00"#).unwrap();
+        drop(f);
+
+        let mut cache = FactsCache::open(&t.repo).unwrap();
+        assert_eq!(cache.len(), 1);
+        assert!(cache.contains(one));
+        cache
+            .get(&t.repo, &t.repo.find_commit(two).unwrap())
+            .unwrap();
+        cache.flush().unwrap();
+        assert_eq!(FactsCache::open(&t.repo).unwrap().len(), 2);
+    }
+
+    #[test]
+    fn retain_and_clear_rewrite_the_file() {
+        let mut t = TestRepo::new();
+        let one = t.commit("A <a@x.io>", "one", &[("a", Some("1\n"))]);
+        let two = t.commit("A <a@x.io>", "two", &[("a", Some("2\n"))]);
+        let mut cache = FactsCache::open(&t.repo).unwrap();
+        for id in [one, two] {
+            cache
+                .get(&t.repo, &t.repo.find_commit(id).unwrap())
+                .unwrap();
+        }
+        cache.flush().unwrap();
+        assert_eq!(cache.retain(|id| id == two).unwrap(), 1);
+        let reopened = FactsCache::open(&t.repo).unwrap();
+        assert!(reopened.contains(two) && !reopened.contains(one));
+
+        cache.clear().unwrap();
+        assert!(!cache.path().unwrap().exists());
+        assert!(FactsCache::open(&t.repo).unwrap().is_empty());
+    }
+
+    #[test]
+    fn in_memory_cache_writes_nothing() {
+        let mut t = TestRepo::new();
+        let one = t.commit("A <a@x.io>", "one", &[("a", Some("1\n"))]);
+        let mut cache = FactsCache::in_memory();
+        cache
+            .get(&t.repo, &t.repo.find_commit(one).unwrap())
+            .unwrap();
+        cache.flush().unwrap();
+        assert!(cache.path().is_none());
+        assert!(!t.repo.path().join("gitrivia").join(FACTS_FILE).exists());
+    }
+}
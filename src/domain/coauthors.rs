@@ -1,41 +1,82 @@
 use anyhow::Result;
-use git2::Repository;
 use std::collections::BTreeMap;
 
-pub fn top_coauthors(repo: &Repository) -> Result<BTreeMap<String, usize>> {
-    let mut file_authors: BTreeMap<String, Vec<String>> = BTreeMap::new();
-    let mut rw = repo.revwalk()?;
-    rw.push_head()?;
-
-    for oid in rw.flatten() {
-        let commit = repo.find_commit(oid)?;
-        let tree = commit.tree()?;
-
-        if let Ok(parent) = commit.parent(0) {
-            let parent_tree = parent.tree()?;
-            let diff = repo.diff_tree_to_tree(Some(&parent_tree), Some(&tree), None)?;
-            for delta in diff.deltas() {
-                if let Some(path) = delta.new_file().path().and_then(|p| p.to_str()) {
-                    let author = commit.author().email().unwrap_or("unknown").to_string();
-                    let authors = file_authors.entry(path.to_string()).or_default();
-                    if !authors.contains(&author) {
-                        authors.push(author);
-                    }
-                }
+use crate::domain::engine::{self, Analyser, CommitCtx};
+use crate::domain::git::RepoExt;
+
+/// Distinct authors per file; pairs sharing a file count as co-authors.
+#[derive(Debug, Default)]
+pub struct SharedFiles {
+    pub authors_by_file: BTreeMap<String, Vec<String>>,
+}
+
+impl Analyser for SharedFiles {
+    fn visit(&mut self, c: &CommitCtx) {
+        for f in c.changes() {
+            let authors = self.authors_by_file.entry(f.path.clone()).or_default();
+            if !authors.contains(&c.author) {
+                authors.push(c.author.clone());
             }
         }
     }
+}
 
-    let mut pairs: BTreeMap<String, usize> = BTreeMap::new();
-    for authors in file_authors.values() {
-        for i in 0..authors.len() {
-            for j in i + 1..authors.len() {
-                let mut pair = vec![authors[i].clone(), authors[j].clone()];
-                pair.sort();
-                let key = format!("{} + {}", pair[0], pair[1]);
-                *pairs.entry(key).or_default() += 1;
-            }
+impl SharedFiles {
+    /// `"A + B"` → number of files both touched.
+    pub fn pairs(&self) -> BTreeMap<String, usize> {
+        let mut pairs: BTreeMap<String, usize> = BTreeMap::new();
+        for authors in self.authors_by_file.values() {
+            count_pairs(&mut pairs, authors);
         }
+        pairs
     }
-    Ok(pairs)
+}
+
+/// Real pairing from `Co-authored-by:` trailers: `"A + B"` → commits they
+/// made together (author with each co-author, and co-authors among
+/// themselves).
+#[derive(Debug, Default)]
+pub struct TrailerPairs {
+    pub pairs: BTreeMap<String, usize>,
+}
+
+impl Analyser for TrailerPairs {
+    fn visit(&mut self, c: &CommitCtx) {
+        if c.coauthors.is_empty() {
+            return;
+        }
+        let mut people = vec![c.author.clone()];
+        people.extend(c.coauthors.iter().cloned());
+        count_pairs(&mut self.pairs, &people);
+    }
+}
+
+fn count_pairs(pairs: &mut BTreeMap<String, usize>, people: &[String]) {
+    for i in 0..people.len() {
+        for j in i + 1..people.len() {
+            let mut pair = [people[i].clone(), people[j].clone()];
+            pair.sort();
+            let key = format!("{} + {}", pair[0], pair[1]);
+            *pairs.entry(key).or_default() += 1;
+        }
+    }
+}
+
+/// Both co-author views, computed in one pass.
+#[derive(Debug)]
+pub struct Coauthors {
+    /// Heuristic: pairs who changed the same files.
+    pub shared_files: BTreeMap<String, usize>,
+    /// Pairs named together in commit trailers.
+    pub trailers: BTreeMap<String, usize>,
+}
+
+pub fn top_coauthors(repo: &RepoExt) -> Result<Coauthors> {
+    let mut shared = SharedFiles::default();
+    let mut trailers = TrailerPairs::default();
+    engine::scan(repo, &mut [&mut shared, &mut trailers])?;
+    Ok(Coauthors {
+        shared_files: shared.pairs(),
+        trailers: trailers.pairs,
+    })
 }
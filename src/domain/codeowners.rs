@@ -0,0 +1,712 @@
+use anyhow::{bail, Context, Result};
+use chrono::{DateTime, Duration, Local, TimeZone};
+use git2::Repository;
+use globset::{GlobBuilder, GlobSet, GlobSetBuilder};
+use rayon::prelude::*;
+use serde::Serialize;
+use std::collections::{BTreeMap, BTreeSet, HashMap};
+use std::path::Path;
+
+use crate::domain::blame;
+use crate::domain::bus_factor::{ScanOpts, TouchOwnership};
+use crate::domain::churn::dir_key;
+use crate::domain::engine::{self, Analyser};
+use crate::domain::git::{commit_time, RepoExt};
+use crate::domain::stats::AuthorCounter;
+
+/// Where GitHub and GitLab look for the file, in GitHub's order.
+pub const LOCATIONS: [&str; 4] = [
+    ".github/CODEOWNERS",
+    "CODEOWNERS",
+    "docs/CODEOWNERS",
+    ".gitlab/CODEOWNERS",
+];
+
+/// How files are grouped into rules.
+#[derive(Debug, Clone)]
+pub enum Grouping {
+    /// One rule per directory at this depth, plus `*` for the root.
+    Dirs(usize),
+    /// One rule per CODEOWNERS pattern; a file belongs to the last one
+    /// matching it.
+    Patterns(Vec<String>),
+}
+
+#[derive(Debug, Clone)]
+pub struct OwnersOpts {
+    pub scan: ScanOpts,
+    pub grouping: Grouping,
+    /// Owners per rule at most.
+    pub top: usize,
+    /// Minimum share of a rule's weighted lines for an owner (0..1).
+    pub min_share: f64,
+    /// Blamed lines lose half their weight every this many days (0 = no
+    /// decay).
+    pub half_life_days: f64,
+    /// Authors without a commit in this many days before the tip have left
+    /// (0 = nobody has).
+    pub inactive_days: i64,
+}
+
+/// A suggested owner of one rule.
+#[derive(Debug, Clone, Serialize)]
+pub struct Candidate {
+    pub author: String,
+    pub email: String,
+    /// Share of the rule's age-weighted lines (0..1).
+    pub share: f64,
+    /// Lines blamed on the author, unweighted.
+    pub lines: usize,
+    pub last_commit: Option<DateTime<Local>>,
+}
+
+/// One suggested CODEOWNERS line.
+#[derive(Debug, Clone, Serialize)]
+pub struct OwnerRule {
+    pub pattern: String,
+    pub files: usize,
+    pub lines: usize,
+    /// Best first; empty when no active author reaches the cutoff.
+    pub owners: Vec<Candidate>,
+}
+
+#[derive(Debug, Default)]
+struct FileLines {
+    /// author → (lines, age-weighted lines)
+    authors: HashMap<String, (usize, f64)>,
+}
+
+/// Blame and history facts the suggestion and the diff are built from.
+#[derive(Debug)]
+pub struct Ownership {
+    files: BTreeMap<String, FileLines>,
+    /// Every file at the tip passing the path filters, scanned or not.
+    tracked: Vec<String>,
+    /// file → authors with a commit touching it
+    touched: HashMap<String, HashMap<String, f64>>,
+    last_seen: HashMap<String, DateTime<Local>>,
+    tip_time: DateTime<Local>,
+}
+
+/// Blame every selected file and walk the history once for touches and
+/// each author's last commit.
+pub fn collect(repo: &RepoExt, opts: &OwnersOpts) -> Result<Ownership> {
+    let tip_time = commit_time(&repo.repo().find_commit(repo.tip())?);
+    let repo_path = repo.repo().path();
+    let mailmap = repo.mailmap();
+    let bots = repo.bots();
+    let tip = repo.tip();
+    let tuning = repo.blame_tuning();
+    let tracked = repo.tracked_files()?;
+    let half_life = opts.half_life_days;
+    let files = tracked
+        .par_iter()
+        .filter_map(|file| {
+            if !opts.scan.ext_ok(file) {
+                return None;
+            }
+            let repo = Repository::open(repo_path).ok()?;
+            let hunks = blame::hunks(&repo, tip, Path::new(file), None, tuning).ok()?;
+            let mut lines = FileLines::default();
+            for h in hunks {
+                let sig = &h.signature;
+                let author = mailmap.resolve_signature(sig);
+                // Bots never own code, whatever `--bots` says.
+                if bots.is_bot(&author) {
+                    continue;
+                }
+                let when = Local
+                    .timestamp_opt(sig.when().seconds(), 0)
+                    .single()
+                    .unwrap_or(tip_time);
+                let n = h.lines;
+                let e = lines.authors.entry(author.to_string()).or_default();
+                e.0 += n;
+                e.1 += n as f64 * decay(tip_time - when, half_life);
+            }
+            Some((file.clone(), lines))
+        })
+        .collect();
+
+    let mut counter = AuthorCounter::new(None);
+    // Touches of every file, not just the scanned ones: the diff checks
+    // owners of any path.
+    let mut touches = TouchOwnership::new(None, opts.scan.clone().all(true));
+    let mut analysers: Vec<&mut dyn Analyser> = vec![&mut counter, &mut touches];
+    engine::scan(repo, &mut analysers)?;
+
+    Ok(Ownership {
+        files,
+        tracked,
+        touched: touches
+            .touches
+            .into_iter()
+            .map(|(f, t)| (f, t.by_author))
+            .collect(),
+        last_seen: counter
+            .finish()
+            .data
+            .into_iter()
+            .map(|(a, m)| (a, m.last))
+            .collect(),
+        tip_time,
+    })
+}
+
+/// Weight of a line of this age.
+fn decay(age: Duration, half_life_days: f64) -> f64 {
+    if half_life_days <= 0.0 {
+        return 1.0;
+    }
+    let days = age.num_seconds().max(0) as f64 / 86_400.0;
+    0.5_f64.powf(days / half_life_days)
+}
+
+/// Email of a `Name <email>` author.
+pub fn email_of(author: &str) -> &str {
+    author
+        .rsplit_once('<')
+        .map_or(author, |(_, e)| e.trim_end_matches('>'))
+}
+
+impl Ownership {
+    /// Author has no commit in the last `inactive_days` before the tip.
+    fn has_left(&self, author: &str, inactive_days: i64) -> bool {
+        self.inactive(self.last_seen.get(author).copied(), inactive_days)
+    }
+
+    /// A last commit at `last` is older than `inactive_days` before the tip.
+    fn inactive(&self, last: Option<DateTime<Local>>, inactive_days: i64) -> bool {
+        inactive_days > 0 && last.is_none_or(|l| l < self.tip_time - Duration::days(inactive_days))
+    }
+
+    /// Rule patterns, in CODEOWNERS order (broader patterns first), each
+    /// with the scanned files it decides.
+    fn groups(&self, grouping: &Grouping) -> Result<Vec<(String, Vec<&str>)>> {
+        let patterns: Vec<String> = match grouping {
+            Grouping::Patterns(p) => p.clone(),
+            Grouping::Dirs(depth) => {
+                let dirs: BTreeSet<String> =
+                    self.files.keys().map(|f| dir_key(f, *depth)).collect();
+                dirs.iter().map(|d| dir_pattern(d)).collect()
+            }
+        };
+        let matchers = patterns
+            .iter()
+            .map(|p| pattern_matcher(p))
+            .collect::<Result<Vec<_>>>()?;
+        let mut groups: Vec<(String, Vec<&str>)> =
+            patterns.iter().map(|p| (p.clone(), Vec::new())).collect();
+        for file in self.files.keys() {
+            let group = match grouping {
+                // Directory rules never overlap in effect: the deepest wins.
+                Grouping::Dirs(depth) => {
+                    let p = dir_pattern(&dir_key(file, *depth));
+                    patterns.iter().position(|x| *x == p)
+                }
+                Grouping::Patterns(_) => matchers.iter().rposition(|m| m.is_match(file)),
+            };
+            if let Some(group) = group {
+                groups[group].1.push(file);
+            }
+        }
+        Ok(groups)
+    }
+
+    /// Suggested rules, in CODEOWNERS order. Rules with fewer than
+    /// `min_total` lines are left out.
+    pub fn suggest(&self, opts: &OwnersOpts) -> Result<Vec<OwnerRule>> {
+        let mut rules = Vec::new();
+        for (pattern, files) in self.groups(&opts.grouping)? {
+            let mut authors: HashMap<&str, (usize, f64)> = HashMap::new();
+            for (a, (n, w)) in files.iter().flat_map(|f| &self.files[*f].authors) {
+                let e = authors.entry(a).or_default();
+                e.0 += n;
+                e.1 += w;
+            }
+            let lines: usize = authors.values().map(|(n, _)| n).sum();
+            if lines < opts.scan.min_total.max(1) {
+                continue;
+            }
+            let weighted: f64 = authors.values().map(|(_, w)| w).sum();
+            // One candidate per person, however many spellings their
+            // commits used; the spelling with the most lines is shown.
+            let mut people: HashMap<String, (Candidate, usize)> = HashMap::new();
+            for (a, (n, w)) in authors {
+                let email = email_of(a).to_lowercase();
+                let last = self.last_seen.get(a).copied();
+                let (c, most) = people.entry(email.clone()).or_insert_with(|| {
+                    let c = Candidate {
+                        author: a.to_string(),
+                        email,
+                        share: 0.0,
+                        lines: 0,
+                        last_commit: None,
+                    };
+                    (c, 0)
+                });
+                if n > *most || (n == *most && a < c.author.as_str()) {
+                    c.author = a.to_string();
+                    *most = n;
+                }
+                c.share += if weighted > 0.0 { w / weighted } else { 0.0 };
+                c.lines += n;
+                c.last_commit = c.last_commit.max(last);
+            }
+            let mut owners: Vec<Candidate> = people
+                .into_values()
+                .map(|(c, _)| c)
+                .filter(|c| !self.inactive(c.last_commit, opts.inactive_days))
+                .filter(|c| c.share >= opts.min_share && c.share > 0.0)
+                .collect();
+            owners.sort_by(|a, b| {
+                b.share
+                    .total_cmp(&a.share)
+                    .then_with(|| a.author.cmp(&b.author))
+            });
+            owners.truncate(opts.top);
+            rules.push(OwnerRule {
+                pattern,
+                files: files.len(),
+                lines,
+                owners,
+            });
+        }
+        Ok(rules)
+    }
+}
+
+/// Rule pattern of a `dir_key` directory.
+fn dir_pattern(dir: &str) -> String {
+    if dir == "." {
+        "*".into()
+    } else {
+        format!("/{dir}/")
+    }
+}
+
+/// Glob set matching what a CODEOWNERS pattern matches (gitignore rules):
+/// a leading or inner `/` anchors the pattern at the root, otherwise it
+/// matches at any depth; a directory matches everything below it.
+pub fn pattern_matcher(pattern: &str) -> Result<GlobSet> {
+    let anchored = pattern.starts_with('/') || pattern.trim_end_matches('/').contains('/');
+    let pat = pattern.trim_start_matches('/').trim_end_matches('/');
+    let base = match (anchored, pat) {
+        (_, "" | "*" | "**") => "**".to_string(),
+        (true, _) => pat.to_string(),
+        (false, _) => format!("**/{pat}"),
+    };
+    let mut set = GlobSetBuilder::new();
+    for g in [base.clone(), format!("{base}/**")] {
+        set.add(
+            GlobBuilder::new(&g)
+                .literal_separator(true)
+                .build()
+                .with_context(|| format!("bad CODEOWNERS pattern '{pattern}'"))?,
+        );
+    }
+    Ok(set.build()?)
+}
+
+/// One rule of an existing CODEOWNERS file.
+#[derive(Debug, Clone)]
+pub struct Rule {
+    /// 1-based line number.
+    pub line: usize,
+    pub pattern: String,
+    pub owners: Vec<String>,
+    matcher: GlobSet,
+}
+
+/// A parsed CODEOWNERS file (GitHub or GitLab).
+#[derive(Debug, Clone)]
+pub struct CodeOwners {
+    pub rules: Vec<Rule>,
+}
+
+impl CodeOwners {
+    /// Parse the file. GitLab section headers (`[Section]`, `^[Section]`)
+    /// are skipped, so their rules read as one list.
+    pub fn parse(text: &str) -> Result<Self> {
+        let mut rules = Vec::new();
+        for (i, raw) in text.lines().enumerate() {
+            let line = raw.trim();
+            if line.is_empty() || line.starts_with('#') {
+                continue;
+            }
+            if line.starts_with('[') || line.starts_with("^[") {
+                continue;
+            }
+            let mut parts = line.split_whitespace();
+            let pattern = parts.next().unwrap_or_default().replace("\\#", "#");
+            let owners: Vec<String> = parts
+                .take_while(|o| !o.starts_with('#'))
+                .map(str::to_string)
+                .collect();
+            let matcher = pattern_matcher(&pattern).with_context(|| format!("line {}", i + 1))?;
+            rules.push(Rule {
+                line: i + 1,
+                pattern,
+                owners,
+                matcher,
+            });
+        }
+        Ok(Self { rules })
+    }
+
+    /// Rule deciding the owners of `path`: the last one matching.
+    pub fn rule_for(&self, path: &str) -> Option<&Rule> {
+        self.rules.iter().rev().find(|r| r.matcher.is_match(path))
+    }
+}
+
+/// Read the CODEOWNERS file committed at the tip, from the first of
+/// `LOCATIONS` present. Returns the location and contents.
+pub fn read_committed(repo: &RepoExt) -> Result<(String, String)> {
+    let tree = repo.repo().find_commit(repo.tip())?.tree()?;
+    for loc in LOCATIONS {
+        if let Ok(entry) = tree.get_path(Path::new(loc)) {
+            let blob = entry.to_object(repo.repo())?.peel_to_blob()?;
+            return Ok((
+                loc.to_string(),
+                String::from_utf8_lossy(blob.content()).into(),
+            ));
+        }
+    }
+    bail!(
+        "no CODEOWNERS file at the tip (looked in {}); pass --against FILE",
+        LOCATIONS.join(", ")
+    )
+}
+
+/// What an owner entry refers to.
+#[derive(Debug, Clone, PartialEq)]
+enum Owner {
+    Author(String),
+    /// `@org/team`: membership is not known locally.
+    Team,
+    Unknown,
+}
+
+/// Problem with an owner listed in the existing file.
+#[derive(Debug, Clone, Copy, Serialize, PartialEq, Eq)]
+#[serde(rename_all = "snake_case")]
+pub enum Issue {
+    /// No commit within the inactivity window.
+    Left,
+    /// Never committed to the repository (as far as email and handle
+    /// matching can tell).
+    Unknown,
+    /// Has neither lines nor commits in any file the rule decides.
+    Untouched,
+    /// The pattern matches no file.
+    NoFiles,
+}
+
+impl Issue {
+    pub fn as_str(self) -> &'static str {
+        match self {
+            Issue::Left => "left",
+            Issue::Unknown => "unknown",
+            Issue::Untouched => "untouched",
+            Issue::NoFiles => "no_files",
+        }
+    }
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct OwnerFlag {
+    pub line: usize,
+    pub pattern: String,
+    /// The owner entry as written (all of them for `no_files`).
+    pub owner: String,
+    pub issue: Issue,
+    pub last_commit: Option<DateTime<Local>>,
+}
+
+/// A suggested rule next to the owners the existing file gives its files.
+#[derive(Debug, Clone, Serialize)]
+pub struct RuleDiff {
+    pub pattern: String,
+    pub suggested: Vec<String>,
+    /// Owners the existing file assigns to any of the rule's files.
+    pub current: Vec<String>,
+    /// Suggested but not listed.
+    pub added: Vec<String>,
+    /// Listed but not suggested (teams are never reported).
+    pub removed: Vec<String>,
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct OwnersDiff {
+    pub rules: Vec<RuleDiff>,
+    pub flags: Vec<OwnerFlag>,
+}
+
+impl Ownership {
+    /// Map an owner entry to an author: emails through the mailmap,
+    /// `@handle`s through `aliases` (handle → email), then by the email's
+    /// user part (including GitHub noreply addresses) or the author name.
+    fn owner(&self, repo: &RepoExt, entry: &str, aliases: &HashMap<String, String>) -> Owner {
+        let handle = entry.strip_prefix('@');
+        if handle.is_some_and(|h| h.contains('/')) {
+            return Owner::Team;
+        }
+        let email = match handle {
+            Some(h) => aliases.get(&h.to_lowercase()).cloned(),
+            None => Some(entry.to_string()),
+        };
+        let authors = self.last_seen.keys();
+        if let Some(email) = email {
+            let email = repo.mailmap().canonical_email(&email);
+            return authors
+                .into_iter()
+                .find(|a| email_of(a).eq_ignore_ascii_case(&email))
+                .map_or(Owner::Unknown, |a| Owner::Author(a.clone()));
+        }
+        let h = handle.unwrap_or_default();
+        authors
+            .into_iter()
+            .find(|a| {
+                let (user, domain) = email_of(a).split_once('@').unwrap_or_default();
+                let user = if domain == "users.noreply.github.com" {
+                    user.split_once('+').map_or(user, |(_, u)| u)
+                } else {
+                    user
+                };
+                let name = a.rsplit_once('<').map_or("", |(n, _)| n.trim());
+                user.eq_ignore_ascii_case(h) || name.replace(' ', "").eq_ignore_ascii_case(h)
+            })
+            .map_or(Owner::Unknown, |a| Owner::Author(a.clone()))
+    }
+
+    /// Compare the suggestion with `existing` and check its owners.
+    pub fn diff(
+        &self,
+        repo: &RepoExt,
+        suggested: &[OwnerRule],
+        existing: &CodeOwners,
+        aliases: &HashMap<String, String>,
+        opts: &OwnersOpts,
+    ) -> Result<OwnersDiff> {
+        let resolved: HashMap<&str, Owner> = existing
+            .rules
+            .iter()
+            .flat_map(|r| &r.owners)
+            .map(|o| (o.as_str(), self.owner(repo, o, aliases)))
+            .collect();
+
+        let groups: HashMap<String, Vec<&str>> = self.groups(&opts.grouping)?.into_iter().collect();
+        let mut rules = Vec::new();
+        for rule in suggested {
+            let current: BTreeSet<&str> = groups[&rule.pattern]
+                .iter()
+                .filter_map(|f| existing.rule_for(f))
+                .flat_map(|r| r.owners.iter().map(String::as_str))
+                .collect();
+            let suggested_emails: Vec<String> =
+                rule.owners.iter().map(|c| c.email.clone()).collect();
+            let current_emails: Vec<Option<String>> = current
+                .iter()
+                .map(|o| match &resolved[o] {
+                    Owner::Author(a) => Some(email_of(a).to_lowercase()),
+                    _ => None,
+                })
+                .collect();
+            let added = suggested_emails
+                .iter()
+                .filter(|e| !current_emails.contains(&Some(e.to_lowercase())))
+                .cloned()
+                .collect();
+            let removed = current
+                .iter()
+                .zip(&current_emails)
+                .filter(|(o, e)| match e {
+                    Some(e) => !suggested_emails.iter().any(|s| s.eq_ignore_ascii_case(e)),
+                    None => resolved[*o] != Owner::Team,
+                })
+                .map(|(o, _)| o.to_string())
+                .collect();
+            rules.push(RuleDiff {
+                pattern: rule.pattern.clone(),
+                suggested: suggested_emails,
+                current: current.into_iter().map(str::to_string).collect(),
+                added,
+                removed,
+            });
+        }
+
+        // Files each existing rule decides.
+        let mut decides: HashMap<usize, Vec<&str>> = HashMap::new();
+        for file in &self.tracked {
+            if let Some(r) = existing.rule_for(file) {
+                decides.entry(r.line).or_default().push(file);
+            }
+        }
+        let mut flags = Vec::new();
+        for rule in &existing.rules {
+            let flag = |owner: &str, issue, last_commit| OwnerFlag {
+                line: rule.line,
+                pattern: rule.pattern.clone(),
+                owner: owner.to_string(),
+                issue,
+                last_commit,
+            };
+            if !rule.owners.is_empty() && !self.tracked.iter().any(|f| rule.matcher.is_match(f)) {
+                flags.push(flag(&rule.owners.join(" "), Issue::NoFiles, None));
+                continue;
+            }
+            let files = decides.get(&rule.line).map_or(&[][..], Vec::as_slice);
+            for entry in &rule.owners {
+                let author = match &resolved[entry.as_str()] {
+                    Owner::Team => continue,
+                    Owner::Unknown => {
+                        flags.push(flag(entry, Issue::Unknown, None));
+                        continue;
+                    }
+                    Owner::Author(a) => a,
+                };
+                let last = self.last_seen.get(author).copied();
+                if self.has_left(author, opts.inactive_days) {
+                    flags.push(flag(entry, Issue::Left, last));
+                }
+                let touched = files.iter().any(|f| {
+                    self.touched.get(*f).is_some_and(|t| t.contains_key(author))
+                        || self
+                            .files
+                            .get(*f)
+                            .is_some_and(|l| l.authors.contains_key(author))
+                });
+                if !files.is_empty() && !touched {
+                    flags.push(flag(entry, Issue::Untouched, last));
+                }
+            }
+        }
+        Ok(OwnersDiff { rules, flags })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::domain::test_repo::TestRepo;
+
+    fn opts(top: usize) -> OwnersOpts {
+        OwnersOpts {
+            scan: ScanOpts::default().min_total(1),
+            grouping: Grouping::Patterns(vec!["*".into()]),
+            top,
+            min_share: 0.0,
+            half_life_days: 0.0,
+            inactive_days: 0,
+        }
+    }
+
+    #[test]
+    fn owners_are_people_not_spellings_or_bots() {
+        // Ann has 4 lines under two spellings, Ben 3, a bot 6.
+        let mut t = TestRepo::new();
+        t.commit("Ann <ann@x.io>", "a", &[("a.rs", Some("1\n2\n"))]);
+        t.commit("ann <ANN@x.io>", "a", &[("a.rs", Some("1\n2\n3\n4\n"))]);
+        t.commit("Ben <ben@x.io>", "b", &[("b.rs", Some("x\ny\nz\n"))]);
+        t.commit(
+            "dependabot[bot] <49699333+dependabot[bot]@users.noreply.github.com>",
+            "bump",
+            &[("c.rs", Some("1\n2\n3\n4\n5\n6\n"))],
+        );
+        let repo = t.open();
+
+        let rules = collect(&repo, &opts(5)).unwrap().suggest(&opts(5)).unwrap();
+        assert_eq!(rules.len(), 1);
+        let owners: Vec<_> = rules[0].owners.iter().map(|c| c.email.as_str()).collect();
+        assert_eq!(owners, ["ann@x.io", "ben@x.io"]);
+        assert_eq!(rules[0].lines, 7);
+        assert_eq!(rules[0].owners[0].lines, 4);
+        assert!((rules[0].owners[0].share - 4.0 / 7.0).abs() < 1e-9);
+
+        let top = collect(&repo, &opts(1)).unwrap().suggest(&opts(1)).unwrap();
+        assert_eq!(top[0].owners.len(), 1);
+        assert_eq!(top[0].owners[0].email, "ann@x.io");
+    }
+
+    #[test]
+    fn diff_checks_the_existing_owners() {
+        // Old leaves early; Ben commits from a GitHub noreply address; Cat
+        // only writes docs.
+        let mut t = TestRepo::new();
+        t.commit("Old <old@x.io>", "x", &[("legacy/x.rs", Some("1\n2\n"))]);
+        t.commit("Ann <ann@x.io>", "a", &[("src/a.rs", Some("1\n2\n"))]);
+        let ben = "Ben Jones <12345+benj@users.noreply.github.com>";
+        t.commit(ben, "b", &[("src/b.rs", Some("1\n2\n"))]);
+        t.commit("Cat <cat@x.io>", "d", &[("docs/d.md", Some("1\n"))]);
+        t.commit("Ann <ann@x.io>", "a", &[("src/a.rs", Some("1\n2\n3\n"))]);
+        let repo = t.open();
+
+        let existing = CodeOwners::parse(
+            "# owners\n\
+             *         @org/docs\n\
+             /src/     @ann-gh @benj cat@x.io ghost@x.io\n\
+             /legacy/  old@x.io\n\
+             /gone/    ann@x.io\n",
+        )
+        .unwrap();
+        let aliases = HashMap::from([("ann-gh".to_string(), "ann@x.io".to_string())]);
+        let opts = OwnersOpts {
+            grouping: Grouping::Patterns(vec!["*".into(), "/src/".into(), "/legacy/".into()]),
+            inactive_days: 2,
+            ..opts(5)
+        };
+        let ownership = collect(&repo, &opts).unwrap();
+        let suggested = ownership.suggest(&opts).unwrap();
+        let diff = ownership
+            .diff(&repo, &suggested, &existing, &aliases, &opts)
+            .unwrap();
+
+        let flags: Vec<_> = diff
+            .flags
+            .iter()
+            .map(|f| (f.line, f.owner.as_str(), f.issue))
+            .collect();
+        assert_eq!(
+            flags,
+            [
+                (3, "cat@x.io", Issue::Untouched),
+                (3, "ghost@x.io", Issue::Unknown),
+                (4, "old@x.io", Issue::Left),
+                (5, "ann@x.io", Issue::NoFiles),
+            ]
+        );
+        assert!(diff.flags[2].last_commit.is_some());
+
+        let rule = |p: &str| diff.rules.iter().find(|r| r.pattern == p).unwrap();
+        let src = rule("/src/");
+        assert_eq!(
+            src.suggested,
+            ["ann@x.io", "12345+benj@users.noreply.github.com"]
+        );
+        assert_eq!(src.current, ["@ann-gh", "@benj", "cat@x.io", "ghost@x.io"]);
+        assert!(
+            src.added.is_empty(),
+            "handles resolve to the suggested people"
+        );
+        assert_eq!(src.removed, ["cat@x.io", "ghost@x.io"]);
+        let root = rule("*");
+        assert_eq!(root.added, ["cat@x.io"]);
+        assert!(root.removed.is_empty(), "teams are never reported");
+        let legacy = rule("/legacy/");
+        assert!(
+            legacy.suggested.is_empty(),
+            "people who left are not suggested"
+        );
+        assert_eq!(legacy.removed, ["old@x.io"]);
+    }
+
+    #[test]
+    fn patterns_match_like_codeowners() {
+        let root = pattern_matcher("/docs/").unwrap();
+        assert!(root.is_match("docs/a.md") && !root.is_match("src/docs/a.md"));
+        let any = pattern_matcher("*.proto").unwrap();
+        assert!(any.is_match("a.proto") && any.is_match("api/v1/a.proto"));
+        assert!(pattern_matcher("*").unwrap().is_match("deep/er/file"));
+    }
+}
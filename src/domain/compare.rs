@@ -0,0 +1,263 @@
+use anyhow::Result;
+use serde::Serialize;
+use std::collections::HashMap;
+
+use crate::domain::bus_factor::ScanOpts;
+use crate::domain::churn::{Churn, ChurnEntry};
+use crate::domain::engine;
+use crate::domain::git::RepoExt;
+use crate::domain::stats::{self, RepoScan};
+use crate::domain::window::TimeWindow;
+
+/// Everything `compare` looks at for one side.
+#[derive(Debug)]
+pub struct Side {
+    pub scan: RepoScan,
+    pub churn: Vec<ChurnEntry>,
+    /// Commits the side actually covers (its window, or first → last
+    /// selected commit when the window is open-ended).
+    pub span: TimeWindow,
+}
+
+/// Summary, authors and churn for the commits `repo` selects. Churn runs
+/// over the side's own span so both sides are ranked alike.
+pub fn collect_side(repo: &RepoExt, opts: &ScanOpts) -> Result<Side> {
+    let scan = stats::scan_repo(repo, None)?;
+    let w = repo.window();
+    // An empty side keeps its open ends: it has no first or last commit.
+    let seen = scan.summary.total_commits > 0;
+    let span = TimeWindow {
+        since: w.since.or(seen.then_some(scan.summary.first_date)),
+        until: w.until.or(seen.then_some(scan.summary.last_date)),
+    };
+    let churn = if seen {
+        engine::run(repo, Churn::new(span, opts.clone())?)?.finish()
+    } else {
+        Vec::new()
+    };
+    Ok(Side { scan, churn, span })
+}
+
+/// One summary metric on both sides.
+#[derive(Debug, Clone, Serialize)]
+pub struct MetricDelta {
+    pub metric: &'static str,
+    pub base: f64,
+    pub current: f64,
+    pub delta: f64,
+}
+
+/// A name ranked on the current side, with where it stood before.
+#[derive(Debug, Clone, Serialize)]
+pub struct RankDelta {
+    pub name: String,
+    pub rank: usize,
+    pub base_rank: Option<usize>,
+    pub value: f64,
+    pub base_value: f64,
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct Contributor {
+    pub author: String,
+    pub commits: f64,
+}
+
+/// Differences between two sides.
+#[derive(Debug, Clone, Serialize)]
+pub struct Comparison {
+    pub metrics: Vec<MetricDelta>,
+    /// Committed on the current side only.
+    pub new_contributors: Vec<Contributor>,
+    /// Committed on the base side only.
+    pub departed_contributors: Vec<Contributor>,
+    /// Current top-N authors.
+    pub top_authors: Vec<RankDelta>,
+    /// Current top-N churn paths.
+    pub churn_top: Vec<RankDelta>,
+    /// In the current churn top-N but not the base one.
+    pub churn_entered: Vec<String>,
+    /// In the base churn top-N but not the current one.
+    pub churn_left: Vec<String>,
+}
+
+pub fn compare(base: &Side, current: &Side, top: usize) -> Comparison {
+    let (b, c) = (&base.scan.summary, &current.scan.summary);
+    let metric = |metric, base: f64, current: f64| MetricDelta {
+        metric,
+        base,
+        current,
+        delta: current - base,
+    };
+    let metrics = vec![
+        metric(
+            "total_commits",
+            b.total_commits as f64,
+            c.total_commits as f64,
+        ),
+        metric(
+            "contributors_total",
+            b.contributors_total as f64,
+            c.contributors_total as f64,
+        ),
+        metric(
+            "avg_commits_per_day",
+            b.avg_commits_per_day,
+            c.avg_commits_per_day,
+        ),
+        metric("drive_by_ratio_pct", b.drive_by_ratio, c.drive_by_ratio),
+        metric(
+            "core_size_80pct",
+            b.core_size_80pct as f64,
+            c.core_size_80pct as f64,
+        ),
+        metric("concentration_hhi", b.hhi, c.hhi),
+        metric("concentration_gini", b.gini, c.gini),
+        metric("merge_rate_pct", b.merge_rate, c.merge_rate),
+        metric("revert_rate_pct", b.revert_rate, c.revert_rate),
+        metric(
+            "conventional_commit_pct",
+            b.conv_commit_pct,
+            c.conv_commit_pct,
+        ),
+        metric("body_present_pct", b.msg_body_pct, c.msg_body_pct),
+        metric(
+            "median_subject_len",
+            b.msg_median_len as f64,
+            c.msg_median_len as f64,
+        ),
+        metric("work_hours_pct", b.work_hours_pct, c.work_hours_pct),
+    ];
+
+    let base_authors = &base.scan.stats.data;
+    let cur_authors = &current.scan.stats.data;
+    let only_in = |side: &RepoScan, other: &HashMap<String, stats::AuthorMeta>| {
+        side.stats
+            .sorted(true)
+            .into_iter()
+            .filter(|(a, _)| !other.contains_key(*a))
+            .map(|(a, m)| Contributor {
+                author: a.clone(),
+                commits: m.count,
+            })
+            .collect::<Vec<_>>()
+    };
+
+    let author_ranks = |side: &Side| -> Vec<(String, f64)> {
+        side.scan
+            .stats
+            .sorted(true)
+            .into_iter()
+            .map(|(a, m)| (a.clone(), m.count))
+            .collect()
+    };
+    let churn_ranks = |side: &Side| -> Vec<(String, f64)> {
+        side.churn
+            .iter()
+            .map(|e| (e.path.clone(), e.churn))
+            .collect()
+    };
+
+    let base_churn = churn_ranks(base);
+    let cur_churn = churn_ranks(current);
+    let top_names = |v: &[(String, f64)]| -> Vec<String> {
+        v.iter().take(top).map(|(n, _)| n.clone()).collect()
+    };
+    let (base_top, cur_top) = (top_names(&base_churn), top_names(&cur_churn));
+
+    Comparison {
+        metrics,
+        new_contributors: only_in(&current.scan, base_authors),
+        departed_contributors: only_in(&base.scan, cur_authors),
+        top_authors: rank_deltas(&author_ranks(base), &author_ranks(current), top),
+        churn_top: rank_deltas(&base_churn, &cur_churn, top),
+        churn_entered: cur_top
+            .iter()
+            .filter(|n| !base_top.contains(n))
+            .cloned()
+            .collect(),
+        churn_left: base_top
+            .iter()
+            .filter(|n| !cur_top.contains(n))
+            .cloned()
+            .collect(),
+    }
+}
+
+/// The current top `n` with their base rank and value (ranks are 1-based).
+fn rank_deltas(base: &[(String, f64)], current: &[(String, f64)], n: usize) -> Vec<RankDelta> {
+    let base_pos: HashMap<&str, (usize, f64)> = base
+        .iter()
+        .enumerate()
+        .map(|(i, (name, v))| (name.as_str(), (i + 1, *v)))
+        .collect();
+    current
+        .iter()
+        .take(n)
+        .enumerate()
+        .map(|(i, (name, v))| {
+            let before = base_pos.get(name.as_str());
+            RankDelta {
+                name: name.clone(),
+                rank: i + 1,
+                base_rank: before.map(|b| b.0),
+                value: *v,
+                base_value: before.map_or(0.0, |b| b.1),
+            }
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::domain::git::RepoOptions;
+    use crate::domain::test_repo::TestRepo;
+
+    fn side(t: &TestRepo, since: Option<&str>, until: Option<&str>) -> Side {
+        let window = TimeWindow::parse(since, until, None).unwrap();
+        let repo = t.open_with(RepoOptions::default().window(window));
+        collect_side(&repo, &ScanOpts::default().min_total(1)).unwrap()
+    }
+
+    #[test]
+    fn an_empty_side_counts_as_zero() {
+        // Commits are made from 2023-11-15 on.
+        let mut t = TestRepo::new();
+        t.commit("Ann <ann@x.io>", "one", &[("a.rs", Some("1\n"))]);
+        t.commit("Ben <ben@x.io>", "two", &[("b.rs", Some("1\n2\n"))]);
+
+        let base = side(&t, None, Some("2023-10-15"));
+        let current = side(&t, Some("2023-10-16"), None);
+        assert_eq!(base.scan.summary.total_commits, 0);
+        assert!(base.span.since.is_none(), "no first commit to start from");
+        assert!(current.span.since.is_some() && current.span.until.is_some());
+
+        let cmp = compare(&base, &current, 10);
+        let commits = &cmp.metrics[0];
+        assert_eq!(commits.metric, "total_commits");
+        assert_eq!(
+            (commits.base, commits.current, commits.delta),
+            (0.0, 2.0, 2.0)
+        );
+        assert!(cmp.metrics.iter().all(|m| m.base == 0.0));
+        let new: Vec<_> = cmp
+            .new_contributors
+            .iter()
+            .map(|c| c.author.as_str())
+            .collect();
+        assert_eq!(new, ["Ann <ann@x.io>", "Ben <ben@x.io>"]);
+        assert!(cmp.departed_contributors.is_empty());
+        let churned: Vec<_> = current.churn.iter().map(|e| e.path.clone()).collect();
+        assert!(!churned.is_empty());
+        assert_eq!(cmp.churn_entered, churned);
+        assert!(cmp.churn_top.iter().all(|r| r.base_rank.is_none()));
+
+        // And the other way round: everyone departed.
+        let cmp = compare(&current, &base, 10);
+        assert!(cmp.metrics.iter().all(|m| m.current == 0.0));
+        assert_eq!(cmp.departed_contributors.len(), 2);
+        assert_eq!(cmp.churn_left, churned);
+        assert!(cmp.top_authors.is_empty());
+    }
+}
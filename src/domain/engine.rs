@@ -0,0 +1,255 @@
+use anyhow::Result;
+use chrono::{DateTime, Local};
+use git2::Commit;
+use std::cell::{OnceCell, RefCell};
+use std::rc::Rc;
+
+use crate::domain::cache::{CommitFacts, FileChange};
+use crate::domain::git::{commit_time, RepoExt};
+use crate::domain::identity::CoauthorCredit;
+
+/// A pluggable per-commit analysis fed by `scan`.
+///
+/// Analysers only accumulate; turning the state into a report is up to
+/// each type (usually a `finish`/`into_*` method).
+///
+/// Diffs (or cached facts) are loaded the first time an analyser reads
+/// `CommitCtx::changes` for a commit, so check cheap things like the
+/// commit time before asking for them.
+pub trait Analyser {
+    /// Called once per selected commit, newest first.
+    fn visit(&mut self, c: &CommitCtx);
+
+    /// Also receive commits by bots when `--bots` keeps them out of the
+    /// analyses (check `CommitCtx::by_bot`).
+    fn wants_bots(&self) -> bool {
+        false
+    }
+
+    /// Return true to stop receiving commits (e.g. a `--limit` was hit).
+    /// The walk ends once every analyser is done.
+    fn done(&self) -> bool {
+        false
+    }
+}
+
+/// One selected commit as seen by analysers.
+pub struct CommitCtx<'a> {
+    pub repo: &'a RepoExt,
+    pub commit: &'a Commit<'a>,
+    /// Canonical author (`Name <email>`).
+    pub author: String,
+    /// The author is a bot that `--bots` filters out; only analysers that
+    /// `wants_bots` see such commits.
+    pub by_bot: bool,
+    /// Canonical `Co-authored-by:` people (always parsed, whatever the
+    /// credit mode; filtered bots left out).
+    pub coauthors: Vec<String>,
+    /// Who gets credit for this commit and how much: the author first,
+    /// then co-authors, weighted per `--coauthor-credit`.
+    pub credits: Vec<(String, f64)>,
+    pub time: DateTime<Local>,
+    facts: OnceCell<Option<Rc<CommitFacts>>>,
+    /// First failure loading facts; `scan` returns it after the visit.
+    error: RefCell<Option<anyhow::Error>>,
+}
+
+impl CommitCtx<'_> {
+    /// Files changed against the first parent that pass the global path
+    /// filter. Root commits report nothing (their diff is the initial
+    /// import, not a change).
+    pub fn changes(&self) -> impl Iterator<Item = &FileChange> {
+        self.facts()
+            .into_iter()
+            .filter(|f| !f.is_root())
+            .flat_map(|f| f.files.iter())
+            .filter(|f| self.repo.path_ok(&f.path))
+    }
+
+    /// Like [`changes`](Self::changes), but a root commit reports the
+    /// files it adds, for counting the lines a commit wrote.
+    pub fn all_changes(&self) -> impl Iterator<Item = &FileChange> {
+        self.facts()
+            .into_iter()
+            .flat_map(|f| f.files.iter())
+            .filter(|f| self.repo.path_ok(&f.path))
+    }
+
+    pub fn is_merge(&self) -> bool {
+        self.commit.parent_count() > 1
+    }
+
+    fn facts(&self) -> Option<&Rc<CommitFacts>> {
+        self.facts
+            .get_or_init(|| match self.repo.facts(self.commit) {
+                Ok(f) => Some(f),
+                Err(e) => {
+                    self.error.borrow_mut().get_or_insert(e);
+                    None
+                }
+            })
+            .as_ref()
+    }
+}
+
+/// Walk the selected history once and dispatch every commit to all
+/// analysers. Each commit is diffed at most once (and usually read from
+/// the facts cache), however many analysers look at it, and only if one
+/// reads its changes.
+pub fn scan(repo: &RepoExt, analysers: &mut [&mut dyn Analyser]) -> Result<()> {
+    for commit in repo.commits()? {
+        if analysers.iter().all(|a| a.done()) {
+            break;
+        }
+        let author = repo.author(&commit);
+        let by_bot = repo.bots().skips(&author);
+        if by_bot && !analysers.iter().any(|a| a.wants_bots()) {
+            continue;
+        }
+        let author = author.to_string();
+        let coauthors: Vec<String> = repo
+            .coauthors(&commit)
+            .iter()
+            .filter(|c| !repo.bots().skips(c))
+            .map(|c| c.to_string())
+            .collect();
+        let credits = credits(repo.coauthor_credit(), &author, &coauthors);
+        let ctx = CommitCtx {
+            repo,
+            commit: &commit,
+            author,
+            by_bot,
+            coauthors,
+            credits,
+            time: commit_time(&commit),
+            facts: OnceCell::new(),
+            error: RefCell::new(None),
+        };
+        for a in analysers
+            .iter_mut()
+            .filter(|a| !a.done() && (!by_bot || a.wants_bots()))
+        {
+            a.visit(&ctx);
+        }
+        if let Some(e) = ctx.error.into_inner() {
+            return Err(e);
+        }
+    }
+    Ok(())
+}
+
+fn credits(mode: CoauthorCredit, author: &str, coauthors: &[String]) -> Vec<(String, f64)> {
+    let people: Vec<&str> = match mode {
+        CoauthorCredit::Ignore => vec![author],
+        _ => std::iter::once(author)
+            .chain(coauthors.iter().map(String::as_str))
+            .collect(),
+    };
+    let w = mode.weight(people.len());
+    people.into_iter().map(|p| (p.to_string(), w)).collect()
+}
+
+/// `scan` with a single analyser, handing it back when done.
+pub fn run<A: Analyser>(repo: &RepoExt, mut analyser: A) -> Result<A> {
+    scan(repo, &mut [&mut analyser])?;
+    Ok(analyser)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::domain::bus_factor::ScanOpts;
+    use crate::domain::cache::FactsCache;
+    use crate::domain::churn::compute_churn;
+    use crate::domain::git::{commit_time, RepoOptions};
+    use crate::domain::test_repo::TestRepo;
+    use crate::domain::window::TimeWindow;
+
+    fn history() -> (TestRepo, Vec<git2::Oid>) {
+        let mut t = TestRepo::new();
+        let ids = (1..=4)
+            .map(|i| {
+                let text = format!("{i}\n");
+                t.commit("A <a@x.io>", "c", &[("a.rs", Some(text.as_str()))])
+            })
+            .collect();
+        (t, ids)
+    }
+
+    fn cached(t: &TestRepo) -> usize {
+        FactsCache::open(&t.repo).unwrap().len()
+    }
+
+    #[test]
+    fn commits_are_only_diffed_when_changes_are_read() {
+        let (t, _) = history();
+        let repo = RepoOptions::default()
+            .open(t.dir.path().to_str().unwrap())
+            .unwrap();
+        crate::domain::firsts::first_commits(&repo).unwrap();
+        drop(repo);
+        assert_eq!(cached(&t), 0);
+    }
+
+    #[test]
+    fn churn_stops_diffing_at_the_start_of_its_window() {
+        let (t, ids) = history();
+        let newest = commit_time(&t.repo.find_commit(ids[3]).unwrap());
+        let repo = RepoOptions::default()
+            .window(TimeWindow {
+                since: None,
+                until: Some(newest),
+            })
+            .open(t.dir.path().to_str().unwrap())
+            .unwrap();
+        // One day back reaches the third commit exactly.
+        let churn = compute_churn(&repo, 1, &ScanOpts::default()).unwrap();
+        assert_eq!(churn[0].touches, 2);
+        drop(repo);
+        assert_eq!(cached(&t), 2);
+    }
+
+    #[test]
+    fn coauthor_credit_modes() {
+        let co = vec!["B <b@x.io>".to_string(), "C <c@x.io>".to_string()];
+        let full = credits(CoauthorCredit::Full, "A <a@x.io>", &co);
+        assert_eq!(full.len(), 3);
+        assert!(full.iter().all(|(_, w)| *w == 1.0));
+        assert_eq!(full[0].0, "A <a@x.io>");
+
+        let split = credits(CoauthorCredit::Split, "A <a@x.io>", &co);
+        let total: f64 = split.iter().map(|(_, w)| w).sum();
+        assert!((total - 1.0).abs() < 1e-9);
+
+        let ignore = credits(CoauthorCredit::Ignore, "A <a@x.io>", &co);
+        assert_eq!(ignore, vec![("A <a@x.io>".to_string(), 1.0)]);
+    }
+
+    #[test]
+    fn coauthors_are_credited_once_and_never_as_the_author() {
+        let mut t = TestRepo::new();
+        t.commit(
+            "Ann <ann@x.io>",
+            "pair\n\nCo-authored-by: ann <ANN@x.io>\nCo-authored-by: Bo <bo@x.io>\nCo-authored-by: bo <bo@x.io>\n",
+            &[("a", Some("1\n"))],
+        );
+        let stats = crate::domain::stats::collect_commits(&t.open(), usize::MAX).unwrap();
+        assert_eq!(stats.data.len(), 2);
+        assert_eq!(stats.find("bo@x.io").unwrap().1.count, 1.0);
+        assert_eq!(stats.find("ann@x.io").unwrap().1.count, 1.0);
+    }
+
+    #[test]
+    fn changes_skip_the_root_commit_but_all_changes_do_not() {
+        struct Count(usize, usize);
+        impl Analyser for Count {
+            fn visit(&mut self, c: &CommitCtx) {
+                self.0 += c.changes().count();
+                self.1 += c.all_changes().count();
+            }
+        }
+        let (t, _) = history();
+        let counts = run(&t.open(), Count(0, 0)).unwrap();
+        assert_eq!((counts.0, counts.1), (3, 4));
+    }
+}
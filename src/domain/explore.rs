@@ -0,0 +1,195 @@
+use anyhow::Result;
+use std::collections::{BTreeMap, HashMap};
+
+use crate::domain::bus_factor::{ScanOpts, TouchOwnership};
+use crate::domain::churn::{self, Churn};
+use crate::domain::engine::{self, Analyser};
+use crate::domain::git::RepoExt;
+use crate::domain::stats::{RepoScan, RepoScanner};
+use crate::domain::window::TimeWindow;
+
+/// Touches, churn and per-author credit of one file or directory.
+#[derive(Debug, Clone, Default)]
+pub struct PathStats {
+    /// Commits changing the path (for a directory: summed over its files).
+    pub touches: usize,
+    /// Decay-weighted churn in the churn window.
+    pub churn: f64,
+    /// Author → touch credit (co-authors per `--coauthor-credit`).
+    pub credit: HashMap<String, f64>,
+}
+
+impl PathStats {
+    /// Author with the most credit and their share of it.
+    pub fn owner(&self) -> Option<(&str, f64)> {
+        let sum: f64 = self.credit.values().sum();
+        self.credit
+            .iter()
+            .max_by(|a, b| a.1.total_cmp(b.1).then_with(|| b.0.cmp(a.0)))
+            .filter(|_| sum > 0.0)
+            .map(|(a, c)| (a.as_str(), c / sum))
+    }
+
+    fn add(&mut self, other: &PathStats) {
+        self.touches += other.touches;
+        self.churn += other.churn;
+        for (a, c) in &other.credit {
+            *self.credit.entry(a.clone()).or_default() += c;
+        }
+    }
+}
+
+/// A file or subdirectory listed in a directory.
+#[derive(Debug, Clone)]
+pub struct Entry {
+    pub name: String,
+    /// Path from the repository root (no trailing slash).
+    pub path: String,
+    pub is_dir: bool,
+    pub stats: PathStats,
+}
+
+/// Everything the explorer shows, from one history walk.
+#[derive(Debug)]
+pub struct Explore {
+    pub scan: RepoScan,
+    /// Files at the selected tip that pass the path and extension filters.
+    pub files: BTreeMap<String, PathStats>,
+    pub churn_window: TimeWindow,
+}
+
+pub fn collect(repo: &RepoExt, window_days: i64, opts: &ScanOpts) -> Result<Explore> {
+    let churn_window = churn::churn_window(repo, window_days);
+    let mut scanner = RepoScanner::new(None);
+    let mut churn = Churn::new(churn_window, opts.clone())?;
+    let mut touches = TouchOwnership::new(None, opts.clone());
+    let mut analysers: Vec<&mut dyn Analyser> = vec![&mut scanner, &mut churn, &mut touches];
+    engine::scan(repo, &mut analysers)?;
+
+    let churn: HashMap<String, f64> = churn
+        .finish()
+        .into_iter()
+        .map(|e| (e.path, e.churn))
+        .collect();
+    let mut touched = touches.touches;
+    let files = repo
+        .tracked_files()?
+        .into_iter()
+        .filter(|f| opts.ext_ok(f))
+        .map(|f| {
+            let t = touched.remove(&f).unwrap_or_default();
+            let stats = PathStats {
+                touches: t.commits,
+                churn: churn.get(&f).copied().unwrap_or(0.0),
+                credit: t.by_author,
+            };
+            (f, stats)
+        })
+        .collect();
+    Ok(Explore {
+        scan: scanner.finish(),
+        files,
+        churn_window,
+    })
+}
+
+impl Explore {
+    /// Direct children of `dir` (`""` = root), directories aggregated
+    /// over every file below them.
+    pub fn list(&self, dir: &str) -> Vec<Entry> {
+        let prefix = if dir.is_empty() {
+            String::new()
+        } else {
+            format!("{dir}/")
+        };
+        let mut dirs: BTreeMap<&str, PathStats> = BTreeMap::new();
+        let mut out = Vec::new();
+        for (path, stats) in self.files.range(prefix.clone()..) {
+            let Some(rest) = path.strip_prefix(&prefix) else {
+                break;
+            };
+            match rest.split_once('/') {
+                Some((sub, _)) => dirs.entry(sub).or_default().add(stats),
+                None => out.push(Entry {
+                    name: rest.to_string(),
+                    path: path.clone(),
+                    is_dir: false,
+                    stats: stats.clone(),
+                }),
+            }
+        }
+        out.extend(dirs.into_iter().map(|(name, stats)| Entry {
+            name: name.to_string(),
+            path: format!("{prefix}{name}"),
+            is_dir: true,
+            stats,
+        }));
+        out
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::domain::test_repo::TestRepo;
+
+    const ANN: &str = "Ann <ann@x.io>";
+    const BEN: &str = "Ben <ben@x.io>";
+
+    fn explore() -> Explore {
+        let mut t = TestRepo::new();
+        t.commit(ANN, "root", &[("README.md", Some("hi\n"))]);
+        t.commit(ANN, "a", &[("src/a.rs", Some("1\n2\n"))]);
+        t.commit(BEN, "b", &[("src/sub/b.rs", Some("1\n"))]);
+        t.commit(BEN, "a again", &[("src/a.rs", Some("1\n2\n3\n"))]);
+        t.commit(ANN, "sibling", &[("src2/c.rs", Some("1\n"))]);
+        let opts = ScanOpts::default().all(true).min_total(1);
+        collect(&t.open(), 100_000, &opts).unwrap()
+    }
+
+    fn names(entries: &[Entry]) -> Vec<(&str, &str, bool)> {
+        entries
+            .iter()
+            .map(|e| (e.name.as_str(), e.path.as_str(), e.is_dir))
+            .collect()
+    }
+
+    #[test]
+    fn directories_list_files_then_subdirectories() {
+        let x = explore();
+        assert_eq!(
+            names(&x.list("")),
+            [
+                ("README.md", "README.md", false),
+                ("src", "src", true),
+                ("src2", "src2", true),
+            ]
+        );
+        assert_eq!(
+            names(&x.list("src")),
+            [("a.rs", "src/a.rs", false), ("sub", "src/sub", true)]
+        );
+        assert_eq!(names(&x.list("src/sub")), [("b.rs", "src/sub/b.rs", false)]);
+        assert!(x.list("nope").is_empty());
+    }
+
+    #[test]
+    fn directories_sum_their_files() {
+        let x = explore();
+        let a = &x.files["src/a.rs"];
+        assert_eq!(a.touches, 2);
+        assert_eq!(a.owner().map(|(_, share)| share), Some(0.5));
+        assert!(a.churn > 0.0);
+
+        let src = x.list("").into_iter().find(|e| e.name == "src").unwrap();
+        assert_eq!(src.stats.touches, 3);
+        assert_eq!(src.stats.credit[ANN], 1.0);
+        assert_eq!(src.stats.credit[BEN], 2.0);
+        let (owner, share) = src.stats.owner().unwrap();
+        assert_eq!(owner, BEN);
+        assert!((share - 2.0 / 3.0).abs() < 1e-9);
+        let churn = a.churn + x.files["src/sub/b.rs"].churn;
+        assert!((src.stats.churn - churn).abs() < 1e-9);
+        assert_eq!(PathStats::default().owner(), None);
+    }
+}
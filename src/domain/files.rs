@@ -1,30 +1,26 @@
 use anyhow::Result;
-use git2::Repository;
 use std::collections::BTreeMap;
 
-pub fn file_contributions(repo: &Repository) -> Result<BTreeMap<String, BTreeMap<String, usize>>> {
-    let mut file_authors: BTreeMap<String, BTreeMap<String, usize>> = BTreeMap::new();
-    let mut rw = repo.revwalk()?;
-    rw.push_head()?;
+use crate::domain::engine::{self, Analyser, CommitCtx};
+use crate::domain::git::RepoExt;
 
-    for oid in rw.flatten() {
-        let commit = repo.find_commit(oid)?;
-        let tree = commit.tree()?;
+/// Commit credit per file per author (see `CommitCtx::credits`).
+#[derive(Debug, Default)]
+pub struct FileContributions {
+    pub by_file: BTreeMap<String, BTreeMap<String, f64>>,
+}
 
-        if let Ok(parent) = commit.parent(0) {
-            let parent_tree = parent.tree()?;
-            let diff = repo.diff_tree_to_tree(Some(&parent_tree), Some(&tree), None)?;
-            diff.deltas().for_each(|delta| {
-                if let Some(path) = delta.new_file().path().and_then(|p| p.to_str()) {
-                    let email = commit.author().email().unwrap_or("unknown").to_string();
-                    *file_authors
-                        .entry(path.to_string())
-                        .or_default()
-                        .entry(email)
-                        .or_default() += 1;
-                }
-            });
+impl Analyser for FileContributions {
+    fn visit(&mut self, c: &CommitCtx) {
+        for f in c.changes() {
+            let authors = self.by_file.entry(f.path.clone()).or_default();
+            for (who, w) in &c.credits {
+                *authors.entry(who.clone()).or_default() += w;
+            }
         }
     }
-    Ok(file_authors)
+}
+
+pub fn file_contributions(repo: &RepoExt) -> Result<BTreeMap<String, BTreeMap<String, f64>>> {
+    Ok(engine::run(repo, FileContributions::default())?.by_file)
 }
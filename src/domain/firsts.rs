@@ -1,29 +1,31 @@
 use anyhow::Result;
-use chrono::{DateTime, Local, TimeZone};
-use git2::Repository;
+use chrono::{DateTime, Local};
 use std::collections::BTreeMap;
 
-pub fn first_commits(repo: &Repository) -> Result<BTreeMap<String, DateTime<Local>>> {
-    let mut firsts: BTreeMap<String, DateTime<Local>> = BTreeMap::new();
-    let mut rw = repo.revwalk()?;
-    rw.push_head()?;
+use crate::domain::engine::{self, Analyser, CommitCtx};
+use crate::domain::git::RepoExt;
 
-    for oid in rw.flatten() {
-        let commit = repo.find_commit(oid)?;
-        let dt = Local
-            .timestamp_opt(commit.time().seconds(), 0)
-            .single()
-            .unwrap();
-        let email = commit.author().email().unwrap_or("unknown").to_string();
+/// Earliest commit time per author.
+#[derive(Debug, Default)]
+pub struct FirstCommits {
+    pub firsts: BTreeMap<String, DateTime<Local>>,
+}
 
-        firsts
-            .entry(email.clone())
-            .and_modify(|d| {
-                if dt < *d {
-                    *d = dt
-                }
-            })
-            .or_insert(dt);
+impl Analyser for FirstCommits {
+    fn visit(&mut self, c: &CommitCtx) {
+        for (who, _) in &c.credits {
+            self.firsts
+                .entry(who.clone())
+                .and_modify(|d| {
+                    if c.time < *d {
+                        *d = c.time
+                    }
+                })
+                .or_insert(c.time);
+        }
     }
-    Ok(firsts)
+}
+
+pub fn first_commits(repo: &RepoExt) -> Result<BTreeMap<String, DateTime<Local>>> {
+    Ok(engine::run(repo, FirstCommits::default())?.firsts)
 }
@@ -1,15 +1,408 @@
 use anyhow::{Context, Result};
-use git2::Repository;
+use chrono::{DateTime, Local, TimeZone};
+use git2::{
+    BlameOptions, Commit, ObjectType, Oid, Repository, Revwalk, Signature, Sort, TreeWalkMode,
+    TreeWalkResult,
+};
+use std::cell::RefCell;
+use std::path::{Path, PathBuf};
+use std::rc::Rc;
 
-pub struct RepoExt(pub Repository);
+use crate::domain::blame::{BlameSettings, BlameTuning};
+use crate::domain::bots::{BotFilter, BotMode};
+use crate::domain::cache::{CommitFacts, FactsCache};
+use crate::domain::identity::{coauthor_trailers, CoauthorCredit, Identity, Mailmap};
+use crate::domain::paths::{LinguistFilter, PathFilter};
+use crate::domain::revs::{ResolvedRevs, RevSelection};
+use crate::domain::window::TimeWindow;
+
+/// Repository-wide options shared by every command.
+///
+/// Start from `RepoOptions::default()`, chain the setters and finish with
+/// `open`:
+///
+/// ```no_run
+/// use gitrivia::{BotMode, RepoOptions, TimeWindow};
+///
+/// let repo = RepoOptions::default()
+///     .window(TimeWindow::parse(None, None, Some("90d"))?)
+///     .pathspec(["src/**"])
+///     .bots(BotMode::Exclude)
+///     .open(".")?;
+/// # anyhow::Ok(())
+/// ```
+#[derive(Debug, Clone, Default)]
+pub struct RepoOptions {
+    /// Extra mailmap file applied on top of the repository's own.
+    pub mailmap: Option<PathBuf>,
+    /// Commits to analyse (defaults to everything reachable from HEAD).
+    pub revs: RevSelection,
+    /// Only commits inside this time window count.
+    pub window: TimeWindow,
+    /// Include/exclude path patterns (`src/**`, `!**/vendor/**`).
+    pub pathspec: Vec<String>,
+    /// Skip files marked `linguist-generated` / `linguist-vendored`.
+    pub skip_generated: bool,
+    /// Keep commit facts in memory only instead of `.git/gitrivia/`.
+    pub no_cache: bool,
+    /// Credit for people named in `Co-authored-by:` trailers.
+    pub coauthor_credit: CoauthorCredit,
+    /// Whether bot commits count, are dropped, or are reported apart.
+    pub bots: BotMode,
+    /// Extra bot patterns (regex on `Name <email>`).
+    pub bot_patterns: Vec<String>,
+    /// Revisions blame looks past, whitespace and move detection.
+    pub blame: BlameSettings,
+}
+
+impl RepoOptions {
+    /// Extra mailmap file applied on top of the repository's own.
+    pub fn mailmap(mut self, path: impl Into<PathBuf>) -> Self {
+        self.mailmap = Some(path.into());
+        self
+    }
+
+    /// Commits to analyse.
+    pub fn revs(mut self, revs: RevSelection) -> Self {
+        self.revs = revs;
+        self
+    }
+
+    /// Only count commits inside `window`.
+    pub fn window(mut self, window: TimeWindow) -> Self {
+        self.window = window;
+        self
+    }
+
+    /// Include/exclude path patterns (`src/**`, `!**/vendor/**`).
+    pub fn pathspec(mut self, patterns: impl IntoIterator<Item = impl Into<String>>) -> Self {
+        self.pathspec = patterns.into_iter().map(Into::into).collect();
+        self
+    }
+
+    /// Skip files marked `linguist-generated` / `linguist-vendored`.
+    pub fn skip_generated(mut self, skip: bool) -> Self {
+        self.skip_generated = skip;
+        self
+    }
+
+    /// Keep commit facts in memory only instead of `.git/gitrivia/`.
+    pub fn no_cache(mut self, no_cache: bool) -> Self {
+        self.no_cache = no_cache;
+        self
+    }
+
+    /// Credit for people named in `Co-authored-by:` trailers.
+    pub fn coauthor_credit(mut self, credit: CoauthorCredit) -> Self {
+        self.coauthor_credit = credit;
+        self
+    }
+
+    /// Whether bot commits count, are dropped, or are reported apart.
+    pub fn bots(mut self, mode: BotMode) -> Self {
+        self.bots = mode;
+        self
+    }
+
+    /// Extra bot patterns (regex on `Name <email>`).
+    pub fn bot_patterns(mut self, patterns: impl IntoIterator<Item = impl Into<String>>) -> Self {
+        self.bot_patterns = patterns.into_iter().map(Into::into).collect();
+        self
+    }
+
+    /// Revisions blame looks past, whitespace and move detection.
+    pub fn blame(mut self, settings: BlameSettings) -> Self {
+        self.blame = settings;
+        self
+    }
+
+    /// Open the repository at (or above) `path` with these options.
+    pub fn open(&self, path: &str) -> Result<RepoExt> {
+        RepoExt::open(path, self)
+    }
+}
+
+/// A repository plus everything needed to interpret it consistently
+/// (identity resolution, revision selection, time window, path filters).
+/// Domain code goes through this, never through a bare `Repository`.
+pub struct RepoExt {
+    repo: Repository,
+    mailmap: Mailmap,
+    revs: ResolvedRevs,
+    window: TimeWindow,
+    tip: Oid,
+    /// No selected commit is at or before `--until`.
+    empty: bool,
+    paths: PathFilter,
+    linguist: Option<LinguistFilter>,
+    no_cache: bool,
+    coauthor_credit: CoauthorCredit,
+    bots: BotFilter,
+    blame: BlameTuning,
+    /// Opened lazily: commands that never diff don't pay for loading it.
+    facts: RefCell<Option<FactsCache>>,
+}
 
 impl RepoExt {
-    pub fn open(path: &str) -> Result<Self> {
-        Repository::discover(path)
-            .with_context(|| format!("cannot open repo at {path}"))
-            .map(Self)
+    pub fn open(path: &str, opts: &RepoOptions) -> Result<Self> {
+        let repo =
+            Repository::discover(path).with_context(|| format!("cannot open repo at {path}"))?;
+        let mut mailmap = Mailmap::load(&repo, opts.mailmap.as_deref())?;
+        let revs = ResolvedRevs::resolve(&repo, &opts.revs)?;
+        learn_names(&repo, &revs, &mut mailmap)?;
+        let mut this = Self {
+            tip: revs.tip,
+            empty: false,
+            repo,
+            mailmap,
+            revs,
+            window: opts.window,
+            paths: PathFilter::new(&opts.pathspec)?,
+            linguist: opts.skip_generated.then(LinguistFilter::new),
+            no_cache: opts.no_cache,
+            coauthor_credit: opts.coauthor_credit,
+            bots: BotFilter::new(opts.bots, &opts.bot_patterns)?,
+            blame: BlameTuning::default(),
+            facts: RefCell::new(None),
+        };
+        // Blame "as of --until": newest selected commit not after the bound.
+        // With none, the selection is empty, like a `--since` in the future.
+        if let Some(until) = this.window.until {
+            let tip = this.revwalk()?.flatten().find(|id| {
+                this.repo
+                    .find_commit(*id)
+                    .is_ok_and(|c| commit_time(&c) <= until)
+            });
+            match tip {
+                Some(t) => this.tip = t,
+                None => this.empty = true,
+            }
+        }
+        this.blame = BlameTuning::resolve(&this.repo, this.tip, &opts.blame)?;
+        Ok(this)
     }
     pub fn repo(&self) -> &Repository {
-        &self.0
+        &self.repo
+    }
+    /// Work tree (or bare repository) directory name.
+    pub fn name(&self) -> String {
+        let dir = self.repo.workdir().unwrap_or_else(|| self.repo.path());
+        dir.canonicalize()
+            .ok()
+            .as_deref()
+            .and_then(Path::file_name)
+            .map(|n| n.to_string_lossy().trim_end_matches(".git").to_string())
+            .unwrap_or_else(|| "repository".into())
+    }
+    pub fn mailmap(&self) -> &Mailmap {
+        &self.mailmap
+    }
+
+    /// Canonical identity for a signature.
+    pub fn identity(&self, sig: &Signature) -> Identity {
+        self.mailmap.resolve_signature(sig)
+    }
+    /// Canonical author of a commit.
+    pub fn author(&self, commit: &Commit) -> Identity {
+        self.identity(&commit.author())
+    }
+
+    /// Canonical co-authors from `Co-authored-by:` trailers, without the
+    /// commit author and duplicates.
+    pub fn coauthors(&self, commit: &Commit) -> Vec<Identity> {
+        let author = self.author(commit);
+        let mut out: Vec<Identity> = Vec::new();
+        for (name, email) in coauthor_trailers(commit.message().unwrap_or("")) {
+            let id = self.mailmap.resolve(&name, &email);
+            if id != author && !out.contains(&id) {
+                out.push(id);
+            }
+        }
+        out
+    }
+
+    pub fn coauthor_credit(&self) -> CoauthorCredit {
+        self.coauthor_credit
+    }
+
+    pub fn bots(&self) -> &BotFilter {
+        &self.bots
+    }
+
+    pub fn window(&self) -> &TimeWindow {
+        &self.window
+    }
+
+    /// Newest-first walk over the selected revisions (ignores the window).
+    pub fn revwalk(&self) -> Result<Revwalk<'_>> {
+        let mut walk = self.repo.revwalk()?;
+        self.revs.apply(&mut walk)?;
+        walk.set_sorting(Sort::TIME)?;
+        Ok(walk)
+    }
+
+    /// Selected commits inside the time window, newest first. Unreadable
+    /// commits are skipped. With a path filter, only commits touching a
+    /// selected path are kept (like `git log -- <paths>`).
+    pub fn commits(&self) -> Result<impl Iterator<Item = Commit<'_>>> {
+        let walk = self.revwalk()?;
+        Ok(walk
+            .flatten()
+            .filter_map(|id| self.repo.find_commit(id).ok())
+            .filter(|c| self.window.contains(commit_time(c)))
+            .filter(|c| !self.filters_paths() || self.touches_selected_paths(c)))
+    }
+
+    /// Whether any path filter is active.
+    pub fn filters_paths(&self) -> bool {
+        !self.paths.is_empty() || self.linguist.is_some()
+    }
+
+    /// Path passes the global include/exclude patterns and linguist markers.
+    pub fn path_ok(&self, path: &str) -> bool {
+        self.paths.matches(path)
+            && self
+                .linguist
+                .as_ref()
+                .is_none_or(|l| l.allows(&self.repo, path))
+    }
+
+    fn touches_selected_paths(&self, commit: &Commit) -> bool {
+        let Ok(facts) = self.facts(commit) else {
+            return false;
+        };
+        facts.files.iter().any(|f| {
+            self.path_ok(&f.path) || f.old_path.as_deref().is_some_and(|p| self.path_ok(p))
+        })
+    }
+
+    /// Per-commit facts (changed files, line stats, renames), read from the
+    /// on-disk cache or computed and added to it.
+    pub fn facts(&self, commit: &Commit) -> Result<Rc<CommitFacts>> {
+        let mut slot = self.facts.borrow_mut();
+        let cache = match slot.as_mut() {
+            Some(c) => c,
+            None => slot.insert(self.open_cache()?),
+        };
+        cache.get(&self.repo, commit)
+    }
+
+    /// The facts cache backing `facts` (honours `--no-cache`).
+    pub fn open_cache(&self) -> Result<FactsCache> {
+        if self.no_cache {
+            Ok(FactsCache::in_memory())
+        } else {
+            FactsCache::open(&self.repo)
+        }
+    }
+
+    /// Commit that blame and file listings look at. For an empty
+    /// selection, the newest selected commit, whose files don't count.
+    pub fn tip(&self) -> Oid {
+        self.tip
+    }
+
+    /// Whether the window leaves no commit to look at (`--until` before
+    /// the first one): no files at the tip and nothing to blame.
+    pub fn is_empty(&self) -> bool {
+        self.empty
+    }
+
+    /// Blame options anchored at the selected tip.
+    pub fn blame_options(&self) -> BlameOptions {
+        self.blame.options_at(self.tip())
+    }
+
+    /// Revisions blame looks past, whitespace and move detection; pass to
+    /// [`blame::hunks`](crate::domain::blame::hunks).
+    pub fn blame_tuning(&self) -> &BlameTuning {
+        &self.blame
+    }
+
+    /// Blob paths in the tip commit's tree that pass the path filters.
+    pub fn tracked_files(&self) -> Result<Vec<String>> {
+        if self.empty {
+            return Ok(Vec::new());
+        }
+        let tree = self.repo.find_commit(self.tip())?.tree()?;
+        let mut files = Vec::new();
+        tree.walk(TreeWalkMode::PreOrder, |dir, entry| {
+            if entry.kind() == Some(ObjectType::Blob) {
+                if let Some(name) = entry.name() {
+                    files.push(format!("{dir}{name}"));
+                }
+            }
+            TreeWalkResult::Ok
+        })?;
+        files.retain(|f| self.path_ok(f));
+        Ok(files)
+    }
+}
+
+/// Display names from the history the selected tips reach (newest first),
+/// so every command names a person the same way.
+fn learn_names(repo: &Repository, revs: &ResolvedRevs, mailmap: &mut Mailmap) -> Result<()> {
+    let mut walk = repo.revwalk()?;
+    revs.apply_reachable(&mut walk)?;
+    walk.set_sorting(Sort::TIME)?;
+    mailmap.learn_names(walk.flatten().flat_map(|id| {
+        let Ok(commit) = repo.find_commit(id) else {
+            return Vec::new();
+        };
+        let author = commit.author();
+        let mut people = vec![(
+            author.name().unwrap_or("unknown").to_string(),
+            author.email().unwrap_or("unknown").to_string(),
+        )];
+        people.extend(coauthor_trailers(commit.message().unwrap_or("")));
+        people
+    }));
+    Ok(())
+}
+
+/// Commit time in the local time zone.
+pub fn commit_time(commit: &Commit) -> DateTime<Local> {
+    Local
+        .timestamp_opt(commit.time().seconds(), 0)
+        .single()
+        .unwrap_or_else(Local::now)
+}
+
+/// Blame options for `tip`; usable from worker threads that opened their
+/// own `Repository`.
+pub fn blame_options_at(tip: Oid) -> BlameOptions {
+    let mut opts = BlameOptions::new();
+    opts.newest_commit(tip);
+    opts
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::domain::stats::scan_repo;
+    use crate::domain::test_repo::TestRepo;
+
+    fn window(since: Option<&str>, until: Option<&str>) -> RepoOptions {
+        RepoOptions::default().window(TimeWindow::parse(since, until, None).unwrap())
+    }
+
+    #[test]
+    fn empty_windows_select_nothing_at_either_end() {
+        // Commits are made in November 2023.
+        let mut t = TestRepo::new();
+        t.commit("A <a@x.io>", "one", &[("a.rs", Some("1\n"))]);
+        for opts in [
+            window(None, Some("2020-01-01")),
+            window(Some("2030-01-01"), None),
+        ] {
+            let repo = t.open_with(opts);
+            assert_eq!(repo.commits().unwrap().count(), 0);
+            assert_eq!(scan_repo(&repo, None).unwrap().summary.total_commits, 0);
+        }
+        let before = t.open_with(window(None, Some("2020-01-01")));
+        assert!(before.is_empty());
+        assert!(before.tracked_files().unwrap().is_empty());
+        assert!(!t.open().is_empty());
+        assert_eq!(t.open().tracked_files().unwrap(), ["a.rs"]);
     }
 }
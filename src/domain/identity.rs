@@ -0,0 +1,339 @@
+use anyhow::{bail, Context, Result};
+use git2::{Repository, Signature};
+use std::collections::HashMap;
+use std::fmt;
+use std::path::Path;
+
+/// Canonical author identity (after `.mailmap` resolution): the email is
+/// lower-cased and each email has one display name, so `to_string()` is a
+/// stable per-person key.
+#[derive(Debug, Clone, PartialEq, Eq, Hash, PartialOrd, Ord)]
+pub struct Identity {
+    pub name: String,
+    pub email: String,
+}
+
+impl fmt::Display for Identity {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        if self.name.is_empty() {
+            write!(f, "<{}>", self.email)
+        } else {
+            write!(f, "{} <{}>", self.name, self.email)
+        }
+    }
+}
+
+#[derive(Debug, Clone, Default)]
+struct Replacement {
+    name: Option<String>,
+    email: Option<String>,
+}
+
+impl Replacement {
+    fn merge(&mut self, name: Option<String>, email: Option<String>) {
+        if name.is_some() {
+            self.name = name;
+        }
+        if email.is_some() {
+            self.email = email;
+        }
+    }
+}
+
+#[derive(Debug, Clone, Default)]
+struct Entry {
+    /// Applies to any commit name using this email.
+    any_name: Option<Replacement>,
+    /// Applies only when the commit name matches too (lower-cased key).
+    by_name: HashMap<String, Replacement>,
+}
+
+/// Pure-Rust mailmap (same semantics as `git check-mailmap`).
+///
+/// Kept independent of `git2::Mailmap` so it can be shared across rayon
+/// workers (parallel blame) without reopening the repository.
+#[derive(Debug, Clone, Default)]
+pub struct Mailmap {
+    entries: HashMap<String, Entry>, // lower-cased commit email -> mappings
+    /// Display name per canonical email (see `learn_names`).
+    names: HashMap<String, String>,
+}
+
+impl Mailmap {
+    /// Load the repository's mailmap: `.mailmap` in the work tree (or
+    /// `HEAD:.mailmap` for bare repos), `mailmap.file` from git config,
+    /// and finally `extra` if given. Later sources win.
+    pub fn load(repo: &Repository, extra: Option<&Path>) -> Result<Self> {
+        let mut mm = Mailmap::default();
+
+        match repo.workdir() {
+            Some(wd) => {
+                if let Ok(text) = std::fs::read_to_string(wd.join(".mailmap")) {
+                    mm.parse(&text);
+                }
+            }
+            None => {
+                if let Some(text) = head_blob(repo, ".mailmap") {
+                    mm.parse(&text);
+                }
+            }
+        }
+
+        if let Ok(cfg) = repo.config() {
+            if let Ok(file) = cfg.get_path("mailmap.file") {
+                if let Ok(text) = std::fs::read_to_string(&file) {
+                    mm.parse(&text);
+                }
+            }
+        }
+
+        if let Some(path) = extra {
+            let text = std::fs::read_to_string(path)
+                .with_context(|| format!("cannot read mailmap {}", path.display()))?;
+            mm.parse(&text);
+        }
+        Ok(mm)
+    }
+
+    /// Add all mappings from mailmap-formatted `text`.
+    pub fn parse(&mut self, text: &str) {
+        for line in text.lines() {
+            let line = line.trim();
+            if line.is_empty() || line.starts_with('#') {
+                continue;
+            }
+            let Some((name1, email1, rest)) = name_and_email(line) else {
+                continue;
+            };
+            match name_and_email(rest) {
+                // Proper Name <proper@email> [Commit Name] <commit@email>
+                Some((name2, email2, _)) => {
+                    self.add(email2, name2, name1, Some(email1));
+                }
+                // Proper Name <commit@email>
+                None => {
+                    self.add(email1, None, name1, None);
+                }
+            }
+        }
+    }
+
+    fn add(
+        &mut self,
+        old_email: String,
+        old_name: Option<String>,
+        new_name: Option<String>,
+        new_email: Option<String>,
+    ) {
+        let entry = self.entries.entry(old_email.to_lowercase()).or_default();
+        let repl = match old_name {
+            Some(n) => entry.by_name.entry(n.to_lowercase()).or_default(),
+            None => entry.any_name.get_or_insert_with(Replacement::default),
+        };
+        repl.merge(new_name, new_email);
+    }
+
+    /// Remember one display name per person from raw `(name, email)`
+    /// pairs, newest first: the most recent non-empty name wins. Without
+    /// this, `Alice <a@x.io>` and `alice <A@x.io>` stay two people unless
+    /// the mailmap joins them.
+    pub fn learn_names(&mut self, authors: impl IntoIterator<Item = (String, String)>) {
+        for (name, email) in authors {
+            let id = self.map(&name, &email);
+            let known = self.names.entry(id.email).or_default();
+            if known.is_empty() {
+                *known = id.name;
+            }
+        }
+    }
+
+    /// Resolve a raw `(name, email)` pair to its canonical identity.
+    pub fn resolve(&self, name: &str, email: &str) -> Identity {
+        let mut out = self.map(name, email);
+        if let Some(n) = self.names.get(&out.email).filter(|n| !n.is_empty()) {
+            out.name = n.clone();
+        }
+        out
+    }
+
+    /// The mailmap's own mapping, with the email lower-cased.
+    fn map(&self, name: &str, email: &str) -> Identity {
+        let mut out = Identity {
+            name: name.to_string(),
+            email: email.to_string(),
+        };
+        if let Some(entry) = self.entries.get(&email.to_lowercase()) {
+            let repl = entry
+                .by_name
+                .get(&name.to_lowercase())
+                .or(entry.any_name.as_ref());
+            if let Some(r) = repl {
+                if let Some(n) = &r.name {
+                    out.name = n.clone();
+                }
+                if let Some(e) = &r.email {
+                    out.email = e.clone();
+                }
+            }
+        }
+        out.email = out.email.to_lowercase();
+        out
+    }
+
+    /// Canonical email for an address regardless of the commit name it was
+    /// used with (for look-ups where only the email is known).
+    pub fn canonical_email(&self, email: &str) -> String {
+        self.entries
+            .get(&email.to_lowercase())
+            .and_then(|e| {
+                e.any_name
+                    .iter()
+                    .chain(e.by_name.values())
+                    .find_map(|r| r.email.clone())
+            })
+            .unwrap_or_else(|| email.to_string())
+            .to_lowercase()
+    }
+
+    pub fn resolve_signature(&self, sig: &Signature) -> Identity {
+        self.resolve(
+            sig.name().unwrap_or("unknown"),
+            sig.email().unwrap_or("unknown"),
+        )
+    }
+}
+
+/// How commits with `Co-authored-by:` trailers are credited.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum CoauthorCredit {
+    /// Every author and co-author gets a whole commit.
+    #[default]
+    Full,
+    /// One commit split evenly between author and co-authors.
+    Split,
+    /// Only the commit author counts.
+    Ignore,
+}
+
+impl CoauthorCredit {
+    pub fn parse(s: &str) -> Result<Self> {
+        Ok(match s.to_ascii_lowercase().as_str() {
+            "full" => Self::Full,
+            "split" => Self::Split,
+            "ignore" => Self::Ignore,
+            _ => bail!("unknown co-author credit '{s}' (use full, split or ignore)"),
+        })
+    }
+
+    /// Weight of each of `n` credited people (author included).
+    pub fn weight(self, n: usize) -> f64 {
+        match self {
+            Self::Split if n > 1 => 1.0 / n as f64,
+            _ => 1.0,
+        }
+    }
+}
+
+/// Raw `(name, email)` pairs from `Co-authored-by:` trailers in the final
+/// paragraph of a commit message (key is case-insensitive).
+pub fn coauthor_trailers(message: &str) -> Vec<(String, String)> {
+    let Ok(trailers) = git2::message_trailers_strs(message) else {
+        return Vec::new();
+    };
+    trailers
+        .iter()
+        .filter(|(k, _)| k.eq_ignore_ascii_case("co-authored-by"))
+        .filter_map(|(_, v)| name_and_email(v))
+        .map(|(name, email, _)| (name.unwrap_or_default(), email))
+        .collect()
+}
+
+/// Parse `Name <email>` from the front of `s`; returns (name, email, rest).
+fn name_and_email(s: &str) -> Option<(Option<String>, String, &str)> {
+    let lt = s.find('<')?;
+    let gt = lt + s[lt..].find('>')?;
+    let name = s[..lt].trim();
+    let email = s[lt + 1..gt].trim();
+    Some((
+        (!name.is_empty()).then(|| name.to_string()),
+        email.to_string(),
+        &s[gt + 1..],
+    ))
+}
+
+fn head_blob(repo: &Repository, path: &str) -> Option<String> {
+    let tree = repo.head().ok()?.peel_to_tree().ok()?;
+    let entry = tree.get_path(Path::new(path)).ok()?;
+    let blob = repo.find_blob(entry.id()).ok()?;
+    Some(String::from_utf8_lossy(blob.content()).into_owned())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn one_person_whatever_the_case_of_name_and_email() {
+        let mut mm = Mailmap::default();
+        // Newest first: the current spelling wins.
+        mm.learn_names([
+            ("Alice Smith".to_string(), "Alice@X.com".to_string()),
+            ("alice smith".to_string(), "alice@x.com".to_string()),
+        ]);
+        let a = mm.resolve("alice smith", "alice@x.com");
+        let b = mm.resolve("Alice Smith", "ALICE@x.com");
+        assert_eq!(a, b);
+        assert_eq!(a.to_string(), "Alice Smith <alice@x.com>");
+    }
+
+    #[test]
+    fn mailmap_names_win_over_learned_ones() {
+        let mut mm = Mailmap::default();
+        mm.parse("Alice Smith <alice@x.com> <al@old.org>\n");
+        mm.learn_names([
+            ("al".to_string(), "al@old.org".to_string()),
+            ("alice".to_string(), "alice@x.com".to_string()),
+        ]);
+        // The mapped old address learns the mailmap's name first.
+        assert_eq!(
+            mm.resolve("al", "al@old.org").to_string(),
+            "Alice Smith <alice@x.com>"
+        );
+        assert_eq!(
+            mm.resolve("alice", "alice@x.com").to_string(),
+            "Alice Smith <alice@x.com>"
+        );
+    }
+
+    #[test]
+    fn mailmap_forms() {
+        let mut mm = Mailmap::default();
+        mm.parse(
+            "# comment\n\
+             Proper <proper@x.io>\n\
+             <new@x.io> <old@x.io>\n\
+             Bob <bob@x.io> bobby <B@Old.io>\n",
+        );
+        assert_eq!(mm.resolve("p", "proper@x.io").name, "Proper");
+        assert_eq!(mm.resolve("o", "old@x.io").email, "new@x.io");
+        assert_eq!(
+            mm.resolve("Bobby", "b@old.io").to_string(),
+            "Bob <bob@x.io>"
+        );
+        // Name-specific entries only match that name.
+        assert_eq!(mm.resolve("robert", "b@old.io").email, "b@old.io");
+        assert_eq!(mm.canonical_email("OLD@x.io"), "new@x.io");
+    }
+
+    #[test]
+    fn coauthor_trailers_from_last_paragraph() {
+        let msg = "Fix\n\nBody\n\nCo-authored-by: Ann <ann@x.io>\nco-authored-by: <ben@x.io>\n";
+        assert_eq!(
+            coauthor_trailers(msg),
+            vec![
+                ("Ann".to_string(), "ann@x.io".to_string()),
+                (String::new(), "ben@x.io".to_string()),
+            ]
+        );
+    }
+}
@@ -0,0 +1,269 @@
+use anyhow::Result;
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
+
+use crate::domain::bus_factor::{self, ScanOpts, TouchOwnership};
+use crate::domain::churn::{self, ChurnEntry};
+use crate::domain::engine::{self, Analyser};
+use crate::domain::git::RepoExt;
+use crate::domain::stats::{RepoScanner, RepoSummary};
+
+/// What `export-metrics` measures beyond the global options.
+#[derive(Debug, Clone)]
+pub struct MetricsOpts {
+    /// Churn window when no global `--since` / `--last` is given.
+    pub window_days: i64,
+    /// Ownership from commit touches instead of blame.
+    pub fast: bool,
+    /// Newest commits looked at for touch ownership (`None` = all).
+    pub max_commits: Option<usize>,
+    /// Ownership share above which a file counts as a bus-factor risk.
+    pub threshold: f64,
+    /// Extension filter and minimum size for ownership.
+    pub ownership: ScanOpts,
+    /// Extension filter for churn.
+    pub churn: ScanOpts,
+    /// Files exported with their churn.
+    pub top: usize,
+}
+
+/// Everything exported, gathered in one history walk (plus blame for
+/// ownership unless `fast`).
+#[derive(Debug)]
+pub struct RepoMetrics {
+    pub summary: RepoSummary,
+    /// Files with an ownership score (at least `min_total` lines/touches).
+    pub files_scored: usize,
+    /// Files whose top author owns more than the threshold.
+    pub files_above_threshold: usize,
+    pub threshold: f64,
+    /// The `top` files by churn, highest first.
+    pub churn: Vec<ChurnEntry>,
+    pub churn_window_days: i64,
+}
+
+pub fn collect(repo: &RepoExt, opts: &MetricsOpts) -> Result<RepoMetrics> {
+    let churn_window = churn::churn_window(repo, opts.window_days);
+    let mut scanner = RepoScanner::new(None);
+    let mut churn = churn::Churn::new(churn_window, opts.churn.clone())?;
+    let mut touches = TouchOwnership::new(opts.max_commits, opts.ownership.clone());
+    let mut analysers: Vec<&mut dyn Analyser> = vec![&mut scanner, &mut churn];
+    if opts.fast {
+        analysers.push(&mut touches);
+    }
+    engine::scan(repo, &mut analysers)?;
+
+    let ownership = if opts.fast {
+        touches.into_scores()
+    } else {
+        bus_factor::compute_scores_parallel(repo, &opts.ownership)?
+    };
+    let mut churn = churn.finish();
+    churn.truncate(opts.top);
+    let span = churn_window.until.zip(churn_window.since);
+    Ok(RepoMetrics {
+        summary: scanner.finish().summary,
+        files_scored: ownership.len(),
+        files_above_threshold: ownership
+            .iter()
+            .filter(|s| s.ratio > opts.threshold)
+            .count(),
+        threshold: opts.threshold,
+        churn,
+        churn_window_days: span.map_or(0, |(until, since)| (until - since).num_days()),
+    })
+}
+
+/// One metric and its samples, as in the Prometheus text format.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct Family {
+    pub name: String,
+    pub help: String,
+    /// Always `gauge`: every value describes the selected history as of
+    /// now and may go down (e.g. when the window moves).
+    pub kind: String,
+    pub samples: Vec<Sample>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct Sample {
+    /// Labels beyond the repository-wide ones.
+    pub labels: BTreeMap<String, String>,
+    pub value: f64,
+}
+
+impl RepoMetrics {
+    /// Metric families named `gitrivia_*`, following the Prometheus naming
+    /// conventions (base units, ratios as 0..1).
+    pub fn families(&self) -> Vec<Family> {
+        let s = &self.summary;
+        let mut out = Vec::new();
+        let mut gauge = |name: &str, help: &str, value: f64| {
+            out.push(Family::gauge(name, help, vec![Sample::new(value)]));
+        };
+        gauge(
+            "gitrivia_commits",
+            "Commits in the selected history.",
+            s.total_commits as f64,
+        );
+        gauge(
+            "gitrivia_contributors",
+            "Distinct authors in the selected history.",
+            s.contributors_total as f64,
+        );
+        gauge(
+            "gitrivia_active_authors_90d",
+            "Authors with a commit in the 90 days before the last commit.",
+            s.active_authors_last_90d as f64,
+        );
+        gauge(
+            "gitrivia_core_authors",
+            "Fewest authors covering 80% of the commits.",
+            s.core_size_80pct as f64,
+        );
+        gauge(
+            "gitrivia_drive_by_ratio",
+            "Share of authors with at most two commits.",
+            s.drive_by_ratio / 100.0,
+        );
+        gauge(
+            "gitrivia_author_concentration_hhi",
+            "Herfindahl-Hirschman index of commits per author (0..1).",
+            s.hhi,
+        );
+        gauge(
+            "gitrivia_author_gini",
+            "Gini coefficient of commits per author (0..1).",
+            s.gini,
+        );
+        gauge(
+            "gitrivia_merge_ratio",
+            "Share of commits that are merges.",
+            s.merge_rate / 100.0,
+        );
+        gauge(
+            "gitrivia_revert_ratio",
+            "Share of commits that are reverts.",
+            s.revert_rate / 100.0,
+        );
+        gauge(
+            "gitrivia_momentum_90d_ratio",
+            "Share of commits made in the 90 days before the last commit.",
+            s.momentum_90d_pct / 100.0,
+        );
+        gauge(
+            "gitrivia_longest_idle_gap_days",
+            "Longest gap between two consecutive commits, in days.",
+            s.longest_idle_gap_days as f64,
+        );
+        gauge(
+            "gitrivia_last_commit_timestamp_seconds",
+            "Time of the newest selected commit.",
+            s.last_date.timestamp() as f64,
+        );
+        gauge(
+            "gitrivia_bus_factor_files",
+            "Files with an ownership score (enough lines or touches).",
+            self.files_scored as f64,
+        );
+        out.push(Family::gauge(
+            "gitrivia_bus_factor_files_above_threshold",
+            "Files whose top author owns more than the threshold.",
+            vec![Sample::new(self.files_above_threshold as f64)
+                .label("threshold", self.threshold.to_string())],
+        ));
+
+        let per_file = |f: fn(&ChurnEntry) -> f64| {
+            self.churn
+                .iter()
+                .map(|e| Sample::new(f(e)).label("path", e.path.clone()))
+                .collect()
+        };
+        out.push(Family::gauge(
+            "gitrivia_churn_window_days",
+            "Length of the churn window, in days.",
+            vec![Sample::new(self.churn_window_days as f64)],
+        ));
+        out.push(Family::gauge(
+            "gitrivia_churn",
+            "Decay-weighted lines changed in the churn window (top files).",
+            per_file(|e| e.churn),
+        ));
+        out.push(Family::gauge(
+            "gitrivia_churn_touches",
+            "Commits changing the file in the churn window (top churn files).",
+            per_file(|e| e.touches as f64),
+        ));
+        out
+    }
+}
+
+impl Family {
+    fn gauge(name: &str, help: &str, samples: Vec<Sample>) -> Self {
+        Self {
+            name: name.to_string(),
+            help: help.to_string(),
+            kind: "gauge".into(),
+            samples,
+        }
+    }
+}
+
+impl Sample {
+    fn new(value: f64) -> Self {
+        Self {
+            labels: BTreeMap::new(),
+            value,
+        }
+    }
+
+    fn label(mut self, name: &str, value: String) -> Self {
+        self.labels.insert(name.to_string(), value);
+        self
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::domain::test_repo::TestRepo;
+
+    #[test]
+    fn ratios_are_fractions() {
+        // Ann has three commits, Ben one: half the authors are drive-by.
+        let mut t = TestRepo::new();
+        for (i, who) in [
+            "Ann <a@x.io>",
+            "Ann <a@x.io>",
+            "Ben <b@x.io>",
+            "Ann <a@x.io>",
+        ]
+        .iter()
+        .enumerate()
+        {
+            t.commit(who, "change", &[("a.rs", Some(&format!("{i}\n")))]);
+        }
+        let opts = MetricsOpts {
+            window_days: 90,
+            fast: true,
+            max_commits: None,
+            threshold: 0.5,
+            ownership: ScanOpts::default(),
+            churn: ScanOpts::default(),
+            top: 5,
+        };
+        let families = collect(&t.open(), &opts).unwrap().families();
+        let value = |name: &str| {
+            families
+                .iter()
+                .find(|f| f.name == name)
+                .map(|f| f.samples[0].value)
+                .unwrap()
+        };
+        assert_eq!(value("gitrivia_drive_by_ratio"), 0.5);
+        assert_eq!(value("gitrivia_commits"), 4.0);
+        for f in families.iter().filter(|f| f.name.ends_with("_ratio")) {
+            assert!((0.0..=1.0).contains(&f.samples[0].value), "{}", f.name);
+        }
+    }
+}
@@ -1,9 +1,27 @@
+pub mod age;
 pub mod blame;
+pub mod bots;
 pub mod bus_factor;
+pub mod cache;
 pub mod churn;
 pub mod coauthors;
+pub mod codeowners;
+pub mod compare;
+pub mod engine;
+pub mod explore;
 pub mod files;
 pub mod firsts;
 pub mod git;
+pub mod identity;
+pub mod metrics;
+pub mod paths;
+pub mod report;
+pub mod reviewers;
+pub mod revs;
 pub mod stats;
 pub mod times;
+pub mod trend;
+pub mod window;
+
+#[cfg(test)]
+pub(crate) mod test_repo;
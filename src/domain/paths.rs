@@ -0,0 +1,262 @@
+use anyhow::{Context, Result};
+use git2::{AttrCheckFlags, AttrValue, Repository};
+use globset::{Glob, GlobSet, GlobSetBuilder};
+use std::cell::RefCell;
+use std::collections::HashMap;
+use std::path::Path;
+
+pub const ALLOWED_EXT: &[&str] = &[
+    "rs", "ts", "tsx", "js", "jsx", "java", "kt", "kts", "go", "py", "rb", "swift", "c", "h",
+    "cpp", "hpp", "cc", "hh", "cs", "php", "scala", "m", "mm", "sh", "bash", "zsh", "fish", "sql",
+    "xml", "yml", "yaml", "toml", "json", "lock", "lua", "vim", "conf", "ini", "cfg", "md", "txt",
+];
+
+/// Extension allow-list used by the per-file scanners (bus-factor, churn).
+/// `allowed` replaces `ALLOWED_EXT` when non-empty; `include_ext` adds to
+/// whichever list applies.
+pub fn ext_ok(file: &str, all: bool, allowed: &[String], include_ext: &[String]) -> bool {
+    if all {
+        return true;
+    }
+    let ext = Path::new(file).extension().and_then(|e| e.to_str());
+    match ext {
+        Some(e) => {
+            let e = e.to_ascii_lowercase();
+            let listed = if allowed.is_empty() {
+                ALLOWED_EXT.contains(&e.as_str())
+            } else {
+                allowed.iter().any(|x| x.eq_ignore_ascii_case(&e))
+            };
+            listed || include_ext.iter().any(|x| x.eq_ignore_ascii_case(&e))
+        }
+        None => false,
+    }
+}
+
+/// Global include/exclude patterns.
+///
+/// Each pattern is a git-style pathspec or glob; a leading `!` (or the
+/// pathspec magic `:!` / `:^` / `:(exclude)`) turns it into an exclude.
+/// - `src`            → the `src` directory (prefix match)
+/// - `src/**/*.rs`    → glob anchored at the repo root
+/// - `*.lock`         → no slash: matches the file name at any depth
+/// - `!**/vendor/**`  → exclude
+///
+/// A path passes when it matches at least one include (or there are none)
+/// and no exclude.
+#[derive(Debug, Clone, Default)]
+pub struct PathFilter {
+    include: Option<GlobSet>,
+    exclude: Option<GlobSet>,
+}
+
+impl PathFilter {
+    pub fn new(patterns: &[String]) -> Result<Self> {
+        let mut inc = GlobSetBuilder::new();
+        let mut exc = GlobSetBuilder::new();
+        let (mut n_inc, mut n_exc) = (0, 0);
+        for raw in patterns {
+            let (negated, pat) = split_magic(raw.trim());
+            if pat.is_empty() {
+                continue;
+            }
+            let (builder, n) = if negated {
+                (&mut exc, &mut n_exc)
+            } else {
+                (&mut inc, &mut n_inc)
+            };
+            for g in expand(pat) {
+                builder.add(Glob::new(&g).with_context(|| format!("bad path pattern '{raw}'"))?);
+            }
+            *n += 1;
+        }
+        Ok(Self {
+            include: (n_inc > 0).then(|| inc.build()).transpose()?,
+            exclude: (n_exc > 0).then(|| exc.build()).transpose()?,
+        })
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.include.is_none() && self.exclude.is_none()
+    }
+
+    pub fn matches(&self, path: &str) -> bool {
+        if let Some(inc) = &self.include {
+            if !inc.is_match(path) {
+                return false;
+            }
+        }
+        match &self.exclude {
+            Some(exc) => !exc.is_match(path),
+            None => true,
+        }
+    }
+}
+
+fn split_magic(p: &str) -> (bool, &str) {
+    if let Some(rest) = p.strip_prefix(":(exclude)") {
+        return (true, rest);
+    }
+    if let Some(rest) = p.strip_prefix(":!").or_else(|| p.strip_prefix(":^")) {
+        return (true, rest);
+    }
+    if let Some(rest) = p.strip_prefix('!') {
+        return (true, rest);
+    }
+    (false, p.strip_prefix(":/").unwrap_or(p))
+}
+
+/// Turn one pattern into globs following pathspec/gitignore conventions.
+fn expand(pat: &str) -> Vec<String> {
+    let anchored = pat.starts_with('/');
+    let pat = pat.trim_start_matches('/').trim_end_matches('/');
+    let has_glob = pat.contains(['*', '?', '[']);
+    let base = if !anchored && !pat.contains('/') && has_glob {
+        format!("**/{pat}")
+    } else {
+        pat.to_string()
+    };
+    // Also match everything below, so `src` / `src/*` cover whole trees.
+    vec![base.clone(), format!("{base}/**")]
+}
+
+/// Skips files marked `linguist-generated` or `linguist-vendored` in
+/// `.gitattributes`. Results are cached per path.
+#[derive(Default)]
+pub struct LinguistFilter {
+    cache: RefCell<HashMap<String, bool>>,
+}
+
+impl LinguistFilter {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// True if `path` is neither generated nor vendored.
+    pub fn allows(&self, repo: &Repository, path: &str) -> bool {
+        if let Some(ok) = self.cache.borrow().get(path) {
+            return *ok;
+        }
+        let marked = |name: &str| {
+            repo.get_attr(Path::new(path), name, AttrCheckFlags::INDEX_THEN_FILE)
+                .map(|v| match AttrValue::from_string(v) {
+                    AttrValue::True => true,
+                    AttrValue::String(s) => s == "true",
+                    _ => false,
+                })
+                .unwrap_or(false)
+        };
+        let ok = !marked("linguist-generated") && !marked("linguist-vendored");
+        self.cache.borrow_mut().insert(path.to_string(), ok);
+        ok
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::domain::test_repo::TestRepo;
+
+    fn filter(patterns: &[&str]) -> PathFilter {
+        let patterns: Vec<String> = patterns.iter().map(|p| p.to_string()).collect();
+        PathFilter::new(&patterns).unwrap()
+    }
+
+    fn strings(v: &[&str]) -> Vec<String> {
+        v.iter().map(|s| s.to_string()).collect()
+    }
+
+    #[test]
+    fn extensions_are_case_insensitive_everywhere() {
+        assert!(ext_ok("src/Main.RS", false, &[], &[]));
+        assert!(!ext_ok("logo.svg", false, &[], &[]));
+        assert!(!ext_ok("Makefile", false, &[], &[]));
+        assert!(ext_ok("Makefile", true, &[], &[]));
+        assert!(ext_ok("logo.svg", false, &[], &strings(&["SVG"])));
+        assert!(ext_ok("logo.SVG", false, &[], &strings(&["svg"])));
+        // An explicit list replaces the defaults; `include_ext` still adds.
+        let only_go = strings(&["Go"]);
+        assert!(ext_ok("main.go", false, &only_go, &[]));
+        assert!(!ext_ok("main.rs", false, &only_go, &[]));
+        assert!(ext_ok("main.rs", false, &only_go, &strings(&["RS"])));
+    }
+
+    #[test]
+    fn patterns_expand_like_pathspecs() {
+        assert_eq!(expand("src"), ["src", "src/**"]);
+        assert_eq!(expand("src/"), ["src", "src/**"]);
+        assert_eq!(expand("*.lock"), ["**/*.lock", "**/*.lock/**"]);
+        assert_eq!(expand("/*.lock"), ["*.lock", "*.lock/**"]);
+        assert_eq!(expand("src/*.rs"), ["src/*.rs", "src/*.rs/**"]);
+    }
+
+    #[test]
+    fn magic_prefixes_mark_excludes() {
+        assert_eq!(split_magic("!vendor"), (true, "vendor"));
+        assert_eq!(split_magic(":!vendor"), (true, "vendor"));
+        assert_eq!(split_magic(":^vendor"), (true, "vendor"));
+        assert_eq!(split_magic(":(exclude)vendor"), (true, "vendor"));
+        assert_eq!(split_magic(":/src"), (false, "src"));
+        assert_eq!(split_magic("src"), (false, "src"));
+    }
+
+    #[test]
+    fn a_bare_directory_is_a_prefix() {
+        let f = filter(&["src"]);
+        assert!(f.matches("src/main.rs"));
+        assert!(f.matches("src/a/b/c.rs"));
+        assert!(!f.matches("srcs/main.rs"));
+        assert!(!f.matches("lib/src/main.rs"));
+    }
+
+    #[test]
+    fn a_slashless_glob_matches_at_any_depth() {
+        let f = filter(&["*.lock"]);
+        assert!(f.matches("Cargo.lock"));
+        assert!(f.matches("web/app/yarn.lock"));
+        assert!(!f.matches("Cargo.toml"));
+        let anchored = filter(&["src/*.rs"]);
+        assert!(anchored.matches("src/main.rs"));
+        assert!(!anchored.matches("lib/src/main.rs"));
+    }
+
+    #[test]
+    fn excludes_win_over_includes() {
+        for exclude in ["!src/gen", ":!src/gen", ":^src/gen", ":(exclude)src/gen"] {
+            let f = filter(&["src", exclude]);
+            assert!(f.matches("src/main.rs"), "{exclude}");
+            assert!(!f.matches("src/gen/api.rs"), "{exclude}");
+            assert!(!f.matches("docs/a.md"), "{exclude}: not included");
+        }
+        // Excludes alone keep everything else.
+        let f = filter(&["!**/vendor/**"]);
+        assert!(f.matches("src/main.rs"));
+        assert!(!f.matches("a/vendor/lib.c"));
+        assert!(filter(&["", "  "]).is_empty());
+        assert!(PathFilter::new(&strings(&["src/[a"])).is_err());
+    }
+
+    #[test]
+    fn linguist_attributes_hide_generated_and_vendored_files() {
+        let mut t = TestRepo::new();
+        t.commit(
+            "A <a@x.io>",
+            "attrs",
+            &[
+                (
+                    ".gitattributes",
+                    Some("*.pb.go linguist-generated\nthird_party/** linguist-vendored=true\nkeep.pb.go -linguist-generated\n"),
+                ),
+                ("api.pb.go", Some("x\n")),
+                ("keep.pb.go", Some("x\n")),
+                ("third_party/lib.c", Some("x\n")),
+                ("main.go", Some("x\n")),
+            ],
+        );
+        let l = LinguistFilter::new();
+        assert!(!l.allows(&t.repo, "api.pb.go"));
+        assert!(!l.allows(&t.repo, "third_party/lib.c"));
+        assert!(l.allows(&t.repo, "keep.pb.go"));
+        assert!(l.allows(&t.repo, "main.go"));
+    }
+}
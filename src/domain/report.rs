@@ -0,0 +1,99 @@
+use anyhow::Result;
+use chrono::{DateTime, Local, NaiveDate};
+use std::collections::BTreeMap;
+
+use crate::domain::bots::{BotActivity, BotMode};
+use crate::domain::bus_factor::{self, BusScore, ScanOpts, TouchOwnership};
+use crate::domain::churn::{self, ChurnEntry};
+use crate::domain::coauthors::{Coauthors, SharedFiles, TrailerPairs};
+use crate::domain::engine::{self, Analyser};
+use crate::domain::firsts::FirstCommits;
+use crate::domain::git::RepoExt;
+use crate::domain::stats::{RepoScan, RepoScanner};
+use crate::domain::times::{CommitTimes, CommitsPerMonth};
+use crate::domain::window::TimeWindow;
+
+/// What the `report` dashboard needs beyond the global options.
+#[derive(Debug, Clone)]
+pub struct ReportOpts {
+    /// Churn window when no global `--since` / `--last` is given.
+    pub window_days: i64,
+    /// Ownership from commit touches instead of blame.
+    pub fast: bool,
+    /// Newest commits looked at for touch ownership (`None` = all).
+    pub max_commits: Option<usize>,
+    /// Extension filter and minimum size for ownership.
+    pub ownership: ScanOpts,
+    /// Extension filter for churn.
+    pub churn: ScanOpts,
+}
+
+/// Every analysis shown in the dashboard.
+#[derive(Debug)]
+pub struct Report {
+    pub scan: RepoScan,
+    /// Commits per month, keyed by the month's first day.
+    pub monthly: BTreeMap<NaiveDate, usize>,
+    pub churn: Vec<ChurnEntry>,
+    pub churn_window: TimeWindow,
+    /// File ownership, most dominated first.
+    pub ownership: Vec<BusScore>,
+    pub commit_times: BTreeMap<String, BTreeMap<String, usize>>,
+    pub first_commits: BTreeMap<String, DateTime<Local>>,
+    pub coauthors: Coauthors,
+}
+
+/// Run all history analyses in one walk (plus blame for ownership unless
+/// `fast`).
+pub fn collect(repo: &RepoExt, opts: &ReportOpts) -> Result<Report> {
+    let churn_window = churn::churn_window(repo, opts.window_days);
+
+    let mut scanner = RepoScanner::new(None);
+    let mut monthly = CommitsPerMonth::default();
+    let mut churn = churn::Churn::new(churn_window, opts.churn.clone())?;
+    let mut times = CommitTimes::default();
+    let mut firsts = FirstCommits::default();
+    let mut shared = SharedFiles::default();
+    let mut trailers = TrailerPairs::default();
+    let mut touches = TouchOwnership::new(opts.max_commits, opts.ownership.clone());
+    let mut bots = BotActivity::default();
+
+    let mut analysers: Vec<&mut dyn Analyser> = vec![
+        &mut scanner,
+        &mut monthly,
+        &mut churn,
+        &mut times,
+        &mut firsts,
+        &mut shared,
+        &mut trailers,
+    ];
+    if opts.fast {
+        analysers.push(&mut touches);
+    }
+    if repo.bots().mode() == BotMode::Separate {
+        analysers.push(&mut bots);
+    }
+    engine::scan(repo, &mut analysers)?;
+
+    let ownership = if opts.fast {
+        touches.into_scores()
+    } else {
+        bus_factor::compute_scores_parallel(repo, &opts.ownership)?
+    };
+    let mut scan = scanner.finish();
+    scan.stats.bots = bots.bots;
+
+    Ok(Report {
+        scan,
+        monthly: monthly.counts,
+        churn: churn.finish(),
+        churn_window,
+        ownership,
+        commit_times: times.by_author,
+        first_commits: firsts.firsts,
+        coauthors: Coauthors {
+            shared_files: shared.pairs(),
+            trailers: trailers.pairs,
+        },
+    })
+}
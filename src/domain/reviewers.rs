@@ -0,0 +1,599 @@
+use anyhow::{bail, Context, Result};
+use chrono::{DateTime, Duration, Local};
+use git2::{Delta, Diff, DiffFindOptions, ObjectType, Oid, Patch, RevparseMode};
+use serde::Serialize;
+use std::collections::{BTreeMap, HashMap, HashSet};
+use std::path::Path;
+
+use crate::domain::blame::{self, LineSpan};
+use crate::domain::churn::churn_window;
+use crate::domain::codeowners::email_of;
+use crate::domain::engine::{self, Analyser, CommitCtx};
+use crate::domain::git::{commit_time, RepoExt};
+use crate::domain::identity::coauthor_trailers;
+
+/// What is being reviewed.
+#[derive(Debug, Clone)]
+pub enum ChangeSource {
+    /// `base..head`, `base...head` or a single commit (against its parent).
+    Range(String),
+    /// Files or directories, as they are at the tip.
+    Paths(Vec<String>),
+    /// A unified diff (`git diff` / `git format-patch` output) against the
+    /// tip.
+    Patch(String),
+}
+
+/// Lines of one file a change rewrites, in the base version.
+#[derive(Debug, Clone)]
+struct FileChange {
+    path: String,
+    /// 1-based inclusive ranges (diff hunks with their context); `None`
+    /// for the whole file.
+    lines: Option<Vec<(usize, usize)>>,
+}
+
+/// A change resolved against the repository.
+#[derive(Debug, Clone)]
+pub struct Change {
+    /// Commit the changed lines are blamed at.
+    pub base: Oid,
+    /// Commits of the change itself (range only).
+    pub commits: Vec<Oid>,
+    /// Emails of the people who wrote the change.
+    pub authors: Vec<String>,
+    /// Existing files the change modifies.
+    files: Vec<FileChange>,
+    /// Directories of files the change adds: their history stands in for
+    /// the new files'.
+    new_dirs: Vec<String>,
+}
+
+impl Change {
+    /// Paths reviewed: modified files, then directories of added ones.
+    pub fn paths(&self) -> Vec<String> {
+        let mut p: Vec<String> = self.files.iter().map(|f| f.path.clone()).collect();
+        p.extend(self.new_dirs.iter().map(|d| format!("{d}/")));
+        p
+    }
+}
+
+/// Resolve the change: its base, commits, authors and touched lines.
+pub fn resolve(repo: &RepoExt, source: &ChangeSource) -> Result<Change> {
+    let git = repo.repo();
+    match source {
+        ChangeSource::Range(spec) => {
+            let rs = git
+                .revparse(spec)
+                .with_context(|| format!("bad revision '{spec}'"))?;
+            let (base, head) = if rs.mode().contains(RevparseMode::RANGE) {
+                let (Some(from), Some(to)) = (rs.from(), rs.to()) else {
+                    bail!("bad range '{spec}'");
+                };
+                let (from, to) = (from.peel_to_commit()?, to.peel_to_commit()?);
+                (git.merge_base(from.id(), to.id())?, to)
+            } else {
+                let Some(c) = rs.from() else {
+                    bail!("bad revision '{spec}'");
+                };
+                let c = c.peel_to_commit()?;
+                let parent = c
+                    .parent_id(0)
+                    .with_context(|| format!("{spec} is a root commit; give a range"))?;
+                (parent, c)
+            };
+
+            let mut walk = git.revwalk()?;
+            walk.push(head.id())?;
+            walk.hide(base)?;
+            let commits: Vec<Oid> = walk.collect::<Result<_, _>>()?;
+            if commits.is_empty() {
+                bail!("'{spec}' contains no commits");
+            }
+            let mut authors = Vec::new();
+            for id in &commits {
+                let c = git.find_commit(*id)?;
+                authors.push(repo.author(&c).email);
+                authors.extend(repo.coauthors(&c).into_iter().map(|c| c.email));
+            }
+
+            let base_tree = git.find_commit(base)?.tree()?;
+            let mut diff = git.diff_tree_to_tree(Some(&base_tree), Some(&head.tree()?), None)?;
+            diff.find_similar(Some(DiffFindOptions::new().renames(true)))?;
+            let (files, new_dirs) = hunks(&diff)?;
+            Ok(Change {
+                base,
+                commits,
+                authors,
+                files,
+                new_dirs,
+            })
+        }
+        ChangeSource::Patch(text) => {
+            let diff = Diff::from_buffer(text.as_bytes()).context("not a unified diff")?;
+            let (files, new_dirs) = hunks(&diff)?;
+            if files.is_empty() && new_dirs.is_empty() {
+                bail!("the patch changes no files");
+            }
+            // `git format-patch` headers name the author; trailers name
+            // co-authors.
+            let mut authors: Vec<String> = text
+                .lines()
+                .take_while(|l| !l.starts_with("diff --git") && !l.starts_with("---"))
+                .filter_map(|l| l.strip_prefix("From: "))
+                .filter_map(|l| {
+                    l.rsplit_once('<')
+                        .map(|(_, e)| e.trim_end_matches('>').to_string())
+                })
+                .collect();
+            authors.extend(coauthor_trailers(text).into_iter().map(|(_, e)| e));
+            Ok(Change {
+                base: repo.tip(),
+                commits: Vec::new(),
+                authors,
+                files,
+                new_dirs,
+            })
+        }
+        ChangeSource::Paths(paths) => {
+            let tree = git.find_commit(repo.tip())?.tree()?;
+            let tracked = repo.tracked_files()?;
+            let mut files = Vec::new();
+            let mut new_dirs = Vec::new();
+            for p in paths {
+                let p = p.trim_matches('/');
+                match tree.get_path(Path::new(p)).map(|e| e.kind()) {
+                    Ok(Some(ObjectType::Blob)) => files.push(FileChange {
+                        path: p.to_string(),
+                        lines: None,
+                    }),
+                    Ok(Some(ObjectType::Tree)) => {
+                        let prefix = format!("{p}/");
+                        files.extend(tracked.iter().filter(|f| f.starts_with(&prefix)).map(|f| {
+                            FileChange {
+                                path: f.clone(),
+                                lines: None,
+                            }
+                        }));
+                    }
+                    // Not there yet: a file the change adds.
+                    _ => new_dirs.push(parent(p)),
+                }
+            }
+            Ok(Change {
+                base: repo.tip(),
+                commits: Vec::new(),
+                authors: Vec::new(),
+                files,
+                new_dirs,
+            })
+        }
+    }
+}
+
+/// Directory of a path (`""` for the root).
+fn parent(path: &str) -> String {
+    path.rsplit_once('/')
+        .map_or(String::new(), |(d, _)| d.to_string())
+}
+
+/// Old-side line ranges per modified file, and directories of added files.
+fn hunks(diff: &Diff) -> Result<(Vec<FileChange>, Vec<String>)> {
+    let mut files = Vec::new();
+    let mut new_dirs = Vec::new();
+    for i in 0..diff.deltas().len() {
+        let delta = diff.get_delta(i).context("diff delta")?;
+        if delta.status() == Delta::Added {
+            if let Some(new) = delta.new_file().path().and_then(|p| p.to_str()) {
+                new_dirs.push(parent(new));
+            }
+            continue;
+        }
+        let Some(path) = delta.old_file().path().and_then(|p| p.to_str()) else {
+            continue;
+        };
+        let Some(patch) = Patch::from_diff(diff, i)? else {
+            // Binary: no lines to blame, but still the file's people.
+            files.push(FileChange {
+                path: path.to_string(),
+                lines: None,
+            });
+            continue;
+        };
+        let mut lines = Vec::new();
+        for h in 0..patch.num_hunks() {
+            let (hunk, _) = patch.hunk(h)?;
+            let start = hunk.old_start().max(1) as usize;
+            let len = hunk.old_lines() as usize;
+            if len > 0 {
+                lines.push((start, start + len - 1));
+            } else {
+                // Pure insertion: the line it follows.
+                lines.push((start, start));
+            }
+        }
+        files.push(FileChange {
+            path: path.to_string(),
+            lines: Some(lines),
+        });
+    }
+    new_dirs.sort();
+    new_dirs.dedup();
+    Ok((files, new_dirs))
+}
+
+#[derive(Debug, Clone)]
+pub struct ReviewOpts {
+    /// Recent touches count over this many days (or the global window).
+    pub window_days: i64,
+    /// Authors without a commit in this many days before the tip are not
+    /// suggested.
+    pub inactive_days: i64,
+    /// Weight of line ownership in the score; touches get the rest (0..1).
+    pub blame_weight: f64,
+    /// Emails never suggested, on top of the change's authors.
+    pub exclude: Vec<String>,
+}
+
+/// A ranked candidate reviewer.
+#[derive(Debug, Clone, Serialize)]
+pub struct Reviewer {
+    pub author: String,
+    /// Weighted mix of `line_share` and `touch_share` (0..1).
+    pub score: f64,
+    /// Changed lines (with context) the author last wrote.
+    pub lines: usize,
+    pub line_share: f64,
+    /// Commits to the changed files in the window (co-author credit per
+    /// `--coauthor-credit`).
+    pub touches: f64,
+    pub touch_share: f64,
+    /// Changed files the author has lines in or touched recently.
+    pub files: usize,
+    pub last_commit: Option<DateTime<Local>>,
+}
+
+/// Commits touching the change's paths in the window, and everyone's last
+/// commit; stops once past both windows.
+struct RecentTouches<'a> {
+    files: HashSet<&'a str>,
+    dirs: &'a [String],
+    skip: HashSet<Oid>,
+    since: DateTime<Local>,
+    stop: DateTime<Local>,
+    stopped: bool,
+    /// email → (touches, files touched)
+    touches: HashMap<String, (f64, HashSet<String>)>,
+    /// email → last commit
+    last_seen: HashMap<String, DateTime<Local>>,
+    /// email → `Name <email>` as last credited
+    names: HashMap<String, String>,
+}
+
+impl Analyser for RecentTouches<'_> {
+    fn visit(&mut self, c: &CommitCtx) {
+        if c.time < self.stop {
+            self.stopped = true;
+            return;
+        }
+        if self.skip.contains(&c.commit.id()) {
+            return;
+        }
+        for (who, _) in &c.credits {
+            let email = email_of(who);
+            let last = self.last_seen.entry(email.to_string()).or_insert(c.time);
+            *last = (*last).max(c.time);
+            self.names
+                .entry(email.to_string())
+                .or_insert_with(|| who.clone());
+        }
+        if c.time < self.since {
+            return;
+        }
+        for f in c.changes() {
+            let in_dir = self
+                .dirs
+                .iter()
+                .any(|d| d.is_empty() || f.path.starts_with(&format!("{d}/")));
+            if !self.files.contains(f.path.as_str()) && !in_dir {
+                continue;
+            }
+            for (who, w) in &c.credits {
+                let e = self.touches.entry(email_of(who).to_string()).or_default();
+                e.0 += w;
+                e.1.insert(f.path.clone());
+            }
+        }
+    }
+
+    fn done(&self) -> bool {
+        self.stopped
+    }
+}
+
+/// Rank reviewers for `change`: owners of the lines it rewrites and people
+/// who recently changed the same files, minus its authors, bots and
+/// inactive contributors.
+pub fn suggest(repo: &RepoExt, change: &Change, opts: &ReviewOpts) -> Result<Vec<Reviewer>> {
+    let git = repo.repo();
+    let bots = repo.bots();
+
+    // People are keyed by canonical email: blame at a base outside the
+    // selected history can spell a name differently than the walk below.
+    // email → (lines, files)
+    let mut blamed: HashMap<String, (usize, HashSet<&str>)> = HashMap::new();
+    let mut names: HashMap<String, String> = HashMap::new();
+    for f in &change.files {
+        let span = f.lines.as_ref().map(|ranges| {
+            let start = ranges.iter().map(|r| r.0).min().unwrap_or(1);
+            let end = ranges.iter().map(|r| r.1).max().unwrap_or(start);
+            LineSpan { start, end }
+        });
+        // Files the base doesn't have (e.g. a patch against another
+        // branch) simply have no owners.
+        let Ok(hunks) = blame::hunks(
+            git,
+            change.base,
+            Path::new(&f.path),
+            span,
+            repo.blame_tuning(),
+        ) else {
+            continue;
+        };
+        let mut add = |sig: &git2::Signature, n: usize| {
+            let id = repo.identity(sig);
+            if bots.is_bot(&id) || n == 0 {
+                return;
+            }
+            let e = blamed.entry(id.email.clone()).or_default();
+            e.0 += n;
+            e.1.insert(&f.path);
+            names
+                .entry(id.email.clone())
+                .or_insert_with(|| id.to_string());
+        };
+        for h in &hunks {
+            let n = match &f.lines {
+                None => h.lines,
+                // Lines of the hunk inside the changed ranges.
+                Some(ranges) => ranges
+                    .iter()
+                    .map(|&(lo, hi)| {
+                        (h.start + h.lines)
+                            .min(hi + 1)
+                            .saturating_sub(h.start.max(lo))
+                    })
+                    .sum(),
+            };
+            add(&h.signature, n);
+        }
+    }
+
+    let tip_time = commit_time(&git.find_commit(repo.tip())?);
+    let window = churn_window(repo, opts.window_days);
+    let since = window.since.unwrap_or(tip_time);
+    let active_since = tip_time - Duration::days(opts.inactive_days.max(0));
+    let mut recent = RecentTouches {
+        files: change.files.iter().map(|f| f.path.as_str()).collect(),
+        dirs: &change.new_dirs,
+        skip: change.commits.iter().copied().collect(),
+        since,
+        stop: since.min(active_since),
+        stopped: false,
+        touches: HashMap::new(),
+        last_seen: HashMap::new(),
+        names: HashMap::new(),
+    };
+    engine::scan(repo, &mut [&mut recent])?;
+    // Names from history are the newest spelling; blame's fill the gaps.
+    names.extend(recent.names.drain());
+
+    let excluded: HashSet<String> = change
+        .authors
+        .iter()
+        .chain(&opts.exclude)
+        .map(|e| repo.mailmap().canonical_email(e).to_lowercase())
+        .collect();
+    let total_lines: usize = blamed.values().map(|(n, _)| n).sum();
+    let total_touches: f64 = recent.touches.values().map(|(n, _)| n).sum();
+
+    let mut people: BTreeMap<&str, ()> = BTreeMap::new();
+    people.extend(blamed.keys().map(|a| (a.as_str(), ())));
+    people.extend(recent.touches.keys().map(|a| (a.as_str(), ())));
+    let mut out: Vec<Reviewer> = people
+        .into_keys()
+        .filter(|a| !excluded.contains(*a))
+        .filter_map(|a| {
+            let last = recent.last_seen.get(a).copied();
+            // Nobody who hasn't committed lately.
+            if opts.inactive_days > 0 && last.is_none_or(|l| l < active_since) {
+                return None;
+            }
+            let (lines, blamed_files) = blamed.get(a).cloned().unwrap_or_default();
+            let (touches, touched_files) = recent.touches.get(a).cloned().unwrap_or_default();
+            let line_share = ratio(lines as f64, total_lines as f64);
+            let touch_share = ratio(touches, total_touches);
+            let mut files: HashSet<&str> = blamed_files;
+            files.extend(touched_files.iter().map(String::as_str));
+            Some(Reviewer {
+                author: names[a].clone(),
+                score: opts.blame_weight * line_share + (1.0 - opts.blame_weight) * touch_share,
+                lines,
+                line_share,
+                touches,
+                touch_share,
+                files: files.len(),
+                last_commit: last,
+            })
+        })
+        .filter(|r| r.score > 0.0)
+        .collect();
+    out.sort_by(|a, b| {
+        b.score
+            .total_cmp(&a.score)
+            .then_with(|| a.author.cmp(&b.author))
+    });
+    Ok(out)
+}
+
+fn ratio(n: f64, total: f64) -> f64 {
+    if total > 0.0 {
+        n / total
+    } else {
+        0.0
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::domain::test_repo::TestRepo;
+
+    #[test]
+    fn one_reviewer_per_email_whatever_blame_spells() {
+        // The change sits on a branch outside the selected history, so the
+        // names blamed at its base are never learned from the walk.
+        let mut t = TestRepo::new();
+        let main = t.commit("Ben <ben@x.io>", "b", &[("b.rs", Some("b\n"))]);
+        t.commit("ANN <ann@x.io>", "a", &[("a.rs", Some("1\n2\n"))]);
+        t.commit("ann <Ann@x.io>", "a", &[("a.rs", Some("1\n2\n3\n4\n"))]);
+        let change = t.commit("Cy <cy@x.io>", "c", &[("a.rs", Some("1\nx\n3\n4\n"))]);
+        let branch = t.repo.head().unwrap().name().unwrap().to_string();
+        t.repo.reference(&branch, main, true, "reset").unwrap();
+
+        let repo = t.open();
+        let change = resolve(&repo, &ChangeSource::Range(change.to_string())).unwrap();
+        let opts = ReviewOpts {
+            window_days: 90,
+            inactive_days: 0,
+            blame_weight: 0.5,
+            exclude: Vec::new(),
+        };
+        let reviewers = suggest(&repo, &change, &opts).unwrap();
+        assert_eq!(reviewers.len(), 1);
+        let r = &reviewers[0];
+        assert_eq!(email_of(&r.author), "ann@x.io");
+        assert_eq!((r.lines, r.line_share, r.files), (4, 1.0, 1));
+    }
+
+    /// Old wrote `a.rs` long ago; Ann and Cat share `src/a.rs`, Ben and
+    /// Cat `src/sub/c.rs`, and Dan has `lib/`. The last commit, by Ann
+    /// with Ben as co-author, edits both `src/` files.
+    fn history() -> (TestRepo, Oid) {
+        let mut t = TestRepo::new();
+        t.commit("Old <old@x.io>", "a", &[("a.rs", Some("1\n2\n3\n4\n"))]);
+        t.commit("Ann <ann@x.io>", "a", &[("src/a.rs", Some("1\n2\n"))]);
+        t.commit("Ben <ben@x.io>", "c", &[("src/sub/c.rs", Some("1\n"))]);
+        t.commit("Dan <dan@x.io>", "d", &[("lib/old.rs", Some("1\n"))]);
+        t.commit("Cat <cat@x.io>", "a", &[("src/a.rs", Some("1\n2\n3\n"))]);
+        t.commit("Cat <cat@x.io>", "c", &[("src/sub/c.rs", Some("1\n2\n"))]);
+        let change = t.commit(
+            "Ann <ann@x.io>",
+            "change\n\nCo-authored-by: Ben <ben@x.io>",
+            &[
+                ("src/a.rs", Some("x\n2\n3\n")),
+                ("src/sub/c.rs", Some("1\ny\n")),
+            ],
+        );
+        (t, change)
+    }
+
+    fn opts(inactive_days: i64) -> ReviewOpts {
+        ReviewOpts {
+            window_days: 100_000,
+            inactive_days,
+            blame_weight: 0.5,
+            exclude: Vec::new(),
+        }
+    }
+
+    fn emails(reviewers: &[Reviewer]) -> Vec<&str> {
+        reviewers.iter().map(|r| email_of(&r.author)).collect()
+    }
+
+    #[test]
+    fn the_change_authors_and_coauthors_are_not_suggested() {
+        let (t, change) = history();
+        let repo = t.open();
+        let change = resolve(&repo, &ChangeSource::Range(change.to_string())).unwrap();
+        assert_eq!(change.authors, ["ann@x.io", "ben@x.io"]);
+        assert_eq!(change.paths(), ["src/a.rs", "src/sub/c.rs"]);
+
+        let reviewers = suggest(&repo, &change, &opts(0)).unwrap();
+        assert_eq!(emails(&reviewers), ["cat@x.io"]);
+        let excluded = ReviewOpts {
+            exclude: vec!["CAT@x.io".into()],
+            ..opts(0)
+        };
+        assert!(suggest(&repo, &change, &excluded).unwrap().is_empty());
+    }
+
+    #[test]
+    fn inactive_owners_are_not_suggested() {
+        let (t, _) = history();
+        let repo = t.open();
+        let change = resolve(&repo, &ChangeSource::Paths(vec!["a.rs".into()])).unwrap();
+        let reviewers = suggest(&repo, &change, &opts(0)).unwrap();
+        assert_eq!(emails(&reviewers), ["old@x.io"]);
+        assert_eq!(reviewers[0].lines, 4);
+        // Old's only commit is six days before the tip.
+        assert_eq!(suggest(&repo, &change, &opts(7)).unwrap().len(), 1);
+        assert!(suggest(&repo, &change, &opts(5)).unwrap().is_empty());
+    }
+
+    #[test]
+    fn paths_expand_directories_and_new_files_use_their_directory() {
+        let (t, _) = history();
+        let repo = t.open();
+        let paths = ["src/", "a.rs", "lib/new.rs", "top.rs"].map(String::from);
+        let change = resolve(&repo, &ChangeSource::Paths(paths.to_vec())).unwrap();
+        assert_eq!(
+            change.paths(),
+            ["src/a.rs", "src/sub/c.rs", "a.rs", "lib/", "/"]
+        );
+        assert!(change.authors.is_empty());
+
+        // A file that doesn't exist yet goes to whoever works next to it.
+        let new = ChangeSource::Paths(vec!["lib/new.rs".into()]);
+        let change = resolve(&repo, &new).unwrap();
+        let reviewers = suggest(&repo, &change, &opts(0)).unwrap();
+        assert_eq!(emails(&reviewers), ["dan@x.io"]);
+        assert_eq!((reviewers[0].lines, reviewers[0].touches), (0, 1.0));
+    }
+
+    #[test]
+    fn patches_name_their_author_and_coauthors() {
+        let (t, _) = history();
+        let repo = t.open();
+        let patch = "From 0000000000000000000000000000000000000000 Mon Sep 17 00:00:00 2001\n\
+                     From: Ann <ANN@x.io>\n\
+                     Subject: [PATCH] tweak\n\
+                     \n\
+                     Co-authored-by: Ben <ben@x.io>\n\
+                     ---\n\
+                     diff --git a/src/sub/c.rs b/src/sub/c.rs\n\
+                     index 1111111..2222222 100644\n\
+                     --- a/src/sub/c.rs\n\
+                     +++ b/src/sub/c.rs\n\
+                     @@ -1,2 +1,2 @@\n\
+                     -1\n\
+                     +one\n \
+                     y\n\
+                     diff --git a/lib/n.rs b/lib/n.rs\n\
+                     new file mode 100644\n\
+                     index 0000000..3333333\n\
+                     --- /dev/null\n\
+                     +++ b/lib/n.rs\n\
+                     @@ -0,0 +1 @@\n\
+                     +n\n";
+        let change = resolve(&repo, &ChangeSource::Patch(patch.into())).unwrap();
+        assert_eq!(change.authors, ["ANN@x.io", "ben@x.io"]);
+        assert_eq!(change.paths(), ["src/sub/c.rs", "lib/"]);
+        assert_eq!(change.base, repo.tip());
+
+        let reviewers = suggest(&repo, &change, &opts(0)).unwrap();
+        // Ben and Ann own the edited lines but wrote the patch.
+        assert_eq!(emails(&reviewers), ["cat@x.io", "dan@x.io"]);
+        assert!(resolve(&repo, &ChangeSource::Patch("hello".into())).is_err());
+    }
+}
@@ -0,0 +1,318 @@
+use anyhow::{bail, Context, Result};
+use git2::{Oid, Repository, RevparseMode, Revwalk};
+use globset::{Glob, GlobSet, GlobSetBuilder};
+
+/// Which commits to analyse (mirrors `git rev-list` selection).
+#[derive(Debug, Clone, Default)]
+pub struct RevSelection {
+    /// Revisions or ranges: `v1.2`, `main..feature`, `a...b`, `^old`.
+    pub revs: Vec<String>,
+    /// Every ref under `refs/` (like `git log --all`).
+    pub all_refs: bool,
+    /// Branches matching this glob (`*` = all local branches).
+    pub branches: Option<String>,
+    /// Ref globs to skip when expanding `all_refs` / `branches`.
+    pub exclude: Vec<String>,
+}
+
+impl RevSelection {
+    fn is_default(&self) -> bool {
+        self.revs.is_empty() && !self.all_refs && self.branches.is_none()
+    }
+}
+
+/// A selection resolved to commit ids once, at open time, so bad specs
+/// fail early with a clear message.
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub struct ResolvedRevs {
+    push: Vec<Oid>,
+    hide: Vec<Oid>,
+    /// Commit used for blame and file listings.
+    pub tip: Oid,
+}
+
+impl ResolvedRevs {
+    pub fn resolve(repo: &Repository, sel: &RevSelection) -> Result<Self> {
+        let head = || -> Result<Oid> {
+            Ok(repo
+                .head()
+                .context("cannot resolve HEAD")?
+                .peel_to_commit()?
+                .id())
+        };
+
+        if sel.is_default() {
+            let tip = head()?;
+            return Ok(Self {
+                push: vec![tip],
+                hide: Vec::new(),
+                tip,
+            });
+        }
+
+        let mut push = Vec::new();
+        let mut hide = Vec::new();
+        let mut tip = None;
+
+        for spec in &sel.revs {
+            if let Some(neg) = spec.strip_prefix('^') {
+                hide.push(peel(repo, neg)?);
+                continue;
+            }
+            let rs = repo
+                .revparse(spec)
+                .with_context(|| format!("bad revision '{spec}'"))?;
+            let mode = rs.mode();
+            let to = rs.to().map(|o| o.peel_to_commit()).transpose()?;
+            let from = rs.from().map(|o| o.peel_to_commit()).transpose()?;
+            if mode.contains(RevparseMode::RANGE) {
+                let (Some(from), Some(to)) = (from, to) else {
+                    bail!("bad range '{spec}'");
+                };
+                if mode.contains(RevparseMode::MERGE_BASE) {
+                    hide.push(repo.merge_base(from.id(), to.id())?);
+                    push.push(from.id());
+                } else {
+                    hide.push(from.id());
+                }
+                push.push(to.id());
+                tip.get_or_insert(to.id());
+            } else if let Some(c) = from {
+                push.push(c.id());
+                tip.get_or_insert(c.id());
+            }
+        }
+
+        if sel.all_refs {
+            if let Ok(h) = head() {
+                push.push(h);
+            }
+        }
+        if sel.all_refs || sel.branches.is_some() {
+            let exclude = globs(&sel.exclude)?;
+            let include = match &sel.branches {
+                Some(b) if !sel.all_refs => Some(Glob::new(&format!("refs/heads/{b}"))?),
+                _ => None,
+            }
+            .map(|g| g.compile_matcher());
+            for r in repo.references()?.flatten() {
+                let Some(name) = r.name() else { continue };
+                let short = r.shorthand().unwrap_or(name);
+                if let Some(inc) = &include {
+                    if !inc.is_match(name) {
+                        continue;
+                    }
+                } else if !name.starts_with("refs/") {
+                    continue;
+                }
+                if exclude.is_match(name) || exclude.is_match(short) {
+                    continue;
+                }
+                // Skip refs that do not point at commits (e.g. tagged trees).
+                if let Ok(c) = r.peel_to_commit() {
+                    push.push(c.id());
+                }
+            }
+        }
+
+        if push.is_empty() {
+            bail!("revision selection matches no commits");
+        }
+        let tip = match tip {
+            Some(t) => t,
+            None => head().unwrap_or(push[0]),
+        };
+        Ok(Self { push, hide, tip })
+    }
+
+    /// Push only the selected tips: everything they reach, including
+    /// history a range hides (blame can land there).
+    pub fn apply_reachable(&self, walk: &mut Revwalk) -> Result<()> {
+        for id in &self.push {
+            walk.push(*id)?;
+        }
+        Ok(())
+    }
+
+    pub fn apply(&self, walk: &mut Revwalk) -> Result<()> {
+        for id in &self.push {
+            walk.push(*id)?;
+        }
+        for id in &self.hide {
+            walk.hide(*id)?;
+        }
+        Ok(())
+    }
+}
+
+fn peel(repo: &Repository, spec: &str) -> Result<Oid> {
+    Ok(repo
+        .revparse_single(spec)
+        .with_context(|| format!("bad revision '{spec}'"))?
+        .peel_to_commit()?
+        .id())
+}
+
+fn globs(patterns: &[String]) -> Result<GlobSet> {
+    let mut b = GlobSetBuilder::new();
+    for p in patterns {
+        b.add(Glob::new(p).with_context(|| format!("bad glob '{p}'"))?);
+    }
+    Ok(b.build()?)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::domain::test_repo::TestRepo;
+
+    /// `base` has two children: `m1` on the default branch and `f1` on
+    /// `feature`; `w1` sits on `wip/one` after `m1`, and tag `v1` marks
+    /// `base`.
+    struct Fixture {
+        t: TestRepo,
+        main: String,
+        m1: Oid,
+        f1: Oid,
+        w1: Oid,
+    }
+
+    fn fixture() -> Fixture {
+        let mut t = TestRepo::new();
+        let base = t.commit("A <a@x.io>", "base", &[("a", Some("1\n"))]);
+        let head = t.repo.head().unwrap().name().unwrap().to_string();
+        let main = head.trim_start_matches("refs/heads/").to_string();
+        let m1 = t.commit("A <a@x.io>", "m1", &[("m", Some("1\n"))]);
+        for (branch, from) in [("feature", base), ("wip/one", m1)] {
+            let name = format!("refs/heads/{branch}");
+            t.repo.reference(&name, from, true, "branch").unwrap();
+        }
+        t.repo.set_head("refs/heads/feature").unwrap();
+        let f1 = t.commit("A <a@x.io>", "f1", &[("f", Some("1\n"))]);
+        t.repo.set_head("refs/heads/wip/one").unwrap();
+        let w1 = t.commit("A <a@x.io>", "w1", &[("w", Some("1\n"))]);
+        t.repo.set_head(&head).unwrap();
+        t.repo.reference("refs/tags/v1", base, true, "tag").unwrap();
+        Fixture {
+            t,
+            main,
+            m1,
+            f1,
+            w1,
+        }
+    }
+
+    fn revs(specs: &[&str]) -> RevSelection {
+        RevSelection {
+            revs: specs.iter().map(|s| s.to_string()).collect(),
+            ..RevSelection::default()
+        }
+    }
+
+    /// Resolve `sel` and return the selected commits' messages, sorted,
+    /// with the tip.
+    fn select(f: &Fixture, sel: &RevSelection) -> (Vec<String>, Oid) {
+        let resolved = ResolvedRevs::resolve(&f.t.repo, sel).unwrap();
+        let mut walk = f.t.repo.revwalk().unwrap();
+        resolved.apply(&mut walk).unwrap();
+        let mut messages: Vec<_> = walk
+            .map(|id| {
+                let c = f.t.repo.find_commit(id.unwrap()).unwrap();
+                c.summary().unwrap().to_string()
+            })
+            .collect();
+        messages.sort();
+        (messages, resolved.tip)
+    }
+
+    #[test]
+    fn the_default_is_everything_behind_head() {
+        let f = fixture();
+        assert_eq!(
+            select(&f, &RevSelection::default()),
+            (vec!["base".into(), "m1".into()], f.m1)
+        );
+    }
+
+    #[test]
+    fn ranges_hide_the_other_side() {
+        let f = fixture();
+        let two = format!("{}..feature", f.main);
+        assert_eq!(select(&f, &revs(&[&two])), (vec!["f1".into()], f.f1));
+        // The symmetric difference hides only the merge base.
+        let three = format!("{}...feature", f.main);
+        let (messages, _) = select(&f, &revs(&[&three]));
+        assert_eq!(messages, ["f1", "m1"]);
+        let negated = format!("^{}", f.main);
+        assert_eq!(
+            select(&f, &revs(&["feature", &negated])),
+            (vec!["f1".into()], f.f1)
+        );
+    }
+
+    #[test]
+    fn several_revisions_union_and_the_first_is_the_tip() {
+        let f = fixture();
+        let (messages, tip) = select(&f, &revs(&["feature", &f.main]));
+        assert_eq!(
+            (messages, tip),
+            (vec!["base".into(), "f1".into(), "m1".into()], f.f1)
+        );
+        let (_, tip) = select(&f, &revs(&["^feature", "wip/one", "feature"]));
+        assert_eq!(tip, f.w1);
+    }
+
+    #[test]
+    fn ref_expansions_honour_exclude() {
+        let f = fixture();
+        let all = RevSelection {
+            all_refs: true,
+            ..RevSelection::default()
+        };
+        let (messages, tip) = select(&f, &all);
+        assert_eq!(messages, ["base", "f1", "m1", "w1"]);
+        assert_eq!(tip, f.m1, "HEAD is the tip when no revision is given");
+
+        let wip = RevSelection {
+            branches: Some("wip/*".into()),
+            ..RevSelection::default()
+        };
+        assert_eq!(select(&f, &wip).0, ["base", "m1", "w1"]);
+
+        let without_wip = RevSelection {
+            all_refs: true,
+            exclude: vec!["refs/heads/wip/*".into()],
+            ..RevSelection::default()
+        };
+        assert_eq!(select(&f, &without_wip).0, ["base", "f1", "m1"]);
+        let without_feature = RevSelection {
+            branches: Some("*".into()),
+            exclude: vec!["feature".into()],
+            ..RevSelection::default()
+        };
+        assert_eq!(select(&f, &without_feature).0, ["base", "m1", "w1"]);
+
+        // Explicit revisions are never excluded.
+        let explicit = RevSelection {
+            exclude: vec!["wip/*".into()],
+            ..revs(&["wip/one"])
+        };
+        assert_eq!(
+            select(&f, &explicit),
+            (vec!["base".into(), "m1".into(), "w1".into()], f.w1)
+        );
+    }
+
+    #[test]
+    fn bad_specs_fail_early() {
+        let f = fixture();
+        assert!(ResolvedRevs::resolve(&f.t.repo, &revs(&["nope"])).is_err());
+        assert!(ResolvedRevs::resolve(&f.t.repo, &revs(&["^nope"])).is_err());
+        let none = RevSelection {
+            branches: Some("nope/*".into()),
+            ..RevSelection::default()
+        };
+        let err = ResolvedRevs::resolve(&f.t.repo, &none).unwrap_err();
+        assert!(err.to_string().contains("matches no commits"));
+    }
+}
@@ -1,45 +1,84 @@
-use chrono::{DateTime, Datelike, Duration, Local, NaiveDate, TimeZone, Timelike, Weekday};
-use git2::{Repository, Sort};
+use anyhow::Result;
+use chrono::{DateTime, Datelike, Duration, Local, NaiveDate, Timelike, Weekday};
+use serde::{Deserialize, Serialize};
 use std::collections::{BTreeMap, HashMap, HashSet};
 
-#[derive(Debug, Clone)]
+use crate::domain::bots::{BotActivity, BotMeta, BotMode};
+use crate::domain::engine::{self, Analyser, CommitCtx};
+use crate::domain::git::RepoExt;
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct AuthorMeta {
-    pub count: usize,
+    /// Commit credit: 1 per authored commit, plus co-authored commits per
+    /// `--coauthor-credit` (fractional with `split`).
+    pub count: f64,
     pub first: DateTime<Local>,
     pub last: DateTime<Local>,
 }
 
-#[derive(Debug, Clone)]
+#[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct CommitStats {
     pub total_seen: usize,
     pub data: HashMap<String, AuthorMeta>,
+    /// Bot activity kept out of `data` (only with `--bots separate`).
+    pub bots: BTreeMap<String, BotMeta>,
 }
 
 impl CommitStats {
-    pub fn formatted_lines(&self, desc: bool) -> Vec<String> {
-        use crate::utils::fmt_date;
-        let mut v: Vec<_> = self
-            .data
+    /// Look up an author by email or full `Name <email>` key.
+    pub fn find(&self, author: &str) -> Option<(&String, &AuthorMeta)> {
+        let suffix = format!("<{}>", author.to_lowercase());
+        self.data
             .iter()
+            .find(|(k, _)| *k == author || k.to_lowercase().ends_with(&suffix))
+    }
+
+    /// Authors ordered by commit count (ties by name).
+    pub fn sorted(&self, desc: bool) -> Vec<(&String, &AuthorMeta)> {
+        let mut entries: Vec<_> = self.data.iter().collect();
+        entries.sort_by(|a, b| {
+            let by_count = if desc {
+                b.1.count.total_cmp(&a.1.count)
+            } else {
+                a.1.count.total_cmp(&b.1.count)
+            };
+            by_count.then_with(|| a.0.cmp(b.0))
+        });
+        entries
+    }
+
+    pub fn formatted_lines(&self, desc: bool) -> Vec<String> {
+        use crate::utils::{fmt_credit, fmt_date};
+        self.sorted(desc)
+            .into_iter()
             .map(|(email, m)| {
-                (
-                    m.count,
-                    format!(
-                        "{:<30} {:>4} commits   🗓  {} → {}",
-                        email,
-                        m.count,
-                        fmt_date(m.first),
-                        fmt_date(m.last)
-                    ),
+                format!(
+                    "{:<30} {:>4} commits   🗓  {} → {}",
+                    email,
+                    fmt_credit(m.count),
+                    fmt_date(m.first),
+                    fmt_date(m.last)
                 )
             })
-            .collect();
-        v.sort_by(|a, b| if desc { b.0.cmp(&a.0) } else { a.0.cmp(&b.0) });
-        v.into_iter().map(|(_, s)| s).collect()
+            .collect()
     }
 }
 
-#[derive(Debug, Clone)]
+/// Busiest calendar day.
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+pub struct PeakDay {
+    pub date: NaiveDate,
+    pub commits: usize,
+}
+
+/// Most active author of the last 30 days before the newest commit.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct RecentTop {
+    pub author: String,
+    pub commits: f64,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct RepoSummary {
     pub total_commits: usize,
     pub contributors_total: usize,
@@ -49,9 +88,9 @@ pub struct RepoSummary {
     pub last_date: DateTime<Local>,
     pub active_days: i64,
     pub avg_commits_per_day: f64,
-    pub peak_day: Option<(NaiveDate, usize)>,
+    pub peak_day: Option<PeakDay>,
     pub active_authors_last_90d: usize,
-    pub top_recent_30d: Option<(String, usize)>,
+    pub top_recent_30d: Option<RecentTop>,
 
     // New insights:
     pub drive_by_ratio: f64,        // authors with ≤2 commits / total authors
@@ -69,176 +108,163 @@ pub struct RepoSummary {
     pub momentum_90d_pct: f64,      // commits in last 90d / total (%)
 }
 
-#[derive(Debug, Clone)]
+#[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct RepoScan {
     pub stats: CommitStats,
     pub summary: RepoSummary,
     pub recent12: CommitStats,
 }
 
-/// Count all commits.
-pub fn count_all_commits(repo: &Repository) -> usize {
-    let mut rw = repo.revwalk().expect("revwalk");
-    rw.push_head().unwrap();
-    rw.set_sorting(Sort::TIME).unwrap();
-    rw.count()
+/// Count all commits (inside the time window).
+pub fn count_all_commits(repo: &RepoExt) -> Result<usize> {
+    Ok(repo.commits()?.count())
 }
 
-/// Old API: per-author stats (kept for other commands)
-pub fn collect_commits(
-    repo: &Repository,
-    limit: usize,
-    since: Option<DateTime<Local>>,
-) -> CommitStats {
-    let mut rw = repo.revwalk().expect("revwalk");
-    rw.push_head().unwrap();
-    rw.set_sorting(Sort::TIME).unwrap();
-
-    let mut data = HashMap::<String, AuthorMeta>::new();
-    let mut seen = 0usize;
-
-    for id in rw.flatten() {
-        if seen >= limit {
-            break;
-        }
-        let commit = match repo.find_commit(id) {
-            Ok(c) => c,
-            Err(_) => continue,
-        };
-        let email = commit
-            .author()
-            .email()
-            .unwrap_or("unknown@example.com")
-            .to_string();
-        let dt = Local
-            .timestamp_opt(commit.time().seconds(), 0)
-            .single()
-            .unwrap();
-
-        if let Some(min) = since {
-            if dt < min {
-                continue;
-            }
+/// Per-author commit counts and first/last dates.
+#[derive(Debug)]
+pub struct AuthorCounter {
+    limit: Option<usize>,
+    seen: usize,
+    data: HashMap<String, AuthorMeta>,
+}
+
+impl AuthorCounter {
+    pub fn new(limit: Option<usize>) -> Self {
+        Self {
+            limit,
+            seen: 0,
+            data: HashMap::new(),
         }
+    }
 
-        seen += 1;
-        let e = data.entry(email).or_insert(AuthorMeta {
-            count: 0,
-            first: dt,
-            last: dt,
-        });
-        e.count += 1;
-        if dt < e.first {
-            e.first = dt;
+    pub fn finish(self) -> CommitStats {
+        CommitStats {
+            total_seen: self.seen,
+            data: self.data,
+            bots: BTreeMap::new(),
         }
-        if dt > e.last {
-            e.last = dt;
+    }
+}
+
+impl Analyser for AuthorCounter {
+    fn visit(&mut self, c: &CommitCtx) {
+        self.seen += 1;
+        for (who, w) in &c.credits {
+            record(&mut self.data, who, c.time, *w);
         }
     }
-    CommitStats {
-        total_seen: seen,
-        data,
+
+    fn done(&self) -> bool {
+        self.limit.is_some_and(|m| self.seen >= m)
     }
 }
 
-/// New single-pass scanner computing author stats + rich repo summary.
-pub fn scan_repo(repo: &Repository, limit: Option<usize>) -> RepoScan {
-    let mut rw = repo.revwalk().expect("revwalk");
-    rw.push_head().unwrap();
-    rw.set_sorting(Sort::TIME).unwrap();
+fn record(data: &mut HashMap<String, AuthorMeta>, author: &str, dt: DateTime<Local>, w: f64) {
+    let e = data.entry(author.to_string()).or_insert(AuthorMeta {
+        count: 0.0,
+        first: dt,
+        last: dt,
+    });
+    e.count += w;
+    if dt < e.first {
+        e.first = dt;
+    }
+    if dt > e.last {
+        e.last = dt;
+    }
+}
+
+/// Old API: per-author stats (kept for other commands)
+pub fn collect_commits(repo: &RepoExt, limit: usize) -> Result<CommitStats> {
+    let mut counter = AuthorCounter::new(Some(limit));
+    let bots = scan_with_bots(repo, &mut counter)?;
+    Ok(CommitStats {
+        bots,
+        ..counter.finish()
+    })
+}
+
+/// Run `a`, also collecting bot activity when bots are reported separately.
+fn scan_with_bots(repo: &RepoExt, a: &mut dyn Analyser) -> Result<BTreeMap<String, BotMeta>> {
+    let mut bots = BotActivity::default();
+    if repo.bots().mode() == BotMode::Separate {
+        engine::scan(repo, &mut [a, &mut bots])?;
+    } else {
+        engine::scan(repo, &mut [a])?;
+    }
+    Ok(bots.bots)
+}
 
-    let mut data = HashMap::<String, AuthorMeta>::new();
-    let mut seen = 0usize;
+/// Single-pass scanner computing author stats + rich repo summary.
+#[derive(Debug, Default)]
+pub struct RepoScanner {
+    limit: Option<usize>,
+    data: HashMap<String, AuthorMeta>,
+    seen: usize,
 
-    let mut first_date: Option<DateTime<Local>> = None;
-    let mut first_author = String::new();
-    let mut last_date: Option<DateTime<Local>> = None;
-    let mut last_author = String::new();
+    first_date: Option<DateTime<Local>>,
+    first_author: String,
+    last_date: Option<DateTime<Local>>,
+    last_author: String,
 
-    let mut contributors = HashSet::<String>::new();
-    let mut day_counts = BTreeMap::<NaiveDate, usize>::new();
+    day_counts: BTreeMap<NaiveDate, usize>,
 
     // Extras
-    let mut all_dates: Vec<NaiveDate> = Vec::new(); // for idle gap
-    let mut weekday_counts = [0usize; 7]; // Mon..Sun
-    let mut work_hours_hits = 0usize;
+    all_dates: Vec<NaiveDate>,  // for idle gap
+    weekday_counts: [usize; 7], // Mon..Sun
+    work_hours_hits: usize,
 
-    let mut merges = 0usize;
-    let mut reverts = 0usize;
+    merges: usize,
+    reverts: usize,
 
-    let mut subj_lens: Vec<usize> = Vec::new();
-    let mut body_hits = 0usize;
-    let mut conv_hits = 0usize;
+    subj_lens: Vec<usize>,
+    body_hits: usize,
+    conv_hits: usize,
 
-    // Recent windows computed after knowing last_date
-    let mut commits_log: Vec<(String, DateTime<Local>)> = Vec::new();
+    // Recent windows computed after knowing last_date:
+    // (credited author, time, credit, is the commit author)
+    commits_log: Vec<(String, DateTime<Local>, f64, bool)>,
+}
 
-    for id in rw.flatten() {
-        if let Some(max) = limit {
-            if seen >= max {
-                break;
-            }
+impl RepoScanner {
+    pub fn new(limit: Option<usize>) -> Self {
+        Self {
+            limit,
+            ..Self::default()
         }
+    }
+}
 
-        let commit = match repo.find_commit(id) {
-            Ok(c) => c,
-            Err(_) => continue,
-        };
-        let email = commit
-            .author()
-            .email()
-            .unwrap_or("unknown@example.com")
-            .to_string();
-        let ts = commit.time().seconds();
-        let dt = Local.timestamp_opt(ts, 0).single().unwrap();
+impl Analyser for RepoScanner {
+    fn done(&self) -> bool {
+        self.limit.is_some_and(|max| self.seen >= max)
+    }
+
+    fn visit(&mut self, c: &CommitCtx) {
+        let email = c.author.clone();
+        let dt = c.time;
 
         // per-author stats
-        seen += 1;
-        let e = data.entry(email.clone()).or_insert(AuthorMeta {
-            count: 0,
-            first: dt,
-            last: dt,
-        });
-        e.count += 1;
-        if dt < e.first {
-            e.first = dt;
-        }
-        if dt > e.last {
-            e.last = dt;
+        self.seen += 1;
+        for (who, w) in &c.credits {
+            record(&mut self.data, who, dt, *w);
         }
 
         // summary core
-        contributors.insert(email.clone());
         let day = dt.date_naive();
-        *day_counts.entry(day).or_default() += 1;
+        *self.day_counts.entry(day).or_default() += 1;
 
-        match first_date {
-            None => {
-                first_date = Some(dt);
-                first_author = email.clone();
-            }
-            Some(d) => {
-                if dt < d {
-                    first_date = Some(dt);
-                    first_author = email.clone();
-                }
-            }
+        if self.first_date.is_none_or(|d| dt < d) {
+            self.first_date = Some(dt);
+            self.first_author = email.clone();
         }
-        match last_date {
-            None => {
-                last_date = Some(dt);
-                last_author = email.clone();
-            }
-            Some(d) => {
-                if dt > d {
-                    last_date = Some(dt);
-                    last_author = email.clone();
-                }
-            }
+        if self.last_date.is_none_or(|d| dt > d) {
+            self.last_date = Some(dt);
+            self.last_author = email.clone();
         }
 
         // extras
-        all_dates.push(day);
+        self.all_dates.push(day);
 
         // weekday 0..6 (Mon..Sun)
         let idx = match dt.weekday() {
@@ -250,33 +276,33 @@ pub fn scan_repo(repo: &Repository, limit: Option<usize>) -> RepoScan {
             Weekday::Sat => 5,
             Weekday::Sun => 6,
         };
-        weekday_counts[idx] += 1;
+        self.weekday_counts[idx] += 1;
 
         let hour = dt.time().hour(); // requires Timelike via prelude
         if (9..=17).contains(&hour) {
-            work_hours_hits += 1;
+            self.work_hours_hits += 1;
         }
 
-        if commit.parent_count() > 1 {
-            merges += 1;
+        if c.is_merge() {
+            self.merges += 1;
         }
-        let subject = commit.summary().unwrap_or("").trim();
-        let message = commit.message().unwrap_or("").trim();
+        let subject = c.commit.summary().unwrap_or("").trim();
+        let message = c.commit.message().unwrap_or("").trim();
 
         if subject.starts_with("Revert") || message.contains("This reverts commit") {
-            reverts += 1;
+            self.reverts += 1;
         }
 
         // message hygiene
-        subj_lens.push(subject.chars().count());
+        self.subj_lens.push(subject.chars().count());
         // body present? crude but effective: look for blank line then non-empty
         let body_present = message
-            .splitn(2, "\n\n")
-            .nth(1)
+            .split_once("\n\n")
+            .map(|(_, b)| b)
             .map(|b| b.trim().chars().any(|c| !c.is_whitespace()))
             .unwrap_or(false);
         if body_present {
-            body_hits += 1;
+            self.body_hits += 1;
         }
 
         // conventional commit heuristic
@@ -288,229 +314,290 @@ pub fn scan_repo(repo: &Repository, limit: Option<usize>) -> RepoScan {
             subject.starts_with(&format!("{t}:")) || subject.starts_with(&format!("{t}("))
         });
         if is_conv {
-            conv_hits += 1;
+            self.conv_hits += 1;
         }
 
-        commits_log.push((email, dt));
+        for (i, (who, w)) in c.credits.iter().enumerate() {
+            self.commits_log.push((who.clone(), dt, *w, i == 0));
+        }
     }
+}
 
-    let total_commits = seen;
-    let (first_date, last_date) = match (first_date, last_date) {
-        (Some(f), Some(l)) => (f, l),
-        _ => {
-            let now = Local::now();
-            (now, now)
-        }
-    };
-
-    // Active period & average
-    let active_days = (last_date.date_naive() - first_date.date_naive())
-        .num_days()
-        .max(0)
-        + 1;
-    let avg_commits_per_day = if active_days > 0 {
-        total_commits as f64 / active_days as f64
-    } else {
-        0.0
-    };
-
-    // Peak day
-    let peak_day = day_counts
-        .iter()
-        .max_by_key(|(_, c)| **c)
-        .map(|(d, c)| (*d, *c));
-
-    // Longest idle gap (sort ascending by date and compute max gap)
-    all_dates.sort_unstable();
-    let mut longest_idle_gap_days = 0i64;
-    for w in all_dates.windows(2) {
-        if let [a, b] = w {
-            let gap = (*b - *a).num_days();
-            if gap > longest_idle_gap_days {
-                longest_idle_gap_days = gap;
+/// Author stats + repo summary in one pass over the selected history.
+pub fn scan_repo(repo: &RepoExt, limit: Option<usize>) -> Result<RepoScan> {
+    let mut scanner = RepoScanner::new(limit);
+    let bots = scan_with_bots(repo, &mut scanner)?;
+    let mut scan = scanner.finish();
+    scan.stats.bots = bots;
+    Ok(scan)
+}
+
+impl RepoScanner {
+    pub fn finish(self) -> RepoScan {
+        let RepoScanner {
+            data,
+            seen,
+            first_date,
+            first_author,
+            last_date,
+            last_author,
+            day_counts,
+            mut all_dates,
+            weekday_counts,
+            work_hours_hits,
+            merges,
+            reverts,
+            subj_lens,
+            body_hits,
+            conv_hits,
+            commits_log,
+            ..
+        } = self;
+
+        let total_commits = seen;
+        let (first_date, last_date) = match (first_date, last_date) {
+            (Some(f), Some(l)) => (f, l),
+            _ => {
+                let now = Local::now();
+                (now, now)
             }
-        }
-    }
+        };
 
-    // Weekday & work-hours %
-    let work_hours_pct = if total_commits > 0 {
-        100.0 * (work_hours_hits as f64) / (total_commits as f64)
-    } else {
-        0.0
-    };
+        // Active period & average
+        let active_days = (last_date.date_naive() - first_date.date_naive())
+            .num_days()
+            .max(0)
+            + 1;
+        let avg_commits_per_day = if active_days > 0 {
+            total_commits as f64 / active_days as f64
+        } else {
+            0.0
+        };
 
-    // Merge/Revert rates
-    let merge_rate = if total_commits > 0 {
-        (merges as f64) * 100.0 / total_commits as f64
-    } else {
-        0.0
-    };
-    let revert_rate = if total_commits > 0 {
-        (reverts as f64) * 100.0 / total_commits as f64
-    } else {
-        0.0
-    };
+        // Peak day
+        let peak_day = day_counts
+            .iter()
+            .max_by_key(|(_, c)| **c)
+            .map(|(d, c)| PeakDay {
+                date: *d,
+                commits: *c,
+            });
 
-    // Message stats
-    let msg_median_len = if subj_lens.is_empty() {
-        0
-    } else {
-        let mut v = subj_lens.clone();
-        v.sort_unstable();
-        v[v.len() / 2]
-    };
-    let msg_body_pct = if total_commits > 0 {
-        100.0 * (body_hits as f64) / (total_commits as f64)
-    } else {
-        0.0
-    };
-    let conv_commit_pct = if total_commits > 0 {
-        100.0 * (conv_hits as f64) / (total_commits as f64)
-    } else {
-        0.0
-    };
-
-    // Recent windows
-    let mut active_last_90 = HashSet::<String>::new();
-    let mut recent30_counts = HashMap::<String, usize>::new();
-    let t90 = last_date - Duration::days(90);
-    let t30 = last_date - Duration::days(30);
-    let mut commits_last_90 = 0usize;
-
-    for (email, dt) in &commits_log {
-        if *dt >= t90 {
-            active_last_90.insert(email.clone());
-            commits_last_90 += 1;
-        }
-        if *dt >= t30 {
-            *recent30_counts.entry(email.clone()).or_default() += 1;
+        // Longest idle gap (sort ascending by date and compute max gap)
+        all_dates.sort_unstable();
+        let mut longest_idle_gap_days = 0i64;
+        for w in all_dates.windows(2) {
+            if let [a, b] = w {
+                let gap = (*b - *a).num_days();
+                if gap > longest_idle_gap_days {
+                    longest_idle_gap_days = gap;
+                }
+            }
         }
-    }
-    let momentum_90d_pct = if total_commits > 0 {
-        100.0 * (commits_last_90 as f64) / (total_commits as f64)
-    } else {
-        0.0
-    };
-    let top_recent_30d = recent30_counts.into_iter().max_by_key(|(_, n)| *n);
-    // --- NEW: Top authors in the last 12 months (365 days from last commit) ---
-    let t365 = last_date - Duration::days(365);
-    let mut recent12_map: HashMap<String, AuthorMeta> = HashMap::new();
-    for (email, dt) in &commits_log {
-        if *dt >= t365 {
-            let e = recent12_map.entry(email.clone()).or_insert(AuthorMeta {
-                count: 0,
-                first: *dt,
-                last: *dt,
-            });
-            e.count += 1;
-            if *dt < e.first {
-                e.first = *dt;
+
+        // Weekday & work-hours %
+        let work_hours_pct = if total_commits > 0 {
+            100.0 * (work_hours_hits as f64) / (total_commits as f64)
+        } else {
+            0.0
+        };
+
+        // Merge/Revert rates
+        let merge_rate = if total_commits > 0 {
+            (merges as f64) * 100.0 / total_commits as f64
+        } else {
+            0.0
+        };
+        let revert_rate = if total_commits > 0 {
+            (reverts as f64) * 100.0 / total_commits as f64
+        } else {
+            0.0
+        };
+
+        // Message stats
+        let msg_median_len = if subj_lens.is_empty() {
+            0
+        } else {
+            let mut v = subj_lens.clone();
+            v.sort_unstable();
+            v[v.len() / 2]
+        };
+        let msg_body_pct = if total_commits > 0 {
+            100.0 * (body_hits as f64) / (total_commits as f64)
+        } else {
+            0.0
+        };
+        let conv_commit_pct = if total_commits > 0 {
+            100.0 * (conv_hits as f64) / (total_commits as f64)
+        } else {
+            0.0
+        };
+
+        // Recent windows
+        let mut active_last_90 = HashSet::<String>::new();
+        let mut recent30_counts = HashMap::<String, f64>::new();
+        let t90 = last_date - Duration::days(90);
+        let t30 = last_date - Duration::days(30);
+        let mut commits_last_90 = 0usize;
+
+        for (email, dt, w, is_author) in &commits_log {
+            if *dt >= t90 {
+                active_last_90.insert(email.clone());
+                if *is_author {
+                    commits_last_90 += 1;
+                }
             }
-            if *dt > e.last {
-                e.last = *dt;
+            if *dt >= t30 {
+                *recent30_counts.entry(email.clone()).or_default() += w;
             }
         }
-    }
-    let recent12_total = recent12_map.values().map(|e| e.count).sum();
-    let recent12_stats = CommitStats {
-        total_seen: recent12_total,
-        data: recent12_map,
-    };
-
-    // Drive-by & core size & concentration (HHI, Gini)
-    let mut counts: Vec<(String, usize)> = data.iter().map(|(k, v)| (k.clone(), v.count)).collect();
-    let contributors_total = counts.len();
-    counts.sort_by(|a, b| b.1.cmp(&a.1));
-
-    let drive_by_ratio = if contributors_total > 0 {
-        let drive = counts.iter().filter(|(_, c)| *c <= 2).count();
-        (drive as f64) * 100.0 / (contributors_total as f64)
-    } else {
-        0.0
-    };
+        let momentum_90d_pct = if total_commits > 0 {
+            100.0 * (commits_last_90 as f64) / (total_commits as f64)
+        } else {
+            0.0
+        };
+        let top_recent_30d = recent30_counts
+            .into_iter()
+            .max_by(|a, b| a.1.total_cmp(&b.1))
+            .map(|(author, commits)| RecentTop { author, commits });
+        // --- NEW: Top authors in the last 12 months (365 days from last commit) ---
+        let t365 = last_date - Duration::days(365);
+        let mut recent12_map: HashMap<String, AuthorMeta> = HashMap::new();
+        let mut recent12_total = 0usize;
+        for (email, dt, w, is_author) in &commits_log {
+            if *dt >= t365 {
+                record(&mut recent12_map, email, *dt, *w);
+                recent12_total += usize::from(*is_author);
+            }
+        }
+        let recent12_stats = CommitStats {
+            total_seen: recent12_total,
+            data: recent12_map,
+            bots: BTreeMap::new(),
+        };
 
-    let total_c: usize = counts.iter().map(|(_, c)| *c).sum();
-    let hhi = if total_c > 0 {
-        counts
-            .iter()
-            .map(|(_, c)| {
-                let s = *c as f64 / total_c as f64;
-                s * s
-            })
-            .sum::<f64>()
-    } else {
-        0.0
-    };
+        // Drive-by & core size & concentration (HHI, Gini)
+        let mut counts: Vec<(String, f64)> =
+            data.iter().map(|(k, v)| (k.clone(), v.count)).collect();
+        let contributors_total = counts.len();
+        counts.sort_by(|a, b| b.1.total_cmp(&a.1));
 
-    let gini = if total_c == 0 || contributors_total == 0 {
-        0.0
-    } else {
-        let mut xs: Vec<usize> = counts.iter().map(|(_, c)| *c).collect();
-        xs.sort_unstable();
-        let n = xs.len() as f64;
-        let sum: f64 = xs.iter().map(|&x| x as f64).sum();
-        let mut cum = 0f64;
-        let mut num = 0f64;
-        for &x in &xs {
-            cum += x as f64;
-            num += cum;
-        }
-        if sum > 0.0 {
-            (n + 1.0 - 2.0 * (num / sum)) / n
+        let drive_by_ratio = if contributors_total > 0 {
+            let drive = counts.iter().filter(|(_, c)| *c <= 2.0).count();
+            (drive as f64) * 100.0 / (contributors_total as f64)
+        } else {
+            0.0
+        };
+
+        let total_c: f64 = counts.iter().map(|(_, c)| *c).sum();
+        let hhi = if total_c > 0.0 {
+            counts
+                .iter()
+                .map(|(_, c)| {
+                    let s = *c / total_c;
+                    s * s
+                })
+                .sum::<f64>()
         } else {
             0.0
+        };
+
+        let gini = if total_c == 0.0 || contributors_total == 0 {
+            0.0
+        } else {
+            let mut xs: Vec<f64> = counts.iter().map(|(_, c)| *c).collect();
+            xs.sort_by(f64::total_cmp);
+            let n = xs.len() as f64;
+            let sum: f64 = xs.iter().sum();
+            let mut cum = 0f64;
+            let mut num = 0f64;
+            for &x in &xs {
+                cum += x;
+                num += cum;
+            }
+            if sum > 0.0 {
+                (n + 1.0 - 2.0 * (num / sum)) / n
+            } else {
+                0.0
+            }
+        };
+
+        // Core size: minimal top authors covering ≥80% of commits
+        let mut acc = 0.0;
+        let target = total_c * 0.80;
+        let mut core_size_80pct = 0usize;
+        for (_, c) in &counts {
+            core_size_80pct += 1;
+            acc += *c;
+            if acc >= target {
+                break;
+            }
         }
-    };
-
-    // Core size: minimal top authors covering ≥80% of commits
-    let mut acc = 0usize;
-    let target = ((total_c as f64) * 0.80).ceil() as usize;
-    let mut core_size_80pct = 0usize;
-    for (_, c) in &counts {
-        core_size_80pct += 1;
-        acc += *c;
-        if acc >= target {
-            break;
+        if total_c == 0.0 {
+            core_size_80pct = 0;
+        }
+
+        let summary = RepoSummary {
+            total_commits,
+            contributors_total,
+            first_author,
+            first_date,
+            last_author,
+            last_date,
+            active_days,
+            avg_commits_per_day,
+            peak_day,
+            active_authors_last_90d: active_last_90.len(),
+            top_recent_30d,
+
+            drive_by_ratio,
+            core_size_80pct,
+            hhi,
+            gini,
+            longest_idle_gap_days,
+            weekday_counts,
+            work_hours_pct,
+            merge_rate,
+            revert_rate,
+            msg_median_len,
+            msg_body_pct,
+            conv_commit_pct,
+            momentum_90d_pct,
+        };
+
+        RepoScan {
+            stats: CommitStats {
+                total_seen: total_commits,
+                data,
+                bots: BTreeMap::new(),
+            },
+            summary,
+            recent12: recent12_stats,
         }
     }
-    if total_c == 0 {
-        core_size_80pct = 0;
-    }
+}
 
-    let summary = RepoSummary {
-        total_commits,
-        contributors_total,
-        first_author,
-        first_date,
-        last_author,
-        last_date,
-        active_days,
-        avg_commits_per_day,
-        peak_day,
-        active_authors_last_90d: active_last_90.len(),
-        top_recent_30d,
-
-        drive_by_ratio,
-        core_size_80pct,
-        hhi,
-        gini,
-        longest_idle_gap_days,
-        weekday_counts,
-        work_hours_pct,
-        merge_rate,
-        revert_rate,
-        msg_median_len,
-        msg_body_pct,
-        conv_commit_pct,
-        momentum_90d_pct,
-    };
-
-    RepoScan {
-        stats: CommitStats {
-            total_seen: total_commits,
-            data,
-        },
-        summary,
-        recent12: recent12_stats,
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::domain::test_repo::TestRepo;
+
+    #[test]
+    fn case_only_name_change_is_one_author() {
+        let mut t = TestRepo::new();
+        t.commit("alice smith <Alice@x.com>", "one", &[("a", Some("1\n"))]);
+        t.commit("Bob <bob@x.com>", "two", &[("b", Some("1\n"))]);
+        t.commit("Alice Smith <alice@x.com>", "three", &[("a", Some("2\n"))]);
+        let repo = t.open();
+
+        let scan = scan_repo(&repo, None).unwrap();
+        assert_eq!(scan.summary.contributors_total, 2);
+        let (key, alice) = scan.stats.find("alice@x.com").unwrap();
+        assert_eq!(key, "Alice Smith <alice@x.com>");
+        assert_eq!(alice.count, 2.0);
+        assert_eq!(scan.summary.first_author, "Alice Smith <alice@x.com>");
+
+        let firsts = crate::domain::firsts::first_commits(&repo).unwrap();
+        assert_eq!(firsts.len(), 2);
     }
 }
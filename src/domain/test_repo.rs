@@ -0,0 +1,67 @@
+//! Throwaway repositories for unit tests.
+
+use git2::{IndexAddOption, Oid, Repository, Signature, Time};
+use tempfile::TempDir;
+
+use crate::domain::git::{RepoExt, RepoOptions};
+
+/// A repository in a temporary directory; commits are made with explicit
+/// authors and times so results are deterministic.
+pub struct TestRepo {
+    pub dir: TempDir,
+    pub repo: Repository,
+    /// Seconds since the epoch of the next commit (one day apart).
+    clock: i64,
+}
+
+impl TestRepo {
+    pub fn new() -> Self {
+        let dir = TempDir::new().unwrap();
+        let repo = Repository::init(dir.path()).unwrap();
+        Self {
+            dir,
+            repo,
+            clock: 1_700_000_000,
+        }
+    }
+
+    /// Write `files` (`None` deletes), stage everything and commit on HEAD
+    /// as `author` (`Name <email>`).
+    pub fn commit(&mut self, author: &str, message: &str, files: &[(&str, Option<&str>)]) -> Oid {
+        for (path, text) in files {
+            let full = self.dir.path().join(path);
+            match text {
+                Some(t) => {
+                    std::fs::create_dir_all(full.parent().unwrap()).unwrap();
+                    std::fs::write(full, t).unwrap();
+                }
+                None => std::fs::remove_file(full).unwrap(),
+            }
+        }
+        let mut index = self.repo.index().unwrap();
+        index.add_all(["*"], IndexAddOption::DEFAULT, None).unwrap();
+        index.update_all(["*"], None).unwrap();
+        index.write().unwrap();
+        let tree = self.repo.find_tree(index.write_tree().unwrap()).unwrap();
+
+        let (name, email) = author.trim_end_matches('>').split_once(" <").unwrap();
+        self.clock += 86_400;
+        let sig = Signature::new(name, email, &Time::new(self.clock, 0)).unwrap();
+        let parent = self.repo.head().ok().map(|h| h.peel_to_commit().unwrap());
+        let parents: Vec<_> = parent.iter().collect();
+        self.repo
+            .commit(Some("HEAD"), &sig, &sig, message, &tree, &parents)
+            .unwrap()
+    }
+
+    /// Open with `opts` and the facts cache kept in memory.
+    pub fn open_with(&self, opts: RepoOptions) -> RepoExt {
+        opts.no_cache(true)
+            .open(self.dir.path().to_str().unwrap())
+            .unwrap()
+    }
+
+    pub fn open(&self) -> RepoExt {
+        self.open_with(RepoOptions::default())
+    }
+}
@@ -1,21 +1,19 @@
 use anyhow::Result;
-use chrono::{Local, TimeZone, Timelike};
-use git2::Repository;
+use chrono::{Datelike, NaiveDate, Timelike};
 use std::collections::BTreeMap;
 
-pub fn commit_times(repo: &Repository) -> Result<BTreeMap<String, BTreeMap<String, usize>>> {
-    let mut author_times: BTreeMap<String, BTreeMap<String, usize>> = BTreeMap::new();
-    let mut rw = repo.revwalk()?;
-    rw.push_head()?;
+use crate::domain::engine::{self, Analyser, CommitCtx};
+use crate::domain::git::RepoExt;
 
-    for oid in rw.flatten() {
-        let commit = repo.find_commit(oid)?;
-        let dt = Local
-            .timestamp_opt(commit.time().seconds(), 0)
-            .single()
-            .unwrap();
-        let hour = dt.hour();
-        let bucket = match hour {
+/// Commits per author in four time-of-day buckets.
+#[derive(Debug, Default)]
+pub struct CommitTimes {
+    pub by_author: BTreeMap<String, BTreeMap<String, usize>>,
+}
+
+impl Analyser for CommitTimes {
+    fn visit(&mut self, c: &CommitCtx) {
+        let bucket = match c.time.hour() {
             0..=5 => "night",
             6..=11 => "morning",
             12..=17 => "afternoon",
@@ -23,12 +21,30 @@ pub fn commit_times(repo: &Repository) -> Result<BTreeMap<String, BTreeMap<Strin
         }
         .to_string();
 
-        let email = commit.author().email().unwrap_or("unknown").to_string();
-        *author_times
-            .entry(email)
+        *self
+            .by_author
+            .entry(c.author.clone())
             .or_default()
             .entry(bucket)
             .or_default() += 1;
     }
-    Ok(author_times)
+}
+
+pub fn commit_times(repo: &RepoExt) -> Result<BTreeMap<String, BTreeMap<String, usize>>> {
+    Ok(engine::run(repo, CommitTimes::default())?.by_author)
+}
+
+/// Commits per calendar month, keyed by the month's first day.
+#[derive(Debug, Default)]
+pub struct CommitsPerMonth {
+    pub counts: BTreeMap<NaiveDate, usize>,
+}
+
+impl Analyser for CommitsPerMonth {
+    fn visit(&mut self, c: &CommitCtx) {
+        let day = c.time.date_naive();
+        if let Some(month) = day.with_day(1) {
+            *self.counts.entry(month).or_default() += 1;
+        }
+    }
 }
@@ -0,0 +1,199 @@
+use anyhow::{bail, Result};
+use chrono::{Datelike, Duration, NaiveDate};
+use serde::{Deserialize, Serialize};
+use std::collections::{BTreeMap, HashSet};
+
+use crate::domain::engine::{self, Analyser, CommitCtx};
+use crate::domain::git::RepoExt;
+use crate::domain::stats::RepoScanner;
+
+/// Calendar bucket size for `trend`.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum Period {
+    Week,
+    #[default]
+    Month,
+    Quarter,
+    Year,
+}
+
+impl Period {
+    pub fn parse(s: &str) -> Result<Self> {
+        Ok(match s.to_ascii_lowercase().as_str() {
+            "week" => Self::Week,
+            "month" => Self::Month,
+            "quarter" => Self::Quarter,
+            "year" => Self::Year,
+            _ => bail!("unknown period '{s}' (use week, month, quarter or year)"),
+        })
+    }
+
+    pub fn as_str(self) -> &'static str {
+        match self {
+            Self::Week => "week",
+            Self::Month => "month",
+            Self::Quarter => "quarter",
+            Self::Year => "year",
+        }
+    }
+
+    /// First day of the bucket containing `day` (weeks start on Monday).
+    pub fn start(self, day: NaiveDate) -> NaiveDate {
+        let ymd = |y, m| NaiveDate::from_ymd_opt(y, m, 1).expect("valid month");
+        match self {
+            Self::Week => day - Duration::days(day.weekday().num_days_from_monday() as i64),
+            Self::Month => ymd(day.year(), day.month()),
+            Self::Quarter => ymd(day.year(), (day.month() - 1) / 3 * 3 + 1),
+            Self::Year => ymd(day.year(), 1),
+        }
+    }
+
+    /// `2025-W03`, `2025-01`, `2025-Q1`, `2025`.
+    pub fn label(self, start: NaiveDate) -> String {
+        match self {
+            Self::Week => {
+                let w = start.iso_week();
+                format!("{}-W{:02}", w.year(), w.week())
+            }
+            Self::Month => start.format("%Y-%m").to_string(),
+            Self::Quarter => format!("{}-Q{}", start.year(), (start.month() - 1) / 3 + 1),
+            Self::Year => start.year().to_string(),
+        }
+    }
+}
+
+/// `stats` metrics for one calendar bucket.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct TrendPoint {
+    pub period: String,
+    pub start: NaiveDate,
+    pub commits: usize,
+    /// Authors (and credited co-authors) with a commit in the bucket.
+    pub active_authors: usize,
+    /// Of those, people whose first selected commit is in this bucket.
+    pub new_authors: usize,
+    pub core_size_80pct: usize,
+    pub drive_by_ratio_pct: f64,
+    pub concentration_hhi: f64,
+    pub concentration_gini: f64,
+    pub merge_rate_pct: f64,
+    pub revert_rate_pct: f64,
+    pub conventional_commit_pct: f64,
+    pub body_present_pct: f64,
+    pub median_subject_len: usize,
+    pub work_hours_pct: f64,
+}
+
+/// Feeds each commit to a `RepoScanner` for its bucket, so every bucket's
+/// metrics are computed exactly like `stats`.
+#[derive(Debug, Default)]
+pub struct Trend {
+    period: Period,
+    buckets: BTreeMap<NaiveDate, RepoScanner>,
+}
+
+impl Trend {
+    pub fn new(period: Period) -> Self {
+        Self {
+            period,
+            buckets: BTreeMap::new(),
+        }
+    }
+
+    /// Buckets oldest first; buckets without commits are left out.
+    pub fn finish(self) -> Vec<TrendPoint> {
+        let mut seen = HashSet::<String>::new();
+        let mut out = Vec::with_capacity(self.buckets.len());
+        for (start, scanner) in self.buckets {
+            let scan = scanner.finish();
+            let new_authors = scan
+                .stats
+                .data
+                .keys()
+                .filter(|a| seen.insert((*a).clone()))
+                .count();
+            let s = scan.summary;
+            out.push(TrendPoint {
+                period: self.period.label(start),
+                start,
+                commits: s.total_commits,
+                active_authors: s.contributors_total,
+                new_authors,
+                core_size_80pct: s.core_size_80pct,
+                drive_by_ratio_pct: s.drive_by_ratio,
+                concentration_hhi: s.hhi,
+                concentration_gini: s.gini,
+                merge_rate_pct: s.merge_rate,
+                revert_rate_pct: s.revert_rate,
+                conventional_commit_pct: s.conv_commit_pct,
+                body_present_pct: s.msg_body_pct,
+                median_subject_len: s.msg_median_len,
+                work_hours_pct: s.work_hours_pct,
+            });
+        }
+        out
+    }
+}
+
+impl Analyser for Trend {
+    fn visit(&mut self, c: &CommitCtx) {
+        let start = self.period.start(c.time.date_naive());
+        self.buckets.entry(start).or_default().visit(c);
+    }
+}
+
+pub fn trend(repo: &RepoExt, period: Period) -> Result<Vec<TrendPoint>> {
+    Ok(engine::run(repo, Trend::new(period))?.finish())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::domain::test_repo::TestRepo;
+
+    fn day(y: i32, m: u32, d: u32) -> NaiveDate {
+        NaiveDate::from_ymd_opt(y, m, d).unwrap()
+    }
+
+    #[test]
+    fn buckets_start_on_their_first_day() {
+        let d = day(2025, 1, 1); // a Wednesday
+        assert_eq!(Period::Week.start(d), day(2024, 12, 30));
+        assert_eq!(Period::Month.start(day(2025, 2, 28)), day(2025, 2, 1));
+        assert_eq!(Period::Quarter.start(day(2025, 6, 30)), day(2025, 4, 1));
+        assert_eq!(Period::Quarter.start(day(2025, 10, 1)), day(2025, 10, 1));
+        assert_eq!(Period::Year.start(d), day(2025, 1, 1));
+    }
+
+    #[test]
+    fn labels_use_iso_weeks_and_quarters() {
+        // The week of 2024-12-30 is ISO week 1 of 2025.
+        assert_eq!(Period::Week.label(day(2024, 12, 30)), "2025-W01");
+        assert_eq!(Period::Month.label(day(2025, 3, 1)), "2025-03");
+        assert_eq!(Period::Quarter.label(day(2025, 7, 1)), "2025-Q3");
+        assert_eq!(Period::Year.label(day(2025, 1, 1)), "2025");
+        assert_eq!(Period::parse("Quarter").unwrap().as_str(), "quarter");
+        assert!(Period::parse("day").is_err());
+    }
+
+    #[test]
+    fn new_authors_count_once_in_their_first_bucket() {
+        // One commit a day from mid-November: Ann throughout, Ben only in
+        // December.
+        let mut t = TestRepo::new();
+        for i in 0..20 {
+            let author = if i >= 16 && i % 2 == 1 {
+                "Ben <ben@x.io>"
+            } else {
+                "Ann <ann@x.io>"
+            };
+            t.commit(author, "change", &[("a", Some(&format!("{i}\n")))]);
+        }
+        let points = trend(&t.open(), Period::Month).unwrap();
+        let labels: Vec<_> = points.iter().map(|p| p.period.as_str()).collect();
+        assert_eq!(labels, ["2023-11", "2023-12"]);
+        assert_eq!(points.iter().map(|p| p.commits).sum::<usize>(), 20);
+        assert_eq!((points[0].active_authors, points[0].new_authors), (1, 1));
+        assert_eq!((points[1].active_authors, points[1].new_authors), (2, 1));
+    }
+}
@@ -0,0 +1,220 @@
+use anyhow::{bail, Context, Result};
+use chrono::{DateTime, Duration, Local, Months, NaiveDate, NaiveDateTime, TimeZone};
+use serde::{Deserialize, Serialize};
+use std::fmt;
+
+use crate::utils::fmt_date;
+
+/// Commit-time window applied to every history scan. Both ends inclusive.
+#[derive(Debug, Clone, Copy, Default, Serialize, Deserialize)]
+pub struct TimeWindow {
+    pub since: Option<DateTime<Local>>,
+    pub until: Option<DateTime<Local>>,
+}
+
+impl TimeWindow {
+    /// Build from CLI-style strings. `last` ("90d", "6 months") is shorthand
+    /// for `since = until - span` and cannot be combined with `since`.
+    pub fn parse(since: Option<&str>, until: Option<&str>, last: Option<&str>) -> Result<Self> {
+        Self::parse_at(since, until, last, Local::now())
+    }
+
+    /// [`parse`](Self::parse) with relative times counted from `now`.
+    fn parse_at(
+        since: Option<&str>,
+        until: Option<&str>,
+        last: Option<&str>,
+        now: DateTime<Local>,
+    ) -> Result<Self> {
+        // A bare date as the upper bound means "through the end of that day".
+        let until = until
+            .map(|u| match NaiveDate::parse_from_str(u.trim(), "%Y-%m-%d") {
+                Ok(d) => local(d.and_hms_opt(23, 59, 59).unwrap()),
+                Err(_) => parse_time(u, now),
+            })
+            .transpose()?;
+        let since = match (since, last) {
+            (Some(_), Some(_)) => bail!("--since and --last are mutually exclusive"),
+            (Some(s), None) => Some(parse_time(s, now)?),
+            (None, Some(l)) => Some(parse_span(l)?.before(until.unwrap_or(now))?),
+            (None, None) => None,
+        };
+        if let (Some(s), Some(u)) = (since, until) {
+            if s > u {
+                bail!("time window is empty: since {} is after until {}", s, u);
+            }
+        }
+        Ok(Self { since, until })
+    }
+
+    pub fn contains(&self, dt: DateTime<Local>) -> bool {
+        self.since.is_none_or(|s| dt >= s) && self.until.is_none_or(|u| dt <= u)
+    }
+
+    pub fn is_unbounded(&self) -> bool {
+        self.since.is_none() && self.until.is_none()
+    }
+}
+
+impl fmt::Display for TimeWindow {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match (self.since, self.until) {
+            (None, None) => write!(f, "all time"),
+            (Some(s), None) => write!(f, "since {}", fmt_date(s)),
+            (None, Some(u)) => write!(f, "until {}", fmt_date(u)),
+            (Some(s), Some(u)) => write!(f, "{} → {}", fmt_date(s), fmt_date(u)),
+        }
+    }
+}
+
+/// A calendar-aware span ("6 months", "2w", "90d").
+#[derive(Debug, Clone, Copy)]
+struct Span {
+    months: u32,
+    seconds: i64,
+}
+
+impl Span {
+    fn before(&self, t: DateTime<Local>) -> Result<DateTime<Local>> {
+        let t = t
+            .checked_sub_months(Months::new(self.months))
+            .context("date out of range")?;
+        Ok(t - Duration::seconds(self.seconds))
+    }
+}
+
+/// Parse "90d", "90 days", "6 months", "1y", "2 weeks".
+fn parse_span(s: &str) -> Result<Span> {
+    let s = s.trim().to_ascii_lowercase();
+    let digits = s.chars().take_while(|c| c.is_ascii_digit()).count();
+    let n: i64 = s[..digits]
+        .parse()
+        .with_context(|| format!("bad time span '{s}' (expected e.g. 90d, 6 months)"))?;
+    let unit = s[digits..].trim();
+    let unit = unit
+        .strip_suffix('s')
+        .filter(|u| !u.is_empty())
+        .unwrap_or(unit);
+    let (months, seconds) = match unit {
+        "min" | "minute" => (0, n * 60),
+        "h" | "hour" => (0, n * 3600),
+        "d" | "day" => (0, n * 86_400),
+        "w" | "week" => (0, n * 7 * 86_400),
+        "m" | "mo" | "month" => (n, 0),
+        "q" | "quarter" => (n * 3, 0),
+        "y" | "year" => (n * 12, 0),
+        _ => bail!("unknown time unit in '{s}' (use d, w, m, q, y)"),
+    };
+    Ok(Span {
+        months: u32::try_from(months).context("time span too large")?,
+        seconds,
+    })
+}
+
+/// Parse an absolute or relative point in time: `2025-01-01`,
+/// `2025-01-01 12:00`, RFC 3339, `now`, `today`, `yesterday`,
+/// `6 months ago`, `2w ago`.
+pub fn parse_time(s: &str, now: DateTime<Local>) -> Result<DateTime<Local>> {
+    let t = s.trim().to_ascii_lowercase();
+    let start_of = |d: NaiveDate| local(d.and_hms_opt(0, 0, 0).unwrap());
+    match t.as_str() {
+        "now" => return Ok(now),
+        "today" => return start_of(now.date_naive()),
+        "yesterday" => return start_of(now.date_naive() - Duration::days(1)),
+        _ => {}
+    }
+    if let Some(rel) = t.strip_suffix("ago") {
+        return parse_span(rel)?.before(now);
+    }
+    if let Ok(d) = NaiveDate::parse_from_str(&t, "%Y-%m-%d") {
+        return start_of(d);
+    }
+    for fmt in ["%Y-%m-%d %H:%M:%S", "%Y-%m-%d %H:%M", "%Y-%m-%dt%H:%M:%S"] {
+        if let Ok(dt) = NaiveDateTime::parse_from_str(&t, fmt) {
+            return local(dt);
+        }
+    }
+    if let Ok(dt) = DateTime::parse_from_rfc3339(s.trim()) {
+        return Ok(dt.with_timezone(&Local));
+    }
+    bail!("cannot parse date '{s}' (try 2025-01-01 or '6 months ago')")
+}
+
+fn local(dt: NaiveDateTime) -> Result<DateTime<Local>> {
+    Local
+        .from_local_datetime(&dt)
+        .earliest()
+        .with_context(|| format!("invalid local time {dt}"))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn at(s: &str) -> DateTime<Local> {
+        local(NaiveDateTime::parse_from_str(s, "%Y-%m-%d %H:%M:%S").unwrap()).unwrap()
+    }
+
+    fn now() -> DateTime<Local> {
+        at("2025-06-15 12:00:00")
+    }
+
+    #[test]
+    fn relative_times_count_back_from_now() {
+        let t = |s| parse_time(s, now()).unwrap();
+        assert_eq!(t("now"), now());
+        assert_eq!(t("6 months ago"), at("2024-12-15 12:00:00"));
+        assert_eq!(t("2w ago"), at("2025-06-01 12:00:00"));
+        assert_eq!(t("10d ago"), at("2025-06-05 12:00:00"));
+        assert_eq!(t("1y ago"), at("2024-06-15 12:00:00"));
+        assert_eq!(t("today"), at("2025-06-15 00:00:00"));
+        assert_eq!(t("Yesterday"), at("2025-06-14 00:00:00"));
+    }
+
+    #[test]
+    fn absolute_times() {
+        let t = |s| parse_time(s, now()).unwrap();
+        assert_eq!(t("2025-01-02"), at("2025-01-02 00:00:00"));
+        assert_eq!(t("2025-01-02 03:04"), at("2025-01-02 03:04:00"));
+        assert_eq!(t("2025-01-02T03:04:05"), at("2025-01-02 03:04:05"));
+        let rfc = t("2025-01-02T03:04:05+02:00");
+        assert_eq!(rfc.timestamp(), 1_735_779_845);
+    }
+
+    #[test]
+    fn garbage_is_rejected() {
+        for s in ["", "soon", "2025-13-01", "3 fortnights ago", "ago", "x ago"] {
+            assert!(parse_time(s, now()).is_err(), "{s}");
+        }
+        assert!(parse_span("5").is_err());
+        assert!(TimeWindow::parse_at(None, None, Some("lots"), now()).is_err());
+    }
+
+    #[test]
+    fn a_bare_until_date_includes_the_whole_day() {
+        let w = TimeWindow::parse_at(Some("2025-01-01"), Some("2025-01-31"), None, now()).unwrap();
+        assert_eq!(w.since, Some(at("2025-01-01 00:00:00")));
+        assert_eq!(w.until, Some(at("2025-01-31 23:59:59")));
+        assert!(w.contains(at("2025-01-31 18:00:00")));
+        assert!(!w.contains(at("2025-02-01 00:00:00")));
+        assert!(!w.contains(at("2024-12-31 23:59:59")));
+    }
+
+    #[test]
+    fn last_counts_back_from_until_or_now() {
+        let w = TimeWindow::parse_at(None, Some("2025-03-31"), Some("1 month"), now()).unwrap();
+        assert_eq!(w.since, Some(at("2025-02-28 23:59:59")));
+        let w = TimeWindow::parse_at(None, None, Some("30d"), now()).unwrap();
+        assert_eq!(w.since, Some(at("2025-05-16 12:00:00")));
+        assert_eq!(w.until, None);
+    }
+
+    #[test]
+    fn inverted_or_conflicting_windows_are_errors() {
+        assert!(TimeWindow::parse_at(Some("2025-02-01"), Some("2025-01-01"), None, now()).is_err());
+        assert!(TimeWindow::parse_at(Some("2025-01-01"), None, Some("30d"), now()).is_err());
+        assert!(TimeWindow::parse_at(None, None, None, now())
+            .unwrap()
+            .is_unbounded());
+    }
+}
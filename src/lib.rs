@@ -0,0 +1,47 @@
+//! Repository analytics behind the `gitrivia` CLI.
+//!
+//! Open a repository through [`RepoOptions`] (revision selection, time
+//! window, path filters, identity and bot handling), then call the
+//! analyses in [`domain`]. Every result type is plain data that derives
+//! `serde::Serialize`, so it can be stored or sent as JSON as-is.
+//!
+//! ```no_run
+//! use gitrivia::{bus_factor, churn, stats, RepoOptions, ScanOpts, TimeWindow};
+//!
+//! let repo = RepoOptions::default()
+//!     .window(TimeWindow::parse(Some("2025-01-01"), None, None)?)
+//!     .open(".")?;
+//!
+//! let summary = stats::scan_repo(&repo, None)?.summary;
+//! println!("{} commits by {} people", summary.total_commits, summary.contributors_total);
+//!
+//! let opts = ScanOpts::default().min_total(10);
+//! let owners = bus_factor::compute_scores_parallel(&repo, &opts)?;
+//! let hot = churn::compute_churn(&repo, 90, &opts)?;
+//! println!("{}", serde_json::to_string_pretty(&(owners, hot))?);
+//! # anyhow::Ok(())
+//! ```
+//!
+//! Analyses that walk history are [`engine::Analyser`]s; several can
+//! share one walk via [`engine::scan`].
+
+pub mod domain;
+pub mod utils;
+
+pub use domain::{
+    age, blame, bots, bus_factor, cache, churn, coauthors, codeowners, compare, engine, explore,
+    files, firsts, identity, metrics, paths, report, reviewers, revs, stats, times, trend, window,
+};
+
+pub use domain::blame::{BlameSettings, MoveDetection};
+pub use domain::bots::BotMode;
+pub use domain::bus_factor::{BusScore, DirScore, ScanOpts};
+pub use domain::churn::ChurnEntry;
+pub use domain::compare::Comparison;
+pub use domain::git::{RepoExt, RepoOptions};
+pub use domain::identity::CoauthorCredit;
+pub use domain::metrics::{MetricsOpts, RepoMetrics};
+pub use domain::revs::RevSelection;
+pub use domain::stats::{AuthorMeta, CommitStats, PeakDay, RecentTop, RepoScan, RepoSummary};
+pub use domain::trend::{Period, TrendPoint};
+pub use domain::window::TimeWindow;
@@ -1,29 +1,41 @@
 mod cli;
 mod commands;
-mod domain;
+mod config;
 mod presentation;
-mod utils;
 
-use clap::Parser;
-use commands::{Global, Runnable};
+use clap::{CommandFactory, Parser};
+use commands::Global;
+use gitrivia::{domain, utils};
 
 fn main() -> anyhow::Result<()> {
-    let cli = cli::Cli::parse();
-    let g = Global {
-        json: cli.json,
-        desc: cli.desc,
+    let (cli, config) = parse_with_config()?;
+    let g = Global::new(&cli, config)?;
+    cli.command.run(&g)
+}
+
+/// Parse the command line with config-file settings applied underneath
+/// it. A first parse finds the repository (and `--no-config`); if config
+/// settings exist, the arguments are re-parsed with them as defaults.
+fn parse_with_config() -> anyhow::Result<(cli::Cli, config::Config)> {
+    let args: Vec<_> = std::env::args_os().collect();
+    let first = cli::Cli::try_parse_from(&args);
+    let no_config = match &first {
+        Ok(c) => c.no_config,
+        Err(_) => args.iter().any(|a| a == "--no-config"),
     };
+    if no_config {
+        return Ok((
+            first.unwrap_or_else(|e| e.exit()),
+            config::Config::default(),
+        ));
+    }
 
-    match cli.command {
-        cli::CliCommand::Stats(c) => c.run(&g),
-        cli::CliCommand::TopAuthors(c) => c.run(&g),
-        cli::CliCommand::AuthorActivity(c) => c.run(&g),
-        cli::CliCommand::BlameSummary(c) => c.run(&g),
-        cli::CliCommand::FileContributions(c) => c.run(&g),
-        cli::CliCommand::CommitTimes(c) => c.run(&g),
-        cli::CliCommand::FirstCommits(c) => c.run(&g),
-        cli::CliCommand::TopCoauthors(c) => c.run(&g),
-        cli::CliCommand::BusFactor(c) => c.run(&g),
-        cli::CliCommand::Churn(c) => c.run(&g),
+    let path = first.as_ref().map_or(".", |c| c.command.path());
+    let config = config::Config::load(path)?;
+    if config.is_empty() {
+        return Ok((first.unwrap_or_else(|e| e.exit()), config));
     }
+    config.validate(&cli::Cli::command())?;
+    let cli = cli::Cli::parse_with(&config, &args).unwrap_or_else(|e| e.exit());
+    Ok((cli, config))
 }
@@ -0,0 +1,38 @@
+//! CODEOWNERS file output, in the syntax GitHub and GitLab share: one
+//! `pattern owner…` line per rule, the last matching rule winning.
+
+use crate::domain::codeowners::OwnerRule;
+
+/// The file: `header` as comment lines, then one line per rule with the
+/// patterns aligned. Rules without owners are written as comments.
+pub fn render(rules: &[OwnerRule], header: &[String]) -> String {
+    let mut out = String::new();
+    for h in header {
+        out.push_str(&format!("# {h}\n"));
+    }
+    if !header.is_empty() {
+        out.push('\n');
+    }
+    let width = rules
+        .iter()
+        .map(|r| escape(&r.pattern).chars().count())
+        .max()
+        .unwrap_or(0);
+    for r in rules {
+        let pattern = escape(&r.pattern);
+        if r.owners.is_empty() {
+            out.push_str(&format!(
+                "# {pattern}  (no active owner above the cutoff)\n"
+            ));
+            continue;
+        }
+        let owners: Vec<&str> = r.owners.iter().map(|c| c.email.as_str()).collect();
+        out.push_str(&format!("{pattern:<width$}  {}\n", owners.join(" ")));
+    }
+    out
+}
+
+/// `#` would start a comment and spaces would end the pattern.
+fn escape(pattern: &str) -> String {
+    pattern.replace('#', "\\#").replace(' ', "\\ ")
+}
@@ -0,0 +1,282 @@
+use anyhow::{bail, Result};
+use serde_json::Value;
+use std::fmt::Display;
+use std::io::{BufWriter, StdoutLock, Write};
+
+use crate::presentation::markdown;
+
+/// How a command prints its results.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum Format {
+    /// Human-readable tables and listings.
+    #[default]
+    Table,
+    /// Pretty JSON (same as `--json`).
+    Json,
+    /// Comma-separated rows with a header.
+    Csv,
+    /// Tab-separated rows with a header.
+    Tsv,
+    /// GitHub-flavored Markdown: headings and pipe tables.
+    Markdown,
+    /// One JSON object per row and line, keyed by the csv header.
+    Ndjson,
+}
+
+impl Format {
+    pub fn parse(s: &str) -> Result<Self> {
+        Ok(match s.to_ascii_lowercase().as_str() {
+            "table" => Self::Table,
+            "json" => Self::Json,
+            "csv" => Self::Csv,
+            "tsv" => Self::Tsv,
+            "markdown" => Self::Markdown,
+            "ndjson" => Self::Ndjson,
+            _ => bail!("unknown format '{s}' (use table, json, csv, tsv, markdown or ndjson)"),
+        })
+    }
+
+    /// True for the formats rendered from [`Rows`] (csv, tsv, markdown,
+    /// ndjson).
+    pub fn uses_rows(self) -> bool {
+        matches!(self, Self::Csv | Self::Tsv | Self::Markdown | Self::Ndjson)
+    }
+
+    /// A number for a cell: full precision for data formats, rounded for
+    /// Markdown, which people read.
+    pub fn float(self, x: f64, decimals: usize) -> String {
+        if self == Self::Markdown {
+            format!("{x:.decimals$}")
+        } else {
+            x.to_string()
+        }
+    }
+
+    /// A 0..1 ratio for a cell: as-is for data formats, a percentage for
+    /// Markdown.
+    pub fn ratio(self, r: f64) -> String {
+        if self == Self::Markdown {
+            format!("{:.1}%", r * 100.0)
+        } else {
+            r.to_string()
+        }
+    }
+}
+
+/// Flat output: one header row, then one row per record. Nested results
+/// are spread over several key columns (e.g. file, author, count).
+#[derive(Debug, Clone)]
+pub struct Rows {
+    title: Option<String>,
+    header: Vec<String>,
+    /// Per column: emit numbers and booleans as such in NDJSON.
+    typed: Vec<bool>,
+    rows: Vec<Vec<String>>,
+}
+
+impl Rows {
+    pub fn new(header: &[&str]) -> Self {
+        Self {
+            title: None,
+            header: header.iter().map(|h| h.to_string()).collect(),
+            typed: vec![false; header.len()],
+            rows: Vec::new(),
+        }
+    }
+
+    /// Columns holding numbers or booleans. NDJSON writes their cells as
+    /// JSON literals (empty cells as `null`); every other cell is a string.
+    pub fn typed(mut self, columns: &[&str]) -> Self {
+        for (h, t) in self.header.iter().zip(&mut self.typed) {
+            *t |= columns.contains(&h.as_str());
+        }
+        self
+    }
+
+    /// Heading shown above the table in Markdown (csv/tsv ignore it).
+    pub fn titled(mut self, title: impl Into<String>) -> Self {
+        self.title = Some(title.into());
+        self
+    }
+
+    pub fn push<I, T>(&mut self, row: I)
+    where
+        I: IntoIterator<Item = T>,
+        T: Display,
+    {
+        self.rows
+            .push(row.into_iter().map(|v| v.to_string()).collect());
+    }
+
+    pub fn render(&self, format: Format) -> String {
+        if format == Format::Markdown {
+            let mut out = String::new();
+            if let Some(title) = &self.title {
+                out.push_str(&markdown::heading(2, title));
+            }
+            out.push_str(&markdown::table(&self.header, &self.rows));
+            return out;
+        }
+        let mut out = String::new();
+        if format != Format::Ndjson {
+            out.push_str(&self.line(&self.header, format));
+        }
+        for row in &self.rows {
+            out.push_str(&self.line(row, format));
+        }
+        out
+    }
+
+    pub fn print(&self, format: Format) {
+        print!("{}", self.render(format));
+    }
+
+    /// Write rows to stdout as they are pushed instead of collecting them,
+    /// so a large result is not held a second time as text. Markdown still
+    /// needs every row to size its columns and is printed by
+    /// [`RowWriter::finish`].
+    pub fn writer(self, format: Format) -> Result<RowWriter> {
+        let mut out = BufWriter::new(std::io::stdout().lock());
+        if !matches!(format, Format::Markdown | Format::Ndjson) {
+            out.write_all(self.line(&self.header, format).as_bytes())?;
+        }
+        Ok(RowWriter {
+            rows: self,
+            format,
+            out,
+        })
+    }
+
+    /// One csv/tsv/ndjson record, newline included.
+    fn line(&self, row: &[String], format: Format) -> String {
+        let mut line = if format == Format::Ndjson {
+            // Written by hand to keep the header's column order.
+            let fields: Vec<_> = self
+                .header
+                .iter()
+                .zip(&self.typed)
+                .zip(row)
+                .map(|((h, &typed), c)| {
+                    format!("{}:{}", Value::from(h.as_str()), json_cell(c, typed))
+                })
+                .collect();
+            format!("{{{}}}", fields.join(","))
+        } else {
+            let cells: Vec<_> = row.iter().map(|c| cell(c, format)).collect();
+            cells.join(if format == Format::Tsv { "\t" } else { "," })
+        };
+        line.push('\n');
+        line
+    }
+}
+
+/// [`Rows`] being written out row by row; see [`Rows::writer`].
+pub struct RowWriter {
+    rows: Rows,
+    format: Format,
+    out: BufWriter<StdoutLock<'static>>,
+}
+
+impl RowWriter {
+    pub fn push<I, T>(&mut self, row: I) -> Result<()>
+    where
+        I: IntoIterator<Item = T>,
+        T: Display,
+    {
+        if self.format == Format::Markdown {
+            self.rows.push(row);
+            return Ok(());
+        }
+        let row: Vec<String> = row.into_iter().map(|v| v.to_string()).collect();
+        self.out
+            .write_all(self.rows.line(&row, self.format).as_bytes())?;
+        Ok(())
+    }
+
+    pub fn finish(mut self) -> Result<()> {
+        if self.format == Format::Markdown {
+            self.out
+                .write_all(self.rows.render(self.format).as_bytes())?;
+        }
+        self.out.flush()?;
+        Ok(())
+    }
+}
+
+/// A cell as a JSON value: literals in typed columns, strings otherwise.
+fn json_cell(s: &str, typed: bool) -> Value {
+    if !typed {
+        return Value::String(s.to_string());
+    }
+    if s.is_empty() {
+        return Value::Null;
+    }
+    match serde_json::from_str::<Value>(s) {
+        Ok(v @ (Value::Number(_) | Value::Bool(_))) => v,
+        _ => Value::String(s.to_string()),
+    }
+}
+
+/// CSV quotes fields per RFC 4180; TSV has no quoting, so tabs and line
+/// breaks become spaces.
+fn cell(s: &str, format: Format) -> String {
+    if format == Format::Tsv {
+        return s.replace(['\t', '\n', '\r'], " ");
+    }
+    if s.contains([',', '"', '\n', '\r']) {
+        format!("\"{}\"", s.replace('"', "\"\""))
+    } else {
+        s.to_string()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn csv_quotes_fields_that_need_it() {
+        let mut rows = Rows::new(&["author", "count"]);
+        rows.push(["Doe, Jane <j@x.io>", "3"]);
+        rows.push(["say \"hi\"", "1"]);
+        rows.push(["two\nlines", "2"]);
+        assert_eq!(
+            rows.render(Format::Csv),
+            "author,count\n\
+             \"Doe, Jane <j@x.io>\",3\n\
+             \"say \"\"hi\"\"\",1\n\
+             \"two\nlines\",2\n"
+        );
+    }
+
+    #[test]
+    fn tsv_flattens_tabs_and_line_breaks() {
+        let mut rows = Rows::new(&["path", "note"]).titled("ignored");
+        rows.push(["a\tb", "x\r\ny"]);
+        rows.push(["c,d", "\"q\""]);
+        assert_eq!(
+            rows.render(Format::Tsv),
+            "path\tnote\na b\tx  y\nc,d\t\"q\"\n"
+        );
+    }
+
+    #[test]
+    fn format_names_are_case_insensitive() {
+        assert_eq!(Format::parse("CSV").unwrap(), Format::Csv);
+        assert_eq!(Format::parse("tsv").unwrap(), Format::Tsv);
+        assert!(Format::parse("xml").is_err());
+        assert!(Format::Csv.uses_rows() && !Format::Table.uses_rows());
+    }
+
+    #[test]
+    fn ndjson_types_only_the_typed_columns() {
+        let mut rows = Rows::new(&["author", "count", "share", "bot"]).typed(&["count", "bot"]);
+        rows.push(["Ann \"A\" <a@x.io>", "3", "0.5", "false"]);
+        rows.push(["42", "", "", "maybe"]);
+        assert_eq!(
+            rows.render(Format::Ndjson),
+            "{\"author\":\"Ann \\\"A\\\" <a@x.io>\",\"count\":3,\"share\":\"0.5\",\"bot\":false}\n\
+             {\"author\":\"42\",\"count\":null,\"share\":\"\",\"bot\":\"maybe\"}\n"
+        );
+    }
+}
@@ -0,0 +1,618 @@
+//! Self-contained HTML dashboard for `report`: inline CSS and SVG only —
+//! no scripts, web fonts or external references — so the file opens
+//! offline and can be mailed as an attachment.
+
+use chrono::{Datelike, Local, NaiveDate};
+use std::fmt::Write;
+
+use crate::domain::report::Report;
+use crate::domain::window::TimeWindow;
+use crate::utils::{fmt_credit, fmt_date};
+
+const STYLE: &str = "
+body { font: 14px/1.45 -apple-system, 'Segoe UI', Helvetica, Arial, sans-serif;
+       color: #1f2328; background: #f6f8fa; margin: 0; }
+main { max-width: 1040px; margin: 0 auto; padding: 24px; }
+h1 { margin: 0 0 4px; font-size: 26px; }
+h2 { margin: 0 0 12px; font-size: 18px; }
+.sub { color: #59636e; margin-bottom: 20px; }
+section { background: #fff; border: 1px solid #d1d9e0; border-radius: 8px;
+          padding: 16px 20px; margin-bottom: 20px; }
+.cards { display: grid; grid-template-columns: repeat(auto-fill, minmax(150px, 1fr));
+         gap: 12px; }
+.card { border: 1px solid #d1d9e0; border-radius: 6px; padding: 10px 12px; }
+.card b { display: block; font-size: 20px; }
+.card span { color: #59636e; font-size: 12px; }
+table { border-collapse: collapse; width: 100%; margin-top: 8px; }
+th, td { text-align: left; padding: 4px 8px; border-bottom: 1px solid #eaeef2; }
+th { font-weight: 600; color: #59636e; }
+td.n { text-align: right; font-variant-numeric: tabular-nums; }
+.cols { display: grid; grid-template-columns: 1fr 1fr; gap: 20px; }
+svg { display: block; max-width: 100%; height: auto; }
+svg text { font: 11px -apple-system, 'Segoe UI', Helvetica, Arial, sans-serif; fill: #59636e; }
+.note { color: #59636e; font-size: 12px; }
+";
+
+const BAR: &str = "#0969da";
+const WIDTH: f64 = 960.0;
+
+/// The whole dashboard as one HTML document.
+pub fn report(
+    r: &Report,
+    repo_name: &str,
+    window: &TimeWindow,
+    ownership_mode: &str,
+    limit: usize,
+) -> String {
+    let s = &r.scan.summary;
+    let mut body = String::new();
+
+    let _ = write!(
+        body,
+        "<h1>{}</h1><div class=\"sub\">{} · generated {}</div>",
+        esc(repo_name),
+        esc(&window.to_string()),
+        Local::now().format("%Y-%m-%d %H:%M")
+    );
+
+    // Summary cards
+    let cards = [
+        (s.total_commits.to_string(), "commits"),
+        (s.contributors_total.to_string(), "contributors"),
+        (format!("{} days", s.active_days), "active period"),
+        (format!("{:.2}", s.avg_commits_per_day), "commits / day"),
+        (format!("{:.1}%", s.momentum_90d_pct), "momentum (90d)"),
+        (
+            s.active_authors_last_90d.to_string(),
+            "authors active (90d)",
+        ),
+        (format!("{:.0}%", s.drive_by_ratio), "drive-by ratio"),
+        (s.core_size_80pct.to_string(), "core size (80%)"),
+        (format!("{:.2} / {:.2}", s.hhi, s.gini), "HHI / Gini"),
+        (format!("{:.0}%", s.merge_rate), "merge rate"),
+        (format!("{:.0}%", s.work_hours_pct), "work hours (09–18)"),
+        (
+            format!("{} days", s.longest_idle_gap_days),
+            "longest idle gap",
+        ),
+    ];
+    body.push_str("<section><h2>Summary</h2><div class=\"cards\">");
+    for (value, label) in cards {
+        let _ = write!(
+            body,
+            "<div class=\"card\"><b>{}</b><span>{}</span></div>",
+            esc(&value),
+            label
+        );
+    }
+    let _ = write!(
+        body,
+        "</div><p class=\"note\">First commit {} by {} · last commit {} by {}</p></section>",
+        fmt_date(s.first_date),
+        esc(&s.first_author),
+        fmt_date(s.last_date),
+        esc(&s.last_author)
+    );
+
+    // Commits over time
+    let (labels, values) = monthly_series(&r.monthly);
+    body.push_str("<section><h2>Commits per month</h2>");
+    body.push_str(&bar_chart(&labels, &values, 220.0));
+    body.push_str("</section>");
+
+    // Weekdays
+    let days = ["Mon", "Tue", "Wed", "Thu", "Fri", "Sat", "Sun"];
+    let labels: Vec<String> = days.iter().map(|d| d.to_string()).collect();
+    let values: Vec<f64> = s.weekday_counts.iter().map(|n| *n as f64).collect();
+    body.push_str("<section><h2>Commits by weekday</h2>");
+    body.push_str(&bar_chart(&labels, &values, 180.0));
+    body.push_str("</section>");
+
+    // Ownership
+    let _ = write!(
+        body,
+        "<section><h2>Ownership</h2><p class=\"note\">Files sized by {}, coloured by the top \
+         author's share (green = shared, red = one person). {} mode.</p>",
+        if ownership_mode.starts_with("FAST") {
+            "touches"
+        } else {
+            "lines"
+        },
+        esc(ownership_mode)
+    );
+    body.push_str(&ownership_treemap(r));
+    let rows: Vec<Vec<String>> = r
+        .ownership
+        .iter()
+        .take(limit)
+        .map(|b| {
+            vec![
+                b.file.clone(),
+                b.top_author.clone(),
+                format!("{:.1}%", b.ratio * 100.0),
+                b.total.to_string(),
+            ]
+        })
+        .collect();
+    body.push_str(&table(&["File", "Top owner", "Ownership", "Total"], &rows));
+    body.push_str("</section>");
+
+    // Churn
+    let _ = write!(
+        body,
+        "<section><h2>Churn hotspots</h2><p class=\"note\">{}; lines added + deleted, \
+         newer changes weigh more.</p>",
+        esc(&r.churn_window.to_string())
+    );
+    let items: Vec<(String, f64)> = r
+        .churn
+        .iter()
+        .take(limit)
+        .map(|e| (e.path.clone(), e.churn))
+        .collect();
+    if items.is_empty() {
+        body.push_str("<p class=\"note\">No changes in this window.</p>");
+    } else {
+        body.push_str(&hbar_chart(&items));
+    }
+    body.push_str("</section>");
+
+    // Authors
+    body.push_str("<section class=\"cols\"><div><h2>Top authors</h2>");
+    let rows: Vec<Vec<String>> = r
+        .scan
+        .stats
+        .sorted(true)
+        .into_iter()
+        .take(limit)
+        .map(|(a, m)| {
+            vec![
+                a.clone(),
+                fmt_credit(m.count),
+                fmt_date(m.first),
+                fmt_date(m.last),
+            ]
+        })
+        .collect();
+    body.push_str(&table(&["Author", "Commits", "First", "Last"], &rows));
+    if !r.scan.stats.bots.is_empty() {
+        body.push_str("<h2 style=\"margin-top:16px\">Bots (not counted above)</h2>");
+        let mut bots: Vec<_> = r.scan.stats.bots.iter().collect();
+        bots.sort_by_key(|(_, m)| std::cmp::Reverse(m.commits));
+        let rows: Vec<Vec<String>> = bots
+            .into_iter()
+            .map(|(b, m)| {
+                vec![
+                    b.clone(),
+                    m.commits.to_string(),
+                    fmt_date(m.first),
+                    fmt_date(m.last),
+                ]
+            })
+            .collect();
+        body.push_str(&table(&["Bot", "Commits", "First", "Last"], &rows));
+    }
+    body.push_str("</div><div><h2>Newest contributors</h2>");
+    let mut firsts: Vec<_> = r.first_commits.iter().collect();
+    firsts.sort_by(|a, b| b.1.cmp(a.1));
+    let rows: Vec<Vec<String>> = firsts
+        .into_iter()
+        .take(limit)
+        .map(|(a, d)| vec![a.clone(), fmt_date(*d)])
+        .collect();
+    body.push_str(&table(&["Author", "First commit"], &rows));
+    body.push_str("</div></section>");
+
+    // Commit times
+    body.push_str("<section><h2>Time of day</h2>");
+    let buckets = ["morning", "afternoon", "evening", "night"];
+    let mut by_total: Vec<_> = r
+        .commit_times
+        .iter()
+        .map(|(a, b)| (a, b, b.values().sum::<usize>()))
+        .collect();
+    by_total.sort_by_key(|t| std::cmp::Reverse(t.2));
+    let rows: Vec<Vec<String>> = by_total
+        .into_iter()
+        .take(limit)
+        .map(|(a, b, _)| {
+            std::iter::once(a.clone())
+                .chain(
+                    buckets
+                        .iter()
+                        .map(|k| b.get(*k).copied().unwrap_or(0).to_string()),
+                )
+                .collect()
+        })
+        .collect();
+    body.push_str(&table(
+        &[
+            "Author",
+            "Morning (6–12)",
+            "Afternoon (12–18)",
+            "Evening (18–24)",
+            "Night (0–6)",
+        ],
+        &rows,
+    ));
+    body.push_str("</section>");
+
+    // Co-authors
+    body.push_str("<section class=\"cols\"><div><h2>Shared files</h2>");
+    body.push_str(&table(
+        &["Pair", "Files"],
+        &top_pairs(&r.coauthors.shared_files, limit),
+    ));
+    body.push_str("</div><div><h2>Co-authored-by pairs</h2>");
+    if r.coauthors.trailers.is_empty() {
+        body.push_str("<p class=\"note\">No Co-authored-by trailers.</p>");
+    } else {
+        body.push_str(&table(
+            &["Pair", "Commits"],
+            &top_pairs(&r.coauthors.trailers, limit),
+        ));
+    }
+    body.push_str("</div></section>");
+
+    body.push_str(
+        "<p class=\"note\">Drive-by ratio = authors with ≤2 commits. Core size (80%) = minimal \
+         number of authors covering 80% of commits. HHI/Gini = contribution concentration \
+         (higher → more concentrated).</p>",
+    );
+
+    format!(
+        "<!DOCTYPE html>\n<html lang=\"en\"><head><meta charset=\"utf-8\">\
+         <meta name=\"viewport\" content=\"width=device-width, initial-scale=1\">\
+         <title>gitrivia — {}</title><style>{STYLE}</style></head>\
+         <body><main>{body}</main></body></html>\n",
+        esc(repo_name)
+    )
+}
+
+fn esc(s: &str) -> String {
+    s.replace('&', "&amp;")
+        .replace('<', "&lt;")
+        .replace('>', "&gt;")
+        .replace('"', "&quot;")
+}
+
+fn table(header: &[&str], rows: &[Vec<String>]) -> String {
+    let mut out = String::from("<table><tr>");
+    for h in header {
+        let _ = write!(out, "<th>{}</th>", esc(h));
+    }
+    out.push_str("</tr>");
+    for row in rows {
+        out.push_str("<tr>");
+        for c in row {
+            let numeric = c.ends_with('%') || c.parse::<f64>().is_ok();
+            let class = if numeric { " class=\"n\"" } else { "" };
+            let _ = write!(out, "<td{class}>{}</td>", esc(c));
+        }
+        out.push_str("</tr>");
+    }
+    out.push_str("</table>");
+    out
+}
+
+fn top_pairs(pairs: &std::collections::BTreeMap<String, usize>, limit: usize) -> Vec<Vec<String>> {
+    let mut v: Vec<_> = pairs.iter().collect();
+    v.sort_by(|a, b| b.1.cmp(a.1));
+    v.into_iter()
+        .take(limit)
+        .map(|(p, n)| vec![p.clone(), n.to_string()])
+        .collect()
+}
+
+/// Every month from the first to the last commit, zeros included.
+fn monthly_series(
+    monthly: &std::collections::BTreeMap<NaiveDate, usize>,
+) -> (Vec<String>, Vec<f64>) {
+    let (Some(first), Some(last)) = (monthly.keys().next(), monthly.keys().next_back()) else {
+        return (Vec::new(), Vec::new());
+    };
+    let (mut labels, mut values) = (Vec::new(), Vec::new());
+    let mut m = *first;
+    while m <= *last {
+        labels.push(m.format("%Y-%m").to_string());
+        values.push(monthly.get(&m).copied().unwrap_or(0) as f64);
+        m = match m.month() {
+            12 => NaiveDate::from_ymd_opt(m.year() + 1, 1, 1),
+            n => NaiveDate::from_ymd_opt(m.year(), n + 1, 1),
+        }
+        .expect("valid month");
+    }
+    (labels, values)
+}
+
+/// Vertical bars with a tooltip each; labels thinned out to fit.
+fn bar_chart(labels: &[String], values: &[f64], height: f64) -> String {
+    if values.is_empty() {
+        return "<p class=\"note\">No commits.</p>".into();
+    }
+    let (left, bottom, top) = (36.0, 22.0, 8.0);
+    let plot_w = WIDTH - left - 8.0;
+    let plot_h = height - bottom - top;
+    let max = values.iter().cloned().fold(0.0, f64::max).max(1.0);
+    let step = plot_w / values.len() as f64;
+    let gap = (step * 0.15).min(4.0);
+    let every = (values.len() as f64 / 12.0).ceil().max(1.0) as usize;
+
+    let mut out = format!("<svg viewBox=\"0 0 {WIDTH} {height}\" role=\"img\">");
+    for frac in [0.0, 0.5, 1.0] {
+        let y = top + plot_h * (1.0 - frac);
+        let _ = write!(
+            out,
+            "<line x1=\"{left}\" x2=\"{}\" y1=\"{y:.1}\" y2=\"{y:.1}\" stroke=\"#eaeef2\"/>\
+             <text x=\"{}\" y=\"{:.1}\" text-anchor=\"end\">{}</text>",
+            WIDTH - 8.0,
+            left - 4.0,
+            y + 4.0,
+            (max * frac).round()
+        );
+    }
+    for (i, (label, v)) in labels.iter().zip(values).enumerate() {
+        let h = plot_h * v / max;
+        let x = left + i as f64 * step;
+        let _ = write!(
+            out,
+            "<rect x=\"{:.1}\" y=\"{:.1}\" width=\"{:.1}\" height=\"{h:.1}\" fill=\"{BAR}\">\
+             <title>{}: {v}</title></rect>",
+            x + gap / 2.0,
+            top + plot_h - h,
+            (step - gap).max(0.5),
+            esc(label)
+        );
+        if i % every == 0 {
+            let _ = write!(
+                out,
+                "<text x=\"{:.1}\" y=\"{}\" text-anchor=\"middle\">{}</text>",
+                x + step / 2.0,
+                height - 6.0,
+                esc(label)
+            );
+        }
+    }
+    out.push_str("</svg>");
+    out
+}
+
+/// Horizontal bars, one labelled row per item.
+fn hbar_chart(items: &[(String, f64)]) -> String {
+    let (row, label_w) = (22.0, 360.0);
+    let height = row * items.len() as f64 + 4.0;
+    let max = items
+        .iter()
+        .map(|i| i.1)
+        .fold(0.0, f64::max)
+        .max(f64::EPSILON);
+    let plot_w = WIDTH - label_w - 60.0;
+
+    let mut out = format!("<svg viewBox=\"0 0 {WIDTH} {height}\" role=\"img\">");
+    for (i, (label, v)) in items.iter().enumerate() {
+        let y = i as f64 * row + 2.0;
+        let w = plot_w * v / max;
+        let _ = write!(
+            out,
+            "<text x=\"{:.1}\" y=\"{:.1}\" text-anchor=\"end\">{}</text>\
+             <rect x=\"{label_w}\" y=\"{y:.1}\" width=\"{w:.1}\" height=\"{:.1}\" fill=\"{BAR}\">\
+             <title>{}: {v:.1}</title></rect>\
+             <text x=\"{:.1}\" y=\"{:.1}\">{v:.1}</text>",
+            label_w - 6.0,
+            y + row * 0.65,
+            esc(&shorten(label, 56)),
+            row - 6.0,
+            esc(label),
+            label_w + w + 4.0,
+            y + row * 0.65
+        );
+    }
+    out.push_str("</svg>");
+    out
+}
+
+/// Treemap of the largest files: area = size, colour = top owner's share.
+fn ownership_treemap(r: &Report) -> String {
+    let mut files: Vec<_> = r.ownership.iter().filter(|b| b.total > 0).collect();
+    if files.is_empty() {
+        return "<p class=\"note\">No files above the size threshold.</p>".into();
+    }
+    files.sort_by_key(|b| std::cmp::Reverse(b.total));
+    files.truncate(80);
+
+    let height = 420.0;
+    let sizes: Vec<f64> = files.iter().map(|b| b.total as f64).collect();
+    let rects = squarify(
+        &sizes,
+        Rect {
+            x: 0.0,
+            y: 0.0,
+            w: WIDTH,
+            h: height,
+        },
+    );
+
+    let mut out = format!("<svg viewBox=\"0 0 {WIDTH} {height}\" role=\"img\">");
+    for (b, rect) in files.iter().zip(rects) {
+        // 0.5 share (or less) → green, 1.0 → red.
+        let hue = 120.0 * (1.0 - ((b.ratio - 0.5) * 2.0).clamp(0.0, 1.0));
+        let _ = write!(
+            out,
+            "<rect x=\"{:.1}\" y=\"{:.1}\" width=\"{:.1}\" height=\"{:.1}\" \
+             fill=\"hsl({hue:.0},60%,62%)\" stroke=\"#fff\">\
+             <title>{} — {} owns {:.1}% of {}</title></rect>",
+            rect.x,
+            rect.y,
+            rect.w,
+            rect.h,
+            esc(&b.file),
+            esc(&b.top_author),
+            b.ratio * 100.0,
+            b.total
+        );
+        let name = b.file.rsplit('/').next().unwrap_or(&b.file);
+        let fits = (rect.w / 6.5) as usize;
+        if rect.h >= 16.0 && fits >= 4 {
+            let _ = write!(
+                out,
+                "<text x=\"{:.1}\" y=\"{:.1}\" style=\"fill:#1f2328\">{}</text>",
+                rect.x + 4.0,
+                rect.y + 13.0,
+                esc(&shorten(name, fits))
+            );
+        }
+    }
+    out.push_str("</svg>");
+    out
+}
+
+fn shorten(s: &str, max: usize) -> String {
+    if s.chars().count() <= max {
+        return s.to_string();
+    }
+    let tail: String = s.chars().rev().take(max.saturating_sub(1)).collect();
+    format!("…{}", tail.chars().rev().collect::<String>())
+}
+
+#[derive(Debug, Clone, Copy)]
+struct Rect {
+    x: f64,
+    y: f64,
+    w: f64,
+    h: f64,
+}
+
+/// Squarified treemap layout (Bruls et al.) of `sizes`, largest first.
+fn squarify(sizes: &[f64], area: Rect) -> Vec<Rect> {
+    let total: f64 = sizes.iter().sum();
+    if total <= 0.0 {
+        return Vec::new();
+    }
+    let scale = area.w * area.h / total;
+    let areas: Vec<f64> = sizes.iter().map(|s| s * scale).collect();
+
+    let mut out = Vec::with_capacity(areas.len());
+    let mut free = area;
+    let mut row: Vec<f64> = Vec::new();
+    for &a in &areas {
+        let side = free.w.min(free.h);
+        let mut with = row.clone();
+        with.push(a);
+        if row.is_empty() || worst(&with, side) <= worst(&row, side) {
+            row = with;
+        } else {
+            free = lay_row(&row, free, &mut out);
+            row = vec![a];
+        }
+    }
+    if !row.is_empty() {
+        lay_row(&row, free, &mut out);
+    }
+    out
+}
+
+/// Worst aspect ratio of a row laid along a side of length `side`.
+fn worst(row: &[f64], side: f64) -> f64 {
+    let sum: f64 = row.iter().sum();
+    let max = row.iter().cloned().fold(0.0, f64::max);
+    let min = row.iter().cloned().fold(f64::INFINITY, f64::min);
+    let s2 = side * side;
+    (s2 * max / (sum * sum)).max(sum * sum / (s2 * min))
+}
+
+/// Place `row` along the shorter side of `free`; returns what is left.
+fn lay_row(row: &[f64], free: Rect, out: &mut Vec<Rect>) -> Rect {
+    let sum: f64 = row.iter().sum();
+    if free.w >= free.h {
+        let w = sum / free.h;
+        let mut y = free.y;
+        for a in row {
+            let h = a / w;
+            out.push(Rect { x: free.x, y, w, h });
+            y += h;
+        }
+        Rect {
+            x: free.x + w,
+            w: free.w - w,
+            ..free
+        }
+    } else {
+        let h = sum / free.w;
+        let mut x = free.x;
+        for a in row {
+            let w = a / h;
+            out.push(Rect { x, y: free.y, w, h });
+            x += w;
+        }
+        Rect {
+            y: free.y + h,
+            h: free.h - h,
+            ..free
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::domain::bus_factor::ScanOpts;
+    use crate::domain::git::RepoOptions;
+    use crate::domain::report::{collect, ReportOpts};
+    use git2::{Repository, Signature};
+
+    /// A dashboard for a one-commit repository whose file name and author
+    /// need escaping; `hostile` replaces the author where git itself
+    /// would refuse it.
+    fn page(hostile: &str) -> String {
+        let dir = tempfile::TempDir::new().unwrap();
+        let repo = Repository::init(dir.path()).unwrap();
+        let file = "R&D/<notes>.rs";
+        std::fs::create_dir_all(dir.path().join("R&D")).unwrap();
+        std::fs::write(dir.path().join(file), "fn a() {}\n").unwrap();
+        let mut index = repo.index().unwrap();
+        index.add_path(std::path::Path::new(file)).unwrap();
+        let tree = repo.find_tree(index.write_tree().unwrap()).unwrap();
+        let sig = Signature::now("Tom & Jerry", "tj@x.io").unwrap();
+        repo.commit(Some("HEAD"), &sig, &sig, "add", &tree, &[])
+            .unwrap();
+
+        let repo = RepoOptions::default()
+            .no_cache(true)
+            .open(dir.path().to_str().unwrap())
+            .unwrap();
+        let scan = ScanOpts::default().min_total(1);
+        let opts = ReportOpts {
+            window_days: 90,
+            fast: false,
+            max_commits: None,
+            ownership: scan.clone(),
+            churn: scan,
+        };
+        let mut r = collect(&repo, &opts).unwrap();
+        assert_eq!(r.ownership.len(), 1);
+        r.ownership[0].top_author = hostile.into();
+        r.scan.summary.last_author = hostile.into();
+        report(&r, "<repo>", repo.window(), "Blame (lines)", 15)
+    }
+
+    #[test]
+    fn the_page_loads_nothing_from_outside() {
+        let page = page("Ann");
+        for external in [
+            "src=", "href=", "url(", "@import", "<script", "<link", "://",
+        ] {
+            assert!(!page.contains(external), "found {external}");
+        }
+    }
+
+    #[test]
+    fn names_and_paths_are_escaped() {
+        let page = page("<script>alert(1)</script>");
+        assert!(!page.contains("<script>"));
+        assert!(page.contains("&lt;script&gt;alert(1)&lt;/script&gt;"));
+        assert!(page.contains("R&amp;D/&lt;notes&gt;.rs"));
+        assert!(page.contains("Tom &amp; Jerry"));
+        assert!(!page.contains("Tom & Jerry"));
+        assert!(page.contains("<title>gitrivia — &lt;repo&gt;</title>"));
+    }
+}
@@ -0,0 +1,178 @@
+//! The versioned envelope every `--json` payload is printed in.
+
+use anyhow::Result;
+use chrono::{DateTime, Local, NaiveDate};
+use clap::Command;
+use serde::Serialize;
+use serde_json::{json, Value};
+
+use crate::commands::Global;
+use crate::domain::{bots::BotMeta, git::RepoExt, stats::AuthorMeta, stats::CommitStats};
+use crate::presentation::schema::{self, schema, Schema, DIALECT};
+
+/// Bumped whenever a payload changes incompatibly (a field removed,
+/// renamed or retyped). New fields do not bump it.
+pub const SCHEMA_VERSION: u32 = 1;
+
+/// Effective options: the global flags and the command's own, after
+/// config files and defaults were applied.
+#[derive(Debug, Serialize)]
+pub struct Options<'a, A: Serialize> {
+    pub global: &'a Value,
+    pub command: &'a A,
+}
+
+#[derive(Debug, Serialize)]
+pub struct Envelope<'a, A: Serialize, T: Serialize> {
+    pub schema_version: u32,
+    pub command: &'a str,
+    /// Work tree (or bare repository) the command looked at.
+    pub repo: Option<String>,
+    /// Tip the history was read from (`--rev`, else `HEAD`); `null` when
+    /// the window selects no commits.
+    pub head_oid: Option<String>,
+    pub generated_at: DateTime<Local>,
+    pub options: Options<'a, A>,
+    #[serde(flatten)]
+    pub data: &'a T,
+}
+
+impl<'a, A: Serialize, T: Serialize> Envelope<'a, A, T> {
+    pub fn new(
+        g: &'a Global,
+        command: &'a str,
+        repo: Option<&RepoExt>,
+        args: &'a A,
+        data: &'a T,
+    ) -> Self {
+        Self {
+            schema_version: SCHEMA_VERSION,
+            command,
+            repo: repo.map(|r| {
+                let r = r.repo();
+                let dir = r.workdir().unwrap_or_else(|| r.path());
+                let dir = dir.canonicalize().unwrap_or_else(|_| dir.to_path_buf());
+                dir.display().to_string()
+            }),
+            head_oid: repo.filter(|r| !r.is_empty()).map(|r| r.tip().to_string()),
+            generated_at: Local::now(),
+            options: Options {
+                global: &g.options,
+                command: args,
+            },
+            data,
+        }
+    }
+}
+
+/// Print `data` for `command` inside the envelope.
+pub fn print<A: Serialize, T: Serialize>(
+    g: &Global,
+    command: &str,
+    repo: Option<&RepoExt>,
+    args: &A,
+    data: &T,
+) -> Result<()> {
+    let envelope = Envelope::new(g, command, repo, args, data);
+    match &g.capture {
+        Some(capture) => *capture.lock().expect("capture") = serde_json::to_string(&envelope)?,
+        None => println!("{}", serde_json::to_string_pretty(&envelope)?),
+    }
+    Ok(())
+}
+
+/// JSON Schema document for `command`'s output with payload `T`.
+pub fn document<T: Schema>(cli: &Command, command: &Command) -> Value {
+    let data = T::schema();
+    let mut doc = json!({
+        "$schema": DIALECT,
+        "title": format!("gitrivia {} --json (schema v{SCHEMA_VERSION})", command.get_name()),
+        "type": "object",
+        "properties": {
+            "schema_version": { "const": SCHEMA_VERSION },
+            "command": { "const": command.get_name() },
+            "repo": { "type": ["string", "null"] },
+            "head_oid": { "type": ["string", "null"], "pattern": "^[0-9a-f]{40,64}$" },
+            "generated_at": { "type": "string", "format": "date-time" },
+            "options": schema::object("options", vec![
+                ("global", schema::options(cli, true)),
+                ("command", schema::options(command, false)),
+            ]),
+        },
+        "additionalProperties": false,
+    });
+    let props = doc["properties"].as_object_mut().expect("object");
+    for (k, v) in data["properties"].as_object().into_iter().flatten() {
+        props.insert(k.clone(), v.clone());
+    }
+    let required: Vec<_> = props.keys().cloned().collect();
+    doc["required"] = json!(required);
+    doc
+}
+
+/// One author's commit credit and active range.
+#[derive(Debug, Serialize)]
+pub struct AuthorRow {
+    pub author: String,
+    pub commits: f64,
+    pub first: NaiveDate,
+    pub last: NaiveDate,
+}
+schema!(AuthorRow {
+    author: String,
+    commits: f64,
+    first: NaiveDate,
+    last: NaiveDate,
+});
+
+impl AuthorRow {
+    pub fn new(author: &str, m: &AuthorMeta) -> Self {
+        Self {
+            author: author.to_string(),
+            commits: m.count,
+            first: m.first.date_naive(),
+            last: m.last.date_naive(),
+        }
+    }
+
+    /// The `n` busiest authors (`usize::MAX` for all), in `desc` order.
+    pub fn top(stats: &CommitStats, desc: bool, n: usize) -> Vec<Self> {
+        stats
+            .sorted(desc)
+            .into_iter()
+            .take(n)
+            .map(|(a, m)| Self::new(a, m))
+            .collect()
+    }
+}
+
+/// A bot reported apart from the authors (`--bots separate`).
+#[derive(Debug, Serialize)]
+pub struct BotRow {
+    pub author: String,
+    pub commits: usize,
+    pub first: NaiveDate,
+    pub last: NaiveDate,
+}
+schema!(BotRow {
+    author: String,
+    commits: usize,
+    first: NaiveDate,
+    last: NaiveDate,
+});
+
+impl BotRow {
+    /// Separately reported bots, busiest first.
+    pub fn all(stats: &CommitStats) -> Vec<Self> {
+        let mut bots: Vec<(&String, &BotMeta)> = stats.bots.iter().collect();
+        bots.sort_by_key(|(_, m)| std::cmp::Reverse(m.commits));
+        bots.into_iter()
+            .map(|(name, m)| Self {
+                author: name.clone(),
+                commits: m.commits,
+                first: m.first.date_naive(),
+                last: m.last.date_naive(),
+            })
+            .collect()
+    }
+}
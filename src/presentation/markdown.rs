@@ -0,0 +1,228 @@
+//! GitHub-flavored Markdown for pasting into issues, PR comments and
+//! wikis: plain headings and pipe tables, no emoji or box drawing.
+
+use crate::domain::{
+    stats::{CommitStats, RepoScan},
+    window::TimeWindow,
+};
+use crate::utils::{fmt_credit, fmt_date};
+
+pub fn heading(level: usize, text: &str) -> String {
+    format!("{} {}\n\n", "#".repeat(level), escape(text))
+}
+
+/// A pipe table; `snake_case` headers become `Snake case`.
+pub fn table(header: &[String], rows: &[Vec<String>]) -> String {
+    let mut out = String::new();
+    let names: Vec<_> = header.iter().map(|h| escape(&title_case(h))).collect();
+    out.push_str(&format!("| {} |\n", names.join(" | ")));
+    out.push_str(&format!("|{}\n", " --- |".repeat(header.len())));
+    for row in rows {
+        let cells: Vec<_> = row.iter().map(|c| escape(c)).collect();
+        out.push_str(&format!("| {} |\n", cells.join(" | ")));
+    }
+    out.push('\n');
+    out
+}
+
+/// Keep `|` inside cells and `Name <email>` visible as text.
+fn escape(s: &str) -> String {
+    let mut out = String::with_capacity(s.len());
+    for c in s.chars() {
+        if matches!(c, '\\' | '|' | '*' | '_' | '`' | '[' | ']') {
+            out.push('\\');
+        }
+        out.push(c);
+    }
+    out.replace('&', "&amp;")
+        .replace('<', "&lt;")
+        .replace('>', "&gt;")
+        .replace(['\n', '\r'], " ")
+}
+
+fn title_case(s: &str) -> String {
+    let s = s.replace('_', " ");
+    let mut chars = s.chars();
+    match chars.next() {
+        Some(c) => c.to_uppercase().chain(chars).collect(),
+        None => s,
+    }
+}
+
+fn metric_table(rows: &[(&str, String)]) -> String {
+    let rows: Vec<_> = rows
+        .iter()
+        .map(|(k, v)| vec![k.to_string(), v.clone()])
+        .collect();
+    table(&["metric".into(), "value".into()], &rows)
+}
+
+fn author_table(stats: &CommitStats, n: usize) -> String {
+    let rows: Vec<_> = stats
+        .sorted(true)
+        .into_iter()
+        .take(n)
+        .map(|(a, m)| {
+            vec![
+                a.clone(),
+                fmt_credit(m.count),
+                fmt_date(m.first),
+                fmt_date(m.last),
+            ]
+        })
+        .collect();
+    let header = ["author", "commits", "first", "last"].map(String::from);
+    table(&header, &rows)
+}
+
+/// The `stats` report; the legend becomes footnotes on the metrics it
+/// explains.
+pub fn stats_report(scan: &RepoScan, window: &TimeWindow) -> String {
+    let s = &scan.summary;
+    let mut out = heading(2, "Repository summary");
+    if !window.is_unbounded() {
+        out.push_str(&format!("Window: {}\n\n", escape(&window.to_string())));
+    }
+
+    let mut summary = vec![
+        (
+            "First commit",
+            format!("{} by {}", fmt_date(s.first_date), s.first_author),
+        ),
+        (
+            "Last commit",
+            format!("{} by {}", fmt_date(s.last_date), s.last_author),
+        ),
+        ("Total commits", s.total_commits.to_string()),
+        ("Contributors", s.contributors_total.to_string()),
+        ("Active period", format!("{} days", s.active_days)),
+        ("Avg commits/day", format!("{:.2}", s.avg_commits_per_day)),
+    ];
+    if let Some(p) = s.peak_day {
+        summary.push(("Peak day", format!("{} ({} commits)", p.date, p.commits)));
+    }
+    summary.push((
+        "Longest idle gap",
+        format!("{} days", s.longest_idle_gap_days),
+    ));
+    summary.push((
+        "Momentum (90d)",
+        format!(
+            "{:.1}% of all commits, {} authors active",
+            s.momentum_90d_pct, s.active_authors_last_90d
+        ),
+    ));
+    if let Some(t) = &s.top_recent_30d {
+        summary.push((
+            "Top last 30d",
+            format!("{} ({} commits)", t.author, fmt_credit(t.commits)),
+        ));
+    }
+    out.push_str(&metric_table(&summary));
+
+    out.push_str(&heading(3, "Contributors"));
+    out.push_str(&metric_table(&[
+        (
+            "Drive-by ratio[^drive-by]",
+            format!("{:.0}%", s.drive_by_ratio),
+        ),
+        ("Core size (80%)[^core]", s.core_size_80pct.to_string()),
+        ("HHI[^concentration]", format!("{:.2}", s.hhi)),
+        ("Gini[^concentration]", format!("{:.2}", s.gini)),
+    ]));
+
+    out.push_str(&heading(3, "Activity patterns"));
+    let wc = s.weekday_counts;
+    let wc_total = wc.iter().sum::<usize>().max(1) as f64;
+    let days = ["Mon", "Tue", "Wed", "Thu", "Fri", "Sat", "Sun"];
+    let mut activity: Vec<_> = days
+        .iter()
+        .zip(wc)
+        .map(|(d, n)| (*d, format!("{:.1}%", 100.0 * n as f64 / wc_total)))
+        .collect();
+    activity.push(("Work hours (09–18)", format!("{:.0}%", s.work_hours_pct)));
+    out.push_str(&metric_table(&activity));
+
+    out.push_str(&heading(3, "Merge/Revert"));
+    out.push_str(&metric_table(&[
+        ("Merge rate", format!("{:.0}%", s.merge_rate)),
+        ("Revert rate", format!("{:.1}%", s.revert_rate)),
+    ]));
+
+    out.push_str(&heading(3, "Messages"));
+    out.push_str(&metric_table(&[
+        (
+            "Median subject length",
+            format!("{} chars", s.msg_median_len),
+        ),
+        ("With body", format!("{:.0}%", s.msg_body_pct)),
+        ("Conventional commits", format!("{:.0}%", s.conv_commit_pct)),
+    ]));
+
+    out.push_str(&heading(3, "Top 5 authors (last 12 months)"));
+    if scan.recent12.data.is_empty() {
+        out.push_str("No commits in the last 12 months.\n\n");
+    } else {
+        out.push_str(&author_table(&scan.recent12, 5));
+    }
+    out.push_str(&heading(3, "Top 5 authors (all time)"));
+    out.push_str(&author_table(&scan.stats, 5));
+
+    if !scan.stats.bots.is_empty() {
+        out.push_str(&heading(3, "Bots (not counted above)"));
+        let mut bots: Vec<_> = scan.stats.bots.iter().collect();
+        bots.sort_by_key(|(_, m)| std::cmp::Reverse(m.commits));
+        let rows: Vec<_> = bots
+            .into_iter()
+            .map(|(b, m)| {
+                vec![
+                    b.clone(),
+                    m.commits.to_string(),
+                    fmt_date(m.first),
+                    fmt_date(m.last),
+                ]
+            })
+            .collect();
+        let header = ["bot", "commits", "first", "last"].map(String::from);
+        out.push_str(&table(&header, &rows));
+    }
+
+    out.push_str(
+        "[^drive-by]: Authors with ≤2 commits (higher → many one-off contributors).\n\
+         [^core]: Minimal number of authors covering 80% of commits.\n\
+         [^concentration]: Contribution concentration (higher → more concentrated).\n",
+    );
+    out
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn cells_keep_pipes_and_addresses_as_text() {
+        let header = ["author".to_string(), "top_file".to_string()];
+        let rows = vec![vec!["Ann <ann@x.io>".to_string(), "a|b\nc".to_string()]];
+        assert_eq!(
+            table(&header, &rows),
+            "| Author | Top file |\n\
+             | --- | --- |\n\
+             | Ann &lt;ann@x.io&gt; | a\\|b c |\n\n"
+        );
+    }
+
+    #[test]
+    fn headings_are_escaped_too() {
+        assert_eq!(heading(3, "a | <b>"), "### a \\| &lt;b&gt;\n\n");
+        assert_eq!(title_case(""), "");
+    }
+
+    #[test]
+    fn inline_markup_is_literal() {
+        assert_eq!(escape("__init__.py"), "\\_\\_init\\_\\_.py");
+        assert_eq!(escape("src/**/*.rs"), "src/\\*\\*/\\*.rs");
+        assert_eq!(escape("`x` [y](z)"), "\\`x\\` \\[y\\](z)");
+        assert_eq!(escape("a\\b"), "a\\\\b");
+        assert_eq!(escape("R&D &lt;"), "R&amp;D &amp;lt;");
+    }
+}
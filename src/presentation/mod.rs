@@ -1 +1,9 @@
+pub mod codeowners;
+pub mod flat;
+pub mod html;
+pub mod json;
+pub mod markdown;
+pub mod prometheus;
+pub mod schema;
 pub mod table;
+pub mod tui;
@@ -0,0 +1,49 @@
+//! Prometheus text exposition format (version 0.0.4), as scraped from an
+//! HTTP endpoint or read by node_exporter's textfile collector.
+
+use std::collections::BTreeMap;
+
+use crate::domain::metrics::Family;
+
+/// All families, each sample carrying `common` labels before its own.
+pub fn render(families: &[Family], common: &BTreeMap<String, String>) -> String {
+    let mut out = String::new();
+    for f in families {
+        out.push_str(&format!("# HELP {} {}\n", f.name, escape_help(&f.help)));
+        out.push_str(&format!("# TYPE {} {}\n", f.name, f.kind));
+        for s in &f.samples {
+            let labels: Vec<_> = common
+                .iter()
+                .chain(&s.labels)
+                .map(|(k, v)| format!("{k}=\"{}\"", escape_label(v)))
+                .collect();
+            out.push_str(&format!(
+                "{}{{{}}} {}\n",
+                f.name,
+                labels.join(","),
+                value(s.value)
+            ));
+        }
+    }
+    out
+}
+
+fn escape_help(s: &str) -> String {
+    s.replace('\\', "\\\\").replace('\n', "\\n")
+}
+
+fn escape_label(s: &str) -> String {
+    s.replace('\\', "\\\\")
+        .replace('"', "\\\"")
+        .replace('\n', "\\n")
+}
+
+fn value(x: f64) -> String {
+    if x.is_nan() {
+        "NaN".into()
+    } else if x.is_infinite() {
+        if x > 0.0 { "+Inf" } else { "-Inf" }.into()
+    } else {
+        x.to_string()
+    }
+}
@@ -0,0 +1,425 @@
+//! Hand-rolled JSON Schema (draft 2020-12) for the `--json` payloads.
+//!
+//! Payload types implement [`Schema`] through the [`schema!`] macro, which
+//! also destructures the type so a field added, removed or retyped without
+//! updating its schema fails to compile. Option schemas come straight from
+//! the clap definitions.
+
+use chrono::{DateTime, Local, NaiveDate};
+use clap::{ArgAction, Command};
+use serde_json::{json, Map, Value};
+use std::any::TypeId;
+use std::collections::BTreeMap;
+use std::path::PathBuf;
+
+use crate::domain::{
+    age::{PathAge, Survival},
+    blame::{AgeBucket, AuthorShare, LastChange, LineSpan},
+    bus_factor::{BusScore, DirScore},
+    churn::ChurnEntry,
+    codeowners::{Candidate, Issue, OwnerFlag, OwnerRule, OwnersDiff, RuleDiff},
+    compare::{Comparison, Contributor, MetricDelta, RankDelta},
+    metrics::{Family, Sample},
+    reviewers::Reviewer,
+    stats::{PeakDay, RecentTop},
+    trend::TrendPoint,
+    window::TimeWindow,
+};
+
+pub const DIALECT: &str = "https://json-schema.org/draft/2020-12/schema";
+
+/// A type with a JSON Schema describing its `serde` form.
+pub trait Schema {
+    fn schema() -> Value;
+}
+
+/// `impl Schema` for a struct from its field list. Every field must be
+/// listed with its exact type (checked at compile time); all of them are
+/// required, `Option`s may be `null`.
+macro_rules! schema {
+    ($name:ident { $($field:ident: $ty:ty),* $(,)? }) => {
+        impl $crate::presentation::schema::Schema for $name {
+            fn schema() -> serde_json::Value {
+                #[allow(dead_code)]
+                fn check(v: &$name) {
+                    let $name { $($field: _),* } = v;
+                    $(let _: &$ty = &v.$field;)*
+                }
+                $crate::presentation::schema::object(
+                    stringify!($name),
+                    vec![$((
+                        stringify!($field),
+                        <$ty as $crate::presentation::schema::Schema>::schema(),
+                    )),*],
+                )
+            }
+        }
+    };
+}
+pub(crate) use schema;
+
+/// Closed object with every property required.
+pub fn object(title: &str, props: Vec<(&str, Value)>) -> Value {
+    let required: Vec<_> = props.iter().map(|(k, _)| *k).collect();
+    json!({
+        "title": title,
+        "type": "object",
+        "properties": props.into_iter().map(|(k, v)| (k.to_string(), v)).collect::<Map<_, _>>(),
+        "required": required,
+        "additionalProperties": false,
+    })
+}
+
+/// Schema for a string restricted to `values`.
+pub fn one_of(values: &[&str]) -> Value {
+    json!({ "type": "string", "enum": values })
+}
+
+fn nullable(v: Value) -> Value {
+    json!({ "anyOf": [v, { "type": "null" }] })
+}
+
+macro_rules! leaf {
+    ($($ty:ty => $schema:tt),* $(,)?) => {
+        $(impl Schema for $ty {
+            fn schema() -> Value {
+                json!($schema)
+            }
+        })*
+    };
+}
+
+leaf! {
+    bool => { "type": "boolean" },
+    str => { "type": "string" },
+    String => { "type": "string" },
+    PathBuf => { "type": "string" },
+    u32 => { "type": "integer", "minimum": 0 },
+    u64 => { "type": "integer", "minimum": 0 },
+    usize => { "type": "integer", "minimum": 0 },
+    i64 => { "type": "integer" },
+    f64 => { "type": "number" },
+    NaiveDate => { "type": "string", "format": "date" },
+    DateTime<Local> => { "type": "string", "format": "date-time" },
+}
+
+impl<T: Schema + ?Sized> Schema for &T {
+    fn schema() -> Value {
+        T::schema()
+    }
+}
+
+impl<T: Schema> Schema for Option<T> {
+    fn schema() -> Value {
+        nullable(T::schema())
+    }
+}
+
+impl<T: Schema> Schema for Vec<T> {
+    fn schema() -> Value {
+        json!({ "type": "array", "items": T::schema() })
+    }
+}
+
+impl<T: Schema, const N: usize> Schema for [T; N] {
+    fn schema() -> Value {
+        json!({ "type": "array", "items": T::schema(), "minItems": N, "maxItems": N })
+    }
+}
+
+impl<T: Schema> Schema for BTreeMap<String, T> {
+    fn schema() -> Value {
+        json!({ "type": "object", "additionalProperties": T::schema() })
+    }
+}
+
+schema!(TimeWindow {
+    since: Option<DateTime<Local>>,
+    until: Option<DateTime<Local>>,
+});
+schema!(PeakDay {
+    date: NaiveDate,
+    commits: usize,
+});
+schema!(RecentTop {
+    author: String,
+    commits: f64,
+});
+schema!(BusScore {
+    file: String,
+    top_author: String,
+    ratio: f64,
+    total: usize,
+});
+schema!(DirScore {
+    dir: String,
+    top_author: String,
+    ratio: f64,
+    total: usize,
+});
+schema!(ChurnEntry {
+    path: String,
+    churn: f64,
+    adds: usize,
+    dels: usize,
+    touches: usize,
+});
+schema!(TrendPoint {
+    period: String,
+    start: NaiveDate,
+    commits: usize,
+    active_authors: usize,
+    new_authors: usize,
+    core_size_80pct: usize,
+    drive_by_ratio_pct: f64,
+    concentration_hhi: f64,
+    concentration_gini: f64,
+    merge_rate_pct: f64,
+    revert_rate_pct: f64,
+    conventional_commit_pct: f64,
+    body_present_pct: f64,
+    median_subject_len: usize,
+    work_hours_pct: f64,
+});
+schema!(MetricDelta {
+    metric: &'static str,
+    base: f64,
+    current: f64,
+    delta: f64,
+});
+schema!(RankDelta {
+    name: String,
+    rank: usize,
+    base_rank: Option<usize>,
+    value: f64,
+    base_value: f64,
+});
+schema!(Contributor {
+    author: String,
+    commits: f64,
+});
+schema!(Comparison {
+    metrics: Vec<MetricDelta>,
+    new_contributors: Vec<Contributor>,
+    departed_contributors: Vec<Contributor>,
+    top_authors: Vec<RankDelta>,
+    churn_top: Vec<RankDelta>,
+    churn_entered: Vec<String>,
+    churn_left: Vec<String>,
+});
+schema!(LineSpan {
+    start: usize,
+    end: usize,
+});
+schema!(AuthorShare {
+    author: String,
+    lines: usize,
+    share: f64,
+    median_age_days: i64,
+    last_change: LastChange,
+});
+schema!(LastChange {
+    commit: String,
+    date: DateTime<Local>,
+    summary: String,
+});
+schema!(AgeBucket {
+    label: String,
+    lines: usize,
+    share: f64,
+});
+schema!(PathAge {
+    path: String,
+    lines: usize,
+    median_days: i64,
+    p90_days: i64,
+    old_share: f64,
+});
+schema!(Survival {
+    cohort: String,
+    period: String,
+    start: NaiveDate,
+    written: usize,
+    alive: usize,
+    survival: f64,
+});
+schema!(Candidate {
+    author: String,
+    email: String,
+    share: f64,
+    lines: usize,
+    last_commit: Option<DateTime<Local>>,
+});
+schema!(OwnerRule {
+    pattern: String,
+    files: usize,
+    lines: usize,
+    owners: Vec<Candidate>,
+});
+schema!(RuleDiff {
+    pattern: String,
+    suggested: Vec<String>,
+    current: Vec<String>,
+    added: Vec<String>,
+    removed: Vec<String>,
+});
+schema!(OwnerFlag {
+    line: usize,
+    pattern: String,
+    owner: String,
+    issue: Issue,
+    last_commit: Option<DateTime<Local>>,
+});
+schema!(OwnersDiff {
+    rules: Vec<RuleDiff>,
+    flags: Vec<OwnerFlag>,
+});
+
+impl Schema for Issue {
+    fn schema() -> Value {
+        one_of(&["left", "unknown", "untouched", "no_files"])
+    }
+}
+
+schema!(Reviewer {
+    author: String,
+    score: f64,
+    lines: usize,
+    line_share: f64,
+    touches: f64,
+    touch_share: f64,
+    files: usize,
+    last_commit: Option<DateTime<Local>>,
+});
+
+schema!(Family {
+    name: String,
+    help: String,
+    kind: String,
+    samples: Vec<Sample>,
+});
+schema!(Sample {
+    labels: BTreeMap<String, String>,
+    value: f64,
+});
+
+/// Options of `cmd` as they serialise from its clap struct: one property
+/// per argument (by field name), plus `action` for its subcommands unless
+/// it is the top-level command (whose subcommand is the command itself).
+pub fn options(cmd: &Command, top_level: bool) -> Value {
+    let mut props = Vec::new();
+    for arg in cmd.get_arguments() {
+        let id = arg.get_id().as_str();
+        let value = match arg.get_action() {
+            ArgAction::Help | ArgAction::HelpShort | ArgAction::HelpLong | ArgAction::Version => {
+                continue
+            }
+            ArgAction::SetTrue | ArgAction::SetFalse => json!({ "type": "boolean" }),
+            ArgAction::Count => json!({ "type": "integer", "minimum": 0 }),
+            ArgAction::Append => json!({ "type": "array", "items": arg_value(arg) }),
+            _ if arg.is_required_set() || !arg.get_default_values().is_empty() => arg_value(arg),
+            _ => nullable(arg_value(arg)),
+        };
+        props.push((id, value));
+    }
+    let actions: Vec<&str> = cmd.get_subcommands().map(Command::get_name).collect();
+    if !top_level && !actions.is_empty() {
+        props.push(("action", nullable(one_of(&actions))));
+    }
+    object(&format!("{} options", cmd.get_name()), props)
+}
+
+fn arg_value(arg: &clap::Arg) -> Value {
+    let possible: Vec<_> = arg
+        .get_possible_values()
+        .iter()
+        .map(|v| v.get_name().to_string())
+        .collect();
+    if !possible.is_empty() {
+        return json!({ "type": "string", "enum": possible });
+    }
+    let ty = arg.get_value_parser().type_id();
+    if ty == TypeId::of::<usize>() || ty == TypeId::of::<u64>() || ty == TypeId::of::<u32>() {
+        usize::schema()
+    } else if ty == TypeId::of::<i64>() {
+        i64::schema()
+    } else if ty == TypeId::of::<f64>() {
+        f64::schema()
+    } else {
+        String::schema()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use clap::Arg;
+    use serde::Serialize;
+
+    #[derive(Serialize)]
+    struct Row {
+        name: String,
+        count: usize,
+        last: Option<NaiveDate>,
+        tags: Vec<String>,
+    }
+    schema!(Row {
+        name: String,
+        count: usize,
+        last: Option<NaiveDate>,
+        tags: Vec<String>,
+    });
+
+    #[test]
+    fn macro_lists_every_serialised_field() {
+        let schema = Row::schema();
+        assert_eq!(schema["title"], "Row");
+        assert_eq!(schema["additionalProperties"], false);
+        assert_eq!(schema["required"], json!(["name", "count", "last", "tags"]));
+        let props = &schema["properties"];
+        assert_eq!(props["count"], json!({ "type": "integer", "minimum": 0 }));
+        assert_eq!(props["last"]["anyOf"][1], json!({ "type": "null" }));
+        assert_eq!(props["tags"]["items"], json!({ "type": "string" }));
+
+        let row = Row {
+            name: "a".into(),
+            count: 1,
+            last: None,
+            tags: vec![],
+        };
+        let value = serde_json::to_value(row).unwrap();
+        let mut fields: Vec<_> = value.as_object().unwrap().keys().collect();
+        let mut listed: Vec<_> = props.as_object().unwrap().keys().collect();
+        fields.sort();
+        listed.sort();
+        assert_eq!(fields, listed);
+    }
+
+    #[test]
+    fn options_follow_the_clap_definitions() {
+        let cmd = Command::new("demo")
+            .arg(Arg::new("all").long("all").action(ArgAction::SetTrue))
+            .arg(
+                Arg::new("limit")
+                    .long("limit")
+                    .default_value("10")
+                    .value_parser(clap::value_parser!(usize)),
+            )
+            .arg(Arg::new("since").long("since"))
+            .arg(
+                Arg::new("by")
+                    .long("by")
+                    .value_parser(["file", "dir"])
+                    .default_value("dir"),
+            )
+            .arg(Arg::new("team").long("team").action(ArgAction::Append));
+        let schema = options(&cmd, false);
+        let props = &schema["properties"];
+        assert_eq!(props["all"], json!({ "type": "boolean" }));
+        assert_eq!(props["limit"], usize::schema());
+        assert_eq!(props["since"], nullable(String::schema()));
+        assert_eq!(props["by"], one_of(&["file", "dir"]));
+        assert_eq!(props["team"]["type"], "array");
+        assert!(props.get("help").is_none() && props.get("action").is_none());
+    }
+}
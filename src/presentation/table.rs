@@ -1,22 +1,10 @@
 use crate::domain::stats::{AuthorMeta, CommitStats};
-use crate::utils::fmt_date;
+use crate::utils::{fmt_credit, fmt_date};
 use comfy_table::{presets::UTF8_HORIZONTAL_ONLY, Table};
 
-fn sorted_entries<'a>(stats: &'a CommitStats, desc: bool) -> Vec<(&'a String, &'a AuthorMeta)> {
-    let mut entries: Vec<_> = stats.data.iter().collect();
-    entries.sort_by(|a, b| {
-        if desc {
-            b.1.count.cmp(&a.1.count)
-        } else {
-            a.1.count.cmp(&b.1.count)
-        }
-    });
-    entries
-}
-
 /// Only top N authors.
 pub fn author_stats_top(stats: &CommitStats, desc: bool, n: usize) -> String {
-    let entries = sorted_entries(stats, desc);
+    let entries = stats.sorted(desc);
     render(entries_to_rows(entries.into_iter().take(n).collect()))
 }
 
@@ -26,7 +14,7 @@ fn entries_to_rows(entries: Vec<(&String, &AuthorMeta)>) -> Vec<[String; 4]> {
         .map(|(email, m)| {
             [
                 email.clone(),
-                m.count.to_string(),
+                fmt_credit(m.count),
                 fmt_date(m.first),
                 fmt_date(m.last),
             ]
@@ -34,6 +22,24 @@ fn entries_to_rows(entries: Vec<(&String, &AuthorMeta)>) -> Vec<[String; 4]> {
         .collect()
 }
 
+/// Bots reported apart from the authors (`--bots separate`).
+pub fn bot_stats(stats: &CommitStats) -> String {
+    let mut bots: Vec<_> = stats.bots.iter().collect();
+    bots.sort_by_key(|(_, m)| std::cmp::Reverse(m.commits));
+    let mut t = Table::new();
+    t.load_preset(UTF8_HORIZONTAL_ONLY)
+        .set_header(vec!["Bot", "Commits", "First", "Last"]);
+    for (name, m) in bots {
+        t.add_row(vec![
+            name.clone(),
+            m.commits.to_string(),
+            fmt_date(m.first),
+            fmt_date(m.last),
+        ]);
+    }
+    t.to_string()
+}
+
 fn render(rows: Vec<[String; 4]>) -> String {
     let mut t = Table::new();
     t.load_preset(UTF8_HORIZONTAL_ONLY)
@@ -0,0 +1,664 @@
+//! Interactive terminal explorer: repo summary, authors, and a file tree
+//! with ownership and churn that drills down to a file's blame.
+
+use anyhow::Result;
+use chrono::NaiveDate;
+use ratatui::crossterm::event::{self, Event, KeyCode, KeyEvent, KeyEventKind, KeyModifiers};
+use ratatui::layout::{Constraint, Layout, Rect};
+use ratatui::style::{Modifier, Style, Stylize};
+use ratatui::text::Line;
+use ratatui::widgets::{Block, Cell, Paragraph, Row, Table, TableState, Tabs};
+use ratatui::{DefaultTerminal, Frame};
+use std::cmp::Ordering;
+use std::path::Path;
+
+use crate::domain::{blame, explore::Explore, git::RepoExt};
+use crate::utils::{fmt_credit, fmt_date};
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum Tab {
+    Summary,
+    Authors,
+    Files,
+}
+
+const TABS: [(Tab, &str); 3] = [
+    (Tab::Summary, "1 Summary"),
+    (Tab::Authors, "2 Authors"),
+    (Tab::Files, "3 Files"),
+];
+
+/// A table cell: shown as text, sorted by value.
+#[derive(Debug, Clone)]
+enum Val {
+    Text(String),
+    Count(usize),
+    Credit(f64),
+    Float(f64),
+    Pct(f64),
+    Date(NaiveDate),
+}
+
+impl Val {
+    fn show(&self) -> String {
+        match self {
+            Val::Text(s) => s.clone(),
+            Val::Count(n) => n.to_string(),
+            Val::Credit(x) => fmt_credit(*x),
+            Val::Float(x) => format!("{x:.1}"),
+            Val::Pct(x) => format!("{:.1}%", x * 100.0),
+            Val::Date(d) => d.to_string(),
+        }
+    }
+
+    fn cmp(&self, other: &Val) -> Ordering {
+        match (self, other) {
+            (Val::Text(a), Val::Text(b)) => a.to_lowercase().cmp(&b.to_lowercase()),
+            (Val::Count(a), Val::Count(b)) => a.cmp(b),
+            (Val::Credit(a), Val::Credit(b))
+            | (Val::Float(a), Val::Float(b))
+            | (Val::Pct(a), Val::Pct(b)) => a.total_cmp(b),
+            (Val::Date(a), Val::Date(b)) => a.cmp(b),
+            _ => Ordering::Equal,
+        }
+    }
+}
+
+/// What Enter does on a row.
+#[derive(Debug, Clone)]
+enum Target {
+    None,
+    Dir(String),
+    File(String),
+}
+
+#[derive(Debug, Clone)]
+struct Item {
+    cells: Vec<Val>,
+    target: Target,
+}
+
+/// Sort column and direction of one table.
+#[derive(Debug, Clone, Copy)]
+struct Sort {
+    column: usize,
+    desc: bool,
+}
+
+/// Where the Files tab is: a directory, or one file's blame.
+#[derive(Debug, Clone)]
+enum Place {
+    Dir(String),
+    Blame {
+        file: String,
+        authors: Vec<(String, usize)>,
+    },
+}
+
+pub struct App<'a> {
+    repo: &'a RepoExt,
+    data: Explore,
+    name: String,
+    tab: Tab,
+    place: Place,
+    /// Selected row per directory visited, restored when going back up.
+    trail: Vec<usize>,
+    authors_sort: Sort,
+    files_sort: Sort,
+    blame_sort: Sort,
+    filter: String,
+    typing: bool,
+    state: TableState,
+    status: String,
+}
+
+impl<'a> App<'a> {
+    pub fn new(repo: &'a RepoExt, data: Explore, name: String) -> Self {
+        let mut state = TableState::default();
+        state.select(Some(0));
+        Self {
+            repo,
+            data,
+            name,
+            tab: Tab::Files,
+            place: Place::Dir(String::new()),
+            trail: Vec::new(),
+            authors_sort: Sort {
+                column: 1,
+                desc: true,
+            },
+            files_sort: Sort {
+                column: 0,
+                desc: false,
+            },
+            blame_sort: Sort {
+                column: 1,
+                desc: true,
+            },
+            filter: String::new(),
+            typing: false,
+            state,
+            status: String::new(),
+        }
+    }
+
+    /// Draw and handle keys until the user quits.
+    pub fn run(mut self, terminal: &mut DefaultTerminal) -> Result<()> {
+        loop {
+            terminal.draw(|f| self.draw(f))?;
+            if let Event::Key(key) = event::read()? {
+                if key.kind == KeyEventKind::Press && !self.on_key(key) {
+                    return Ok(());
+                }
+            }
+        }
+    }
+
+    /// Column headers and the rows of the current table, filtered and
+    /// sorted.
+    fn table(&self) -> (Vec<&'static str>, Vec<Item>) {
+        let (header, mut items, sort) = match (&self.tab, &self.place) {
+            (Tab::Authors, _) => (
+                vec!["Author", "Commits", "Share", "First", "Last"],
+                self.author_items(),
+                self.authors_sort,
+            ),
+            (_, Place::Dir(dir)) => (
+                vec!["Name", "Owner", "Ownership", "Touches", "Churn"],
+                self.dir_items(dir),
+                self.files_sort,
+            ),
+            (_, Place::Blame { authors, .. }) => (
+                vec!["Author", "Lines", "Share"],
+                blame_items(authors),
+                self.blame_sort,
+            ),
+        };
+        let needle = self.filter.to_lowercase();
+        if !needle.is_empty() {
+            items.retain(|i| i.cells[0].show().to_lowercase().contains(&needle));
+        }
+        items.sort_by(|a, b| {
+            // Directories stay above files when sorting by name.
+            let dirs_first = match (&a.target, &b.target) {
+                (Target::Dir(_), Target::File(_)) if sort.column == 0 => Ordering::Less,
+                (Target::File(_), Target::Dir(_)) if sort.column == 0 => Ordering::Greater,
+                _ => Ordering::Equal,
+            };
+            let by = a.cells[sort.column].cmp(&b.cells[sort.column]);
+            dirs_first.then(if sort.desc { by.reverse() } else { by })
+        });
+        (header, items)
+    }
+
+    fn author_items(&self) -> Vec<Item> {
+        let stats = &self.data.scan.stats;
+        let total: f64 = stats.data.values().map(|m| m.count).sum();
+        stats
+            .sorted(true)
+            .into_iter()
+            .map(|(author, m)| Item {
+                cells: vec![
+                    Val::Text(author.clone()),
+                    Val::Credit(m.count),
+                    Val::Pct(if total > 0.0 { m.count / total } else { 0.0 }),
+                    Val::Date(m.first.date_naive()),
+                    Val::Date(m.last.date_naive()),
+                ],
+                target: Target::None,
+            })
+            .collect()
+    }
+
+    fn dir_items(&self, dir: &str) -> Vec<Item> {
+        self.data
+            .list(dir)
+            .into_iter()
+            .map(|e| {
+                let (owner, share) = e
+                    .stats
+                    .owner()
+                    .map_or((String::new(), 0.0), |(a, s)| (a.to_string(), s));
+                let (name, target) = if e.is_dir {
+                    (format!("{}/", e.name), Target::Dir(e.path))
+                } else {
+                    (e.name, Target::File(e.path))
+                };
+                Item {
+                    cells: vec![
+                        Val::Text(name),
+                        Val::Text(owner),
+                        Val::Pct(share),
+                        Val::Count(e.stats.touches),
+                        Val::Float(e.stats.churn),
+                    ],
+                    target,
+                }
+            })
+            .collect()
+    }
+
+    fn sort_mut(&mut self) -> &mut Sort {
+        match (&self.tab, &self.place) {
+            (Tab::Authors, _) => &mut self.authors_sort,
+            (_, Place::Dir(_)) => &mut self.files_sort,
+            (_, Place::Blame { .. }) => &mut self.blame_sort,
+        }
+    }
+
+    /// Handle one key press; false to quit.
+    fn on_key(&mut self, key: KeyEvent) -> bool {
+        if key.modifiers.contains(KeyModifiers::CONTROL) && key.code == KeyCode::Char('c') {
+            return false;
+        }
+        if self.typing {
+            match key.code {
+                KeyCode::Esc => {
+                    self.filter.clear();
+                    self.typing = false;
+                }
+                KeyCode::Enter => self.typing = false,
+                KeyCode::Backspace => {
+                    self.filter.pop();
+                }
+                KeyCode::Char(c) => self.filter.push(c),
+                _ => return true,
+            }
+            self.state.select(Some(0));
+            return true;
+        }
+
+        let rows = self.table().1.len();
+        let selected = self.state.selected().unwrap_or(0);
+        let page = 10;
+        match key.code {
+            KeyCode::Char('q') => return false,
+            KeyCode::Char('1') => self.switch(Tab::Summary),
+            KeyCode::Char('2') => self.switch(Tab::Authors),
+            KeyCode::Char('3') => self.switch(Tab::Files),
+            KeyCode::Tab => {
+                let next = match self.tab {
+                    Tab::Summary => Tab::Authors,
+                    Tab::Authors => Tab::Files,
+                    Tab::Files => Tab::Summary,
+                };
+                self.switch(next);
+            }
+            KeyCode::Down | KeyCode::Char('j') => self.select(selected + 1, rows),
+            KeyCode::Up | KeyCode::Char('k') => self.select(selected.saturating_sub(1), rows),
+            KeyCode::PageDown => self.select(selected + page, rows),
+            KeyCode::PageUp => self.select(selected.saturating_sub(page), rows),
+            KeyCode::Home | KeyCode::Char('g') => self.select(0, rows),
+            KeyCode::End | KeyCode::Char('G') => self.select(rows.saturating_sub(1), rows),
+            KeyCode::Char('/') if self.tab != Tab::Summary => self.typing = true,
+            KeyCode::Esc => {
+                self.filter.clear();
+                self.state.select(Some(0));
+            }
+            KeyCode::Char('s') | KeyCode::Char('r') if self.tab != Tab::Summary => {
+                let columns = self.table().0.len();
+                let reverse = key.code == KeyCode::Char('r');
+                let sort = self.sort_mut();
+                if reverse {
+                    sort.desc = !sort.desc;
+                } else {
+                    sort.column = (sort.column + 1) % columns;
+                    // Numbers read best largest first, names A→Z.
+                    sort.desc = sort.column != 0;
+                }
+            }
+            KeyCode::Enter | KeyCode::Right | KeyCode::Char('l') if self.tab == Tab::Files => {
+                self.open(selected);
+            }
+            KeyCode::Backspace | KeyCode::Left | KeyCode::Char('h') if self.tab == Tab::Files => {
+                self.up();
+            }
+            _ => {}
+        }
+        true
+    }
+
+    fn switch(&mut self, tab: Tab) {
+        if self.tab != tab {
+            self.tab = tab;
+            self.filter.clear();
+            self.state.select(Some(0));
+        }
+    }
+
+    fn select(&mut self, row: usize, rows: usize) {
+        self.state.select(Some(row.min(rows.saturating_sub(1))));
+    }
+
+    /// Descend into the selected directory or blame the selected file.
+    fn open(&mut self, selected: usize) {
+        let Some(item) = self.table().1.into_iter().nth(selected) else {
+            return;
+        };
+        let place = match item.target {
+            Target::None => return,
+            Target::Dir(dir) => Place::Dir(dir),
+            Target::File(file) => match blame::blame_counts(self.repo, Path::new(&file)) {
+                Ok(counts) => Place::Blame {
+                    file,
+                    authors: counts.into_iter().collect(),
+                },
+                Err(e) => {
+                    self.status = format!("blame failed for {file}: {e}");
+                    return;
+                }
+            },
+        };
+        self.status.clear();
+        self.trail.push(selected);
+        self.place = place;
+        self.filter.clear();
+        self.state.select(Some(0));
+    }
+
+    fn up(&mut self) {
+        let parent = match &self.place {
+            Place::Dir(dir) if dir.is_empty() => return,
+            Place::Dir(dir) => dir.rsplit_once('/').map_or("", |(p, _)| p).to_string(),
+            Place::Blame { file, .. } => file.rsplit_once('/').map_or("", |(p, _)| p).to_string(),
+        };
+        self.place = Place::Dir(parent);
+        self.filter.clear();
+        self.status.clear();
+        self.state.select(Some(self.trail.pop().unwrap_or(0)));
+    }
+
+    fn draw(&mut self, f: &mut Frame) {
+        let [top, main, bottom] = Layout::vertical([
+            Constraint::Length(1),
+            Constraint::Min(0),
+            Constraint::Length(1),
+        ])
+        .areas(f.area());
+
+        let selected = TABS.iter().position(|(t, _)| *t == self.tab).unwrap_or(0);
+        let [tabs_area, title_area] =
+            Layout::horizontal([Constraint::Length(36), Constraint::Min(0)]).areas(top);
+        f.render_widget(
+            Tabs::new(TABS.map(|(_, name)| name))
+                .select(selected)
+                .highlight_style(Style::new().bold().reversed()),
+            tabs_area,
+        );
+        f.render_widget(
+            Line::from(format!("{} · {}", self.name, self.repo.window())).right_aligned(),
+            title_area,
+        );
+
+        match self.tab {
+            Tab::Summary => self.draw_summary(f, main),
+            _ => self.draw_table(f, main),
+        }
+
+        let help = if self.typing {
+            format!("filter: {}▏  (Enter keep · Esc clear)", self.filter)
+        } else if !self.status.is_empty() {
+            self.status.clone()
+        } else {
+            let mut keys = "q quit · Tab/1-3 panes · ↑↓ move".to_string();
+            if self.tab != Tab::Summary {
+                keys.push_str(" · s sort · r reverse · / filter");
+            }
+            if self.tab == Tab::Files {
+                keys.push_str(" · Enter open · ← up");
+            }
+            if !self.filter.is_empty() {
+                keys = format!("filter “{}” · Esc clear · {keys}", self.filter);
+            }
+            keys
+        };
+        f.render_widget(Line::from(help).dim(), bottom);
+    }
+
+    fn draw_summary(&self, f: &mut Frame, area: Rect) {
+        let s = &self.data.scan.summary;
+        let pct = |x: f64| format!("{x:.1}%");
+        let mut lines = vec![
+            ("Commits", s.total_commits.to_string()),
+            ("Contributors", s.contributors_total.to_string()),
+            (
+                "First commit",
+                format!("{} by {}", fmt_date(s.first_date), s.first_author),
+            ),
+            (
+                "Last commit",
+                format!("{} by {}", fmt_date(s.last_date), s.last_author),
+            ),
+            ("Active days", s.active_days.to_string()),
+            (
+                "Active authors (90d)",
+                s.active_authors_last_90d.to_string(),
+            ),
+            ("Core size (80%)", s.core_size_80pct.to_string()),
+            ("Drive-by authors", pct(s.drive_by_ratio)),
+            ("Concentration (HHI)", format!("{:.3}", s.hhi)),
+            ("Gini", format!("{:.3}", s.gini)),
+            ("Merge rate", pct(s.merge_rate)),
+            ("Revert rate", pct(s.revert_rate)),
+            ("Momentum (90d)", pct(s.momentum_90d_pct)),
+            ("Work hours", pct(s.work_hours_pct)),
+            (
+                "Longest idle gap",
+                format!("{} days", s.longest_idle_gap_days),
+            ),
+        ];
+        if let Some(top) = &s.top_recent_30d {
+            lines.push((
+                "Top author (30d)",
+                format!("{} ({} commits)", top.author, fmt_credit(top.commits)),
+            ));
+        }
+        lines.push(("Files", self.data.files.len().to_string()));
+        lines.push(("Churn window", self.data.churn_window.to_string()));
+        let text: Vec<Line> = lines
+            .into_iter()
+            .map(|(k, v)| Line::from(vec![format!("{k:<22}").bold(), v.into()]))
+            .collect();
+        f.render_widget(
+            Paragraph::new(text).block(Block::bordered().title(" Repository ")),
+            area,
+        );
+    }
+
+    fn draw_table(&mut self, f: &mut Frame, area: Rect) {
+        let (header, items) = self.table();
+        let sort = *self.sort_mut();
+        let title = match (&self.tab, &self.place) {
+            (Tab::Authors, _) => format!(" Authors ({}) ", items.len()),
+            (_, Place::Dir(dir)) if dir.is_empty() => " / ".to_string(),
+            (_, Place::Dir(dir)) => format!(" /{dir}/ "),
+            (_, Place::Blame { file, .. }) => format!(" blame /{file} "),
+        };
+        let header = Row::new(header.iter().enumerate().map(|(i, h)| {
+            let arrow = match (i == sort.column, sort.desc) {
+                (false, _) => "",
+                (true, true) => " ▼",
+                (true, false) => " ▲",
+            };
+            Cell::from(format!("{h}{arrow}"))
+        }))
+        .style(Style::new().add_modifier(Modifier::BOLD));
+        let widths: Vec<Constraint> = (0..header_len(&self.tab, &self.place))
+            .map(|i| match i {
+                0 => Constraint::Fill(3),
+                1 if self.tab == Tab::Files && matches!(self.place, Place::Dir(_)) => {
+                    Constraint::Fill(2)
+                }
+                _ => Constraint::Length(12),
+            })
+            .collect();
+        let rows = items.iter().map(|i| {
+            let row = Row::new(i.cells.iter().map(|c| Cell::from(c.show())));
+            if matches!(i.target, Target::Dir(_)) {
+                row.bold()
+            } else {
+                row
+            }
+        });
+        let table = Table::new(rows, widths)
+            .header(header)
+            .block(Block::bordered().title(title))
+            .row_highlight_style(Style::new().reversed());
+        f.render_stateful_widget(table, area, &mut self.state);
+    }
+}
+
+fn header_len(tab: &Tab, place: &Place) -> usize {
+    match (tab, place) {
+        (Tab::Authors, _) => 5,
+        (_, Place::Dir(_)) => 5,
+        (_, Place::Blame { .. }) => 3,
+    }
+}
+
+fn blame_items(authors: &[(String, usize)]) -> Vec<Item> {
+    let total: usize = authors.iter().map(|(_, n)| n).sum();
+    authors
+        .iter()
+        .map(|(author, lines)| Item {
+            cells: vec![
+                Val::Text(author.clone()),
+                Val::Count(*lines),
+                Val::Pct(if total > 0 {
+                    *lines as f64 / total as f64
+                } else {
+                    0.0
+                }),
+            ],
+            target: Target::None,
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::domain::{bus_factor::ScanOpts, explore, git::RepoOptions};
+    use git2::{Repository, Signature, Time};
+
+    /// Commit `files` on HEAD as `name`, `day` days into the history.
+    fn commit(git: &Repository, name: &str, day: i64, files: &[(&str, &str)]) {
+        let dir = git.workdir().unwrap();
+        let mut index = git.index().unwrap();
+        for (path, text) in files {
+            std::fs::create_dir_all(dir.join(path).parent().unwrap()).unwrap();
+            std::fs::write(dir.join(path), text).unwrap();
+            index.add_path(Path::new(path)).unwrap();
+        }
+        index.write().unwrap();
+        let tree = git.find_tree(index.write_tree().unwrap()).unwrap();
+        let email = format!("{}@x.io", name.to_lowercase());
+        let sig =
+            Signature::new(name, &email, &Time::new(1_700_000_000 + day * 86_400, 0)).unwrap();
+        let parent = git.head().ok().map(|h| h.peel_to_commit().unwrap());
+        let parents: Vec<_> = parent.iter().collect();
+        git.commit(Some("HEAD"), &sig, &sig, "change", &tree, &parents)
+            .unwrap();
+    }
+
+    /// `src/` (three touches, mostly Ben), `zeta.rs` (two) and
+    /// `README.md` (none: root commits count no touches).
+    fn repo() -> (tempfile::TempDir, RepoExt) {
+        let dir = tempfile::TempDir::new().unwrap();
+        let git = Repository::init(dir.path()).unwrap();
+        commit(&git, "Ann", 1, &[("README.md", "hi\n")]);
+        commit(&git, "Ann", 2, &[("src/a.rs", "1\n"), ("zeta.rs", "1\n")]);
+        commit(&git, "Ben", 3, &[("src/sub/b.rs", "1\n2\n")]);
+        commit(
+            &git,
+            "Ben",
+            4,
+            &[("src/a.rs", "1\n2\n3\n"), ("zeta.rs", "2\n")],
+        );
+        let repo = RepoOptions::default()
+            .no_cache(true)
+            .open(dir.path().to_str().unwrap())
+            .unwrap();
+        (dir, repo)
+    }
+
+    fn app(repo: &RepoExt) -> App<'_> {
+        let opts = ScanOpts::default().all(true).min_total(1);
+        let data = explore::collect(repo, 100_000, &opts).unwrap();
+        App::new(repo, data, "test".into())
+    }
+
+    fn keys(app: &mut App, codes: &[KeyCode]) {
+        for code in codes {
+            assert!(app.on_key(KeyEvent::from(*code)));
+        }
+    }
+
+    fn column(app: &App, i: usize) -> Vec<String> {
+        app.table()
+            .1
+            .iter()
+            .map(|item| item.cells[i].show())
+            .collect()
+    }
+
+    #[test]
+    fn directories_show_aggregated_owner_and_touches() {
+        let (_dir, repo) = repo();
+        let app = app(&repo);
+        assert_eq!(column(&app, 0), ["src/", "README.md", "zeta.rs"]);
+        assert_eq!(column(&app, 1)[0], "Ben <ben@x.io>");
+        assert_eq!(column(&app, 2)[0], "66.7%");
+        assert_eq!(column(&app, 3), ["3", "0", "2"]);
+    }
+
+    #[test]
+    fn s_cycles_the_sort_column_and_r_reverses_it() {
+        let (_dir, repo) = repo();
+        let mut app = app(&repo);
+        keys(&mut app, &[KeyCode::Char('s'); 3]);
+        assert_eq!(column(&app, 0), ["src/", "zeta.rs", "README.md"]);
+        keys(&mut app, &[KeyCode::Char('r')]);
+        assert_eq!(column(&app, 0), ["README.md", "zeta.rs", "src/"]);
+        // Back to names: A→Z with directories first.
+        keys(&mut app, &[KeyCode::Char('s'), KeyCode::Char('s')]);
+        assert_eq!(column(&app, 0), ["src/", "README.md", "zeta.rs"]);
+    }
+
+    #[test]
+    fn slash_filters_by_name_until_esc() {
+        let (_dir, repo) = repo();
+        let mut app = app(&repo);
+        let typed = [KeyCode::Char('/'), KeyCode::Char('Z'), KeyCode::Char('e')];
+        keys(&mut app, &typed);
+        assert!(app.typing);
+        keys(&mut app, &[KeyCode::Enter]);
+        assert!(!app.typing);
+        assert_eq!(column(&app, 0), ["zeta.rs"]);
+        keys(&mut app, &[KeyCode::Esc]);
+        assert_eq!(column(&app, 0).len(), 3);
+    }
+
+    #[test]
+    fn enter_drills_down_to_blame_and_back_up() {
+        let (_dir, repo) = repo();
+        let mut app = app(&repo);
+        keys(&mut app, &[KeyCode::Enter]);
+        assert!(matches!(&app.place, Place::Dir(d) if d == "src"));
+        assert_eq!(column(&app, 0), ["sub/", "a.rs"]);
+
+        keys(&mut app, &[KeyCode::Down, KeyCode::Enter]);
+        assert!(matches!(&app.place, Place::Blame { file, .. } if file == "src/a.rs"));
+        assert_eq!(app.table().0, ["Author", "Lines", "Share"]);
+        assert_eq!(column(&app, 0), ["Ben <ben@x.io>", "Ann <ann@x.io>"]);
+        assert_eq!(column(&app, 1), ["2", "1"]);
+
+        keys(&mut app, &[KeyCode::Backspace]);
+        assert!(matches!(&app.place, Place::Dir(d) if d == "src"));
+        assert_eq!(app.state.selected(), Some(1), "back on a.rs");
+        keys(&mut app, &[KeyCode::Left, KeyCode::Left]);
+        assert!(matches!(&app.place, Place::Dir(d) if d.is_empty()));
+        assert_eq!(app.state.selected(), Some(0));
+        assert!(!app.on_key(KeyEvent::from(KeyCode::Char('q'))));
+    }
+}
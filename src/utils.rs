@@ -3,3 +3,13 @@ use chrono::{DateTime, Local};
 pub fn fmt_date(dt: DateTime<Local>) -> String {
     dt.format("%Y-%m-%d").to_string()
 }
+
+/// Commit credit for display: whole numbers as-is, fractions (from split
+/// co-author credit) with one decimal.
+pub fn fmt_credit(n: f64) -> String {
+    if n.fract() == 0.0 {
+        format!("{n:.0}")
+    } else {
+        format!("{n:.1}")
+    }
+}